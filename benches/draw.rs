@@ -0,0 +1,92 @@
+//! Compares drawing a multi-paragraph text box with measuring every line on every draw and with
+//! reusing the line measurements stored in a `MeasurementCache`.
+//!
+//! Run with `cargo bench --bench draw`.
+
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::Rectangle,
+    Pixel,
+};
+use embedded_text::{
+    alignment::HorizontalAlignment, cache::MeasurementCache, style::TextBoxStyleBuilder, TextBox,
+};
+
+const PARAGRAPH: &str = "Lorem Ipsum is simply dummy text of the printing and typesetting \
+    industry. Lorem Ipsum has been the industry's standard dummy text ever since the 1500s, when \
+    an unknown printer took a galley of type and scrambled it to make a type specimen book.\n";
+
+const ITERATIONS: u32 = 2000;
+
+/// Display that counts the drawn pixels instead of storing them.
+struct CountingDisplay {
+    pixels: u64,
+}
+
+impl OriginDimensions for CountingDisplay {
+    fn size(&self) -> Size {
+        Size::new(240, 320)
+    }
+}
+
+impl DrawTarget for CountingDisplay {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.pixels += pixels.into_iter().count() as u64;
+        Ok(())
+    }
+}
+
+/// Returns the average time of drawing the text box using `draw`.
+fn time(mut draw: impl FnMut(&mut CountingDisplay)) -> Duration {
+    let mut display = CountingDisplay { pixels: 0 };
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        draw(&mut display);
+    }
+    let elapsed = start.elapsed();
+
+    // Keep the drawing from being optimized away.
+    assert!(display.pixels > 0);
+
+    elapsed / ITERATIONS
+}
+
+fn main() {
+    let text = PARAGRAPH.repeat(4);
+    let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+    let style = TextBoxStyleBuilder::new()
+        .alignment(HorizontalAlignment::Justified)
+        .build();
+    let bounds = Rectangle::new(Point::zero(), Size::new(240, 320));
+    let text_box = TextBox::with_textbox_style(&text, bounds, character_style, style);
+
+    let uncached = time(|display| {
+        text_box.draw(display).unwrap();
+    });
+
+    let cache = MeasurementCache::<32>::new();
+    let cached = time(|display| {
+        text_box.draw_cached(display, &cache).unwrap();
+    });
+
+    println!("draw:        {:>10?} per draw", uncached);
+    println!("draw_cached: {:>10?} per draw", cached);
+    println!(
+        "draw_cached takes {:.0}% of the time of draw",
+        cached.as_secs_f64() / uncached.as_secs_f64() * 100.0
+    );
+}
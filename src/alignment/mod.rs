@@ -1,16 +1,14 @@
 //! Text alignment options.
-use crate::{
-    rendering::{cursor::Cursor, space_config::SpaceConfig},
-    style::LineMeasurement,
-    utils::str_width,
-};
-use embedded_graphics::text::renderer::TextRenderer;
+use crate::{rendering::cursor::Cursor, style::LineMeasurement};
+
+pub use crate::rendering::space_config::SpaceConfig;
 
 #[cfg(test)]
 mod test;
 
 /// Horizontal text alignment options.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HorizontalAlignment {
     /// Left aligned.
     Left,
@@ -26,26 +24,74 @@ pub enum HorizontalAlignment {
 }
 
 impl HorizontalAlignment {
-    /// Calculate offset from the left side and whitespace information.
-    pub(crate) fn place_line(
+    /// Calculates the horizontal placement of a line.
+    ///
+    /// `space_width` is the width of a space character, including the word spacing, and
+    /// `measurement` is the measurement of the line, made with spaces of that width. The other
+    /// parameters are the justification settings of [`TextBoxStyle`].
+    ///
+    /// Returns the offset of the line from the left side of the text area, in pixels, and the
+    /// widths of the spaces in the line. The line is rendered starting at the offset and every
+    /// space advances the cursor by the width returned by [`SpaceConfig::consume`]. Printed
+    /// characters are followed by the extra space returned by
+    /// [`SpaceConfig::consume_character_gap`]. To keep the line inside the text area, the offset
+    /// plus the width of the line, with the widths of its spaces replaced by the ones of the space
+    /// configuration and the extra space after its characters added, must not exceed
+    /// [`LineMeasurement::max_line_width`].
+    ///
+    /// Custom alignments can be implemented by plugins that override the `place_line` method of
+    /// the `Plugin` trait, which requires the `plugin` feature.
+    ///
+    /// [`TextBoxStyle`]: crate::style::TextBoxStyle
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::alignment::HorizontalAlignment;
+    /// # use embedded_text::style::LineMeasurement;
+    /// # fn place(measurement: LineMeasurement) {
+    ///
+    /// let (offset, mut spaces) =
+    ///     HorizontalAlignment::Justified.place_line(6, measurement, false, None, false);
+    /// // The width of the first space of the line.
+    /// let first_space = spaces.consume(1);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn place_line(
         self,
-        renderer: &impl TextRenderer,
+        space_width: u32,
         measurement: LineMeasurement,
+        justify_last_line: bool,
+        justify_max_space_width: Option<u32>,
+        justify_characters: bool,
     ) -> (i32, SpaceConfig) {
-        let space_width = str_width(renderer, " ");
         let space_config = SpaceConfig::new(space_width, None);
-        let remaining_space = measurement.max_line_width - measurement.width;
+        // Lines that overflow the bounding box are aligned to the left.
+        let remaining_space = measurement.max_line_width.saturating_sub(measurement.width);
         match self {
             HorizontalAlignment::Left => (0, space_config),
             HorizontalAlignment::Center => ((remaining_space as i32 + 1) / 2, space_config),
             HorizontalAlignment::Right => (remaining_space as i32, space_config),
             HorizontalAlignment::Justified => {
-                let space_count = measurement.space_count;
-                let space_info = if !measurement.last_line() && space_count != 0 {
-                    let space = remaining_space + space_count * space_width;
-                    let space_width = space / space_count;
+                // Trailing spaces keep their width, only the spaces between words are stretched.
+                let space_count = measurement.space_count - measurement.trailing_space_count;
+                let stretch = justify_last_line || !measurement.last_line();
+                let space = remaining_space.saturating_add(space_count.saturating_mul(space_width));
+                // Lines that would need too wide spaces are left aligned.
+                let too_wide = matches!(
+                    justify_max_space_width,
+                    Some(max_width) if space > max_width.saturating_mul(space_count)
+                );
+                let space_info = if stretch && space_count != 0 && !too_wide {
+                    let stretched_width = space / space_count;
                     let extra_pixels = space % space_count;
-                    SpaceConfig::new(space_width, Some(extra_pixels))
+                    SpaceConfig::new(stretched_width, Some(extra_pixels))
+                        .limit(space_count, space_width)
+                } else if stretch && justify_characters {
+                    // Lines without usable spaces are stretched between their characters.
+                    let gaps = measurement.character_count.saturating_sub(1);
+                    space_config.with_character_spacing(gaps, remaining_space)
                 } else {
                     space_config
                 };
@@ -70,6 +116,7 @@ impl HorizontalAlignment {
 
 /// Vertical text alignment options.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerticalAlignment {
     /// Top aligned text.
     ///
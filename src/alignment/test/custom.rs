@@ -0,0 +1,58 @@
+use embedded_graphics::{
+    geometry::Point,
+    mock_display::MockDisplay,
+    mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    primitives::Rectangle,
+    Drawable,
+};
+
+use crate::{
+    alignment::SpaceConfig, plugin::private::Plugin, style::LineMeasurement, utils::test::size_for,
+    TextBox,
+};
+
+/// Centers lines with double width spaces.
+#[derive(Clone)]
+struct WideCenter;
+
+impl<'a> Plugin<'a, BinaryColor> for WideCenter {
+    fn place_line(
+        &mut self,
+        metrics: &LineMeasurement,
+        _offset: i32,
+        _space_config: SpaceConfig,
+    ) -> (i32, SpaceConfig) {
+        // The line is measured using the 6 pixel wide spaces of the font.
+        let space_width = 12;
+        let spaces = metrics.space_count() - metrics.trailing_space_count();
+        let width = metrics.width() + spaces * (space_width - 6);
+        let offset = metrics.max_line_width().saturating_sub(width) / 2;
+
+        (offset as i32, SpaceConfig::new(space_width, None))
+    }
+}
+
+#[test]
+fn plugin_places_line() {
+    let mut display = MockDisplay::new();
+
+    TextBox::new(
+        "a b",
+        Rectangle::new(Point::zero(), size_for(&FONT_6X9, 7, 1)),
+        MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+    )
+    .add_plugin(WideCenter)
+    .draw(&mut display)
+    .unwrap();
+
+    display.assert_pattern(&[
+        "                                ",
+        "                            #   ",
+        "                            #   ",
+        "           ###              ### ",
+        "          #  #              #  #",
+        "          #  #              #  #",
+        "           ###              ### ",
+    ]);
+}
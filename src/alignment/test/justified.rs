@@ -112,6 +112,131 @@ fn simple_word_wrapping() {
     );
 }
 
+#[test]
+fn last_line_is_not_stretched_by_default() {
+    assert_rendered(
+        HorizontalAlignment::Justified,
+        "a b\nc d",
+        size_for(&FONT_6X9, 5, 2),
+        &[
+            "..................",
+            ".............#....",
+            ".............#....",
+            "..###........###..",
+            ".#..#........#..#.",
+            ".#..#........#..#.",
+            "..###........###..",
+            "..................",
+            "..................",
+            "..................",
+            "................#.",
+            "................#.",
+            "..###.........###.",
+            ".#...........#..#.",
+            ".#...........#..#.",
+            "..###.........###.",
+            "..................",
+            "..................",
+        ],
+    );
+}
+
+#[test]
+fn justify_last_line() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .justify_last_line(true)
+            .build(),
+        "a b\nc d",
+        size_for(&FONT_6X9, 5, 2),
+        &[
+            "..............................",
+            ".........................#....",
+            ".........................#....",
+            "..###....................###..",
+            ".#..#....................#..#.",
+            ".#..#....................#..#.",
+            "..###....................###..",
+            "..............................",
+            "..............................",
+            "..............................",
+            "............................#.",
+            "............................#.",
+            "..###.....................###.",
+            ".#.......................#..#.",
+            ".#.......................#..#.",
+            "..###.....................###.",
+            "..............................",
+            "..............................",
+        ],
+    );
+}
+
+#[test]
+fn justify_max_space_width() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .justify_max_space_width(12)
+            .build(),
+        "ab cd efg hi jk",
+        size_for(&FONT_6X9, 7, 2),
+        &[
+            "..............................            ",
+            ".......#....................#.            ",
+            ".......#....................#.            ",
+            "..###..###..........###...###.            ",
+            ".#..#..#..#........#.....#..#.            ",
+            ".#..#..#..#........#.....#..#.            ",
+            "..###..###..........###...###.            ",
+            "..............................            ",
+            "..............................            ",
+            "..........................................",
+            ".........#.....................#......#...",
+            "........#.#....................#..........",
+            "..##....#.....##...............###...##...",
+            ".#.##..###...#..#..............#..#...#...",
+            ".##.....#....#..#..............#..#...#...",
+            "..###...#.....###..............#..#..###..",
+            "................#.........................",
+            "..............##..........................",
+        ],
+    );
+}
+
+#[test]
+fn justify_max_space_width_does_not_overflow() {
+    let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 7, 2));
+    let text = "ab cd efg hi jk";
+
+    let mut expected = MockDisplay::new();
+    TextBox::with_alignment(
+        text,
+        bounds,
+        character_style,
+        HorizontalAlignment::Justified,
+    )
+    .draw(&mut expected)
+    .unwrap();
+
+    let mut display = MockDisplay::new();
+    TextBox::with_textbox_style(
+        text,
+        bounds,
+        character_style,
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .justify_max_space_width(u32::MAX)
+            .build(),
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    assert_eq!(display, expected);
+}
+
 #[test]
 fn simple_word_wrapping_with_spaces() {
     assert_styled_rendered(
@@ -124,12 +249,12 @@ fn simple_word_wrapping_with_spaces() {
         size_for(&FONT_6X9, 9, 2),
         &[
             "......................................................",
-            ".....................................#................",
-            ".....................................#................",
-            "...............#...#...##...#.#....###................",
-            "...............#.#.#..#..#..##.#..#..#................",
-            "...............#.#.#..#..#..#.....#..#................",
-            "................#.#....##...#......###................",
+            "..............................................#.......",
+            "..............................................#.......",
+            "........................#...#...##...#.#....###.......",
+            "........................#.#.#..#..#..##.#..#..#.......",
+            "........................#.#.#..#..#..#.....#..#.......",
+            ".........................#.#....##...#......###.......",
             "......................................................",
             "......................................................",
             "................................................      ",
@@ -346,3 +471,27 @@ fn tab_rendering() {
         ],
     );
 }
+
+#[test]
+fn characters_are_spread_in_lines_without_spaces() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .justify_characters(true)
+            .justify_last_line(true)
+            .build(),
+        "abc",
+        size_for(&FONT_6X9, 5, 1),
+        &[
+            "..............................",
+            ".............#................",
+            ".............#................",
+            "..###........###..........###.",
+            ".#..#........#..#........#....",
+            ".#..#........#..#........#....",
+            "..###........###..........###.",
+            "..............................",
+            "..............................",
+        ],
+    );
+}
@@ -1,4 +1,5 @@
 mod center;
+mod custom;
 mod justified;
 mod left;
 mod right;
@@ -0,0 +1,771 @@
+//! Reuse work between draws.
+
+use core::{
+    cell::Cell,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+
+use crate::style::{LineMeasurement, TextBoxStyle};
+
+/// Identifies the layout that the cached measurements belong to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CacheKey {
+    text_hash: u64,
+    text_len: usize,
+    size: Size,
+    style: TextBoxStyle,
+}
+
+impl CacheKey {
+    fn new(text: &str, size: Size, style: &TextBoxStyle) -> Self {
+        Self {
+            text_hash: Fnv::hash_of(&text),
+            text_len: text.len(),
+            size,
+            style: *style,
+        }
+    }
+}
+
+/// 64 bit FNV-1a hasher.
+pub(crate) struct Fnv(u64);
+
+impl Fnv {
+    /// Returns the hash of a value.
+    pub(crate) fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = Self(0xcbf2_9ce4_8422_2325);
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// The measurement of a line that starts at `offset` and is measured to fit into `width`.
+///
+/// `end` is the offset after the tokens read by the measurement.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CachedLine {
+    offset: usize,
+    width: u32,
+    measurement: LineMeasurement,
+    end: usize,
+}
+
+/// Cached measurements of a text box.
+///
+/// Drawing a text box measures the height of its text, and the width and height of each line
+/// before the line is drawn. A `MeasurementCache` keeps these measurements between calls to
+/// [`TextBox::draw_cached`], so that redrawing a text box that hasn't changed, e.g. a label that
+/// is redrawn every frame, doesn't measure the text again.
+///
+/// The cache holds the measurements of the first `N` lines of the text. The remaining lines are
+/// measured on every draw.
+///
+/// The measurements are discarded when the text, the size of the text box or its
+/// [`TextBoxStyle`] changes. The text is compared using a hash of its contents, so text that is
+/// changed in place is also detected. Changes to the character style, the fonts or the font
+/// variants of the text box are not detected: call [`clear`] after changing them.
+///
+/// [`TextBox::draw_cached`]: crate::TextBox::draw_cached()
+/// [`clear`]: MeasurementCache::clear()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{cache::MeasurementCache, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+/// let text_box = TextBox::new("Status: ready", bounding_box, character_style);
+///
+/// let cache = MeasurementCache::<4>::new();
+/// for _frame in 0..3 {
+///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+///
+///     // The text is only measured in the first frame.
+///     text_box.draw_cached(&mut display, &cache)?;
+/// }
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Debug)]
+pub struct MeasurementCache<const N: usize> {
+    key: Cell<Option<CacheKey>>,
+    text_height: Cell<Option<u32>>,
+    lines: [Cell<Option<CachedLine>>; N],
+}
+
+impl<const N: usize> MeasurementCache<N> {
+    // Only used to initialize the array, which can't be done with `Cell::new` directly.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_LINE: Cell<Option<CachedLine>> = Cell::new(None);
+
+    /// Creates a new, empty measurement cache.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            key: Cell::new(None),
+            text_height: Cell::new(None),
+            lines: [Self::EMPTY_LINE; N],
+        }
+    }
+
+    /// Discards the cached measurements.
+    #[inline]
+    pub fn clear(&self) {
+        self.key.set(None);
+        self.text_height.set(None);
+        for line in self.lines.iter() {
+            line.set(None);
+        }
+    }
+
+    /// Returns the cache entries for a text box, discarding the measurements of a different one.
+    pub(crate) fn entries(&self, text: &str, size: Size, style: &TextBoxStyle) -> CacheEntries<'_> {
+        let key = CacheKey::new(text, size, style);
+        if self.key.get() != Some(key) {
+            self.clear();
+            self.key.set(Some(key));
+        }
+
+        CacheEntries {
+            text_height: &self.text_height,
+            lines: &self.lines,
+        }
+    }
+}
+
+impl<const N: usize> Default for MeasurementCache<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The cache entries of a single text box.
+#[derive(Clone, Copy)]
+pub(crate) struct CacheEntries<'a> {
+    text_height: &'a Cell<Option<u32>>,
+    lines: &'a [Cell<Option<CachedLine>>],
+}
+
+impl CacheEntries<'_> {
+    /// Returns the cached height of the text, or measures and caches it.
+    pub(crate) fn text_height(&self, measure: impl FnOnce() -> u32) -> u32 {
+        match self.text_height.get() {
+            Some(height) => height,
+            None => {
+                let height = measure();
+                self.text_height.set(Some(height));
+                height
+            }
+        }
+    }
+
+    /// Returns the cached measurement of the `index`th line and the offset after it, or measures
+    /// and caches them.
+    pub(crate) fn line(
+        &self,
+        index: usize,
+        offset: usize,
+        width: u32,
+        measure: impl FnOnce() -> (LineMeasurement, usize),
+    ) -> (LineMeasurement, usize) {
+        let entry = match self.lines.get(index) {
+            Some(entry) => entry,
+            None => return measure(),
+        };
+
+        match entry.get() {
+            Some(line) if line.offset == offset && line.width == width => {
+                (line.measurement, line.end)
+            }
+            _ => {
+                let (measurement, end) = measure();
+                entry.set(Some(CachedLine {
+                    offset,
+                    width,
+                    measurement,
+                    end,
+                }));
+                (measurement, end)
+            }
+        }
+    }
+}
+
+/// The properties of a text box that apply to all of its lines.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Frame<S: TextRenderer> {
+    pub character_style: S,
+    pub style: TextBoxStyle,
+    pub bounds: Rectangle,
+    pub vertical_offset: i32,
+    pub background_color: S::Color,
+    pub border_color: Option<S::Color>,
+}
+
+/// A line drawn by a previous draw.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DrawnLine {
+    /// The hash of everything that affects how the line is drawn.
+    pub hash: u64,
+
+    /// The visible area of the line.
+    pub area: Rectangle,
+}
+
+/// The lines drawn by the previous call to [`TextBox::draw_incremental`].
+///
+/// `draw_incremental` compares each line of the text box with the line drawn at the same place
+/// by the previous call, and only redraws the lines that have changed, after erasing them. This
+/// makes redrawing text that changes a little every frame, like a clock or a counter, much
+/// cheaper than drawing the whole text box.
+///
+/// Lines are compared using a hash of their text, their position and their measurements. The
+/// whole text box is redrawn instead if
+///  - the character style, the [`TextBoxStyle`], the bounds, the vertical offset, the background
+///    or the border color of the text box has changed,
+///  - a line has moved, or the number of displayed lines has changed,
+///  - the text box displays more than `N` lines, or
+///  - the text contains carriage returns.
+///
+/// A state should only be used with one text box. Call [`clear`] to redraw the whole text box
+/// the next time, e.g. after something else has been drawn over it.
+///
+/// [`TextBox::draw_incremental`]: crate::TextBox::draw_incremental()
+/// [`clear`]: RedrawState::clear()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{cache::RedrawState, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 18));
+///
+/// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// display.set_allow_overdraw(true);
+///
+/// let mut state = RedrawState::<_, 2>::new();
+/// for text in ["Temp: 21 C\nHumidity: 40%", "Temp: 22 C\nHumidity: 40%"] {
+///     // Only the first line is drawn the second time.
+///     TextBox::new(text, bounding_box, character_style)
+///         .set_background_color(Some(BinaryColor::Off))
+///         .draw_incremental(&mut display, &mut state)?;
+/// }
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Clone)]
+pub struct RedrawState<S: TextRenderer, const N: usize> {
+    frame: Option<Frame<S>>,
+    lines: [Option<DrawnLine>; N],
+    line_count: usize,
+    restart: bool,
+}
+
+impl<S: TextRenderer, const N: usize> RedrawState<S, N> {
+    /// Creates a new state. The first draw using it draws the whole text box.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            frame: None,
+            lines: [None; N],
+            line_count: 0,
+            restart: false,
+        }
+    }
+
+    /// Forgets the drawn lines, so that the whole text box is drawn the next time.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.frame = None;
+    }
+}
+
+impl<S: TextRenderer + PartialEq, const N: usize> RedrawState<S, N> {
+    /// Starts a draw of the given frame.
+    pub(crate) fn begin(&mut self, frame: Frame<S>, full: bool) -> Redraw<'_> {
+        let full = full || self.line_count > N || self.frame.as_ref() != Some(&frame);
+        if full {
+            self.lines = [None; N];
+        }
+        self.frame = Some(frame);
+        self.restart = false;
+
+        Redraw {
+            lines: &mut self.lines,
+            line_count: &mut self.line_count,
+            restart: &mut self.restart,
+            full,
+        }
+    }
+
+    /// Returns whether the last draw stopped because the whole text box needs to be redrawn.
+    pub(crate) const fn needs_restart(&self) -> bool {
+        self.restart
+    }
+}
+
+impl<S: TextRenderer, const N: usize> fmt::Debug for RedrawState<S, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedrawState")
+            .field("lines", &self.lines)
+            .field("line_count", &self.line_count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: TextRenderer, const N: usize> Default for RedrawState<S, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What to do with a displayed line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LineAction {
+    /// The line hasn't changed.
+    Skip,
+
+    /// The line has changed and must be erased before it is drawn.
+    Redraw,
+
+    /// The line is drawn as a part of the whole text box.
+    Draw,
+
+    /// The layout has changed, the whole text box must be redrawn.
+    Restart,
+}
+
+/// The drawn lines of a single draw.
+pub(crate) struct Redraw<'a> {
+    lines: &'a mut [Option<DrawnLine>],
+    line_count: &'a mut usize,
+    restart: &'a mut bool,
+
+    /// True if the whole text box is drawn.
+    pub full: bool,
+}
+
+impl Redraw<'_> {
+    /// Records the `index`th displayed line, and returns how to draw it.
+    pub(crate) fn line(&mut self, index: usize, line: DrawnLine) -> LineAction {
+        if self.full {
+            if let Some(entry) = self.lines.get_mut(index) {
+                *entry = Some(line);
+            }
+            return LineAction::Draw;
+        }
+
+        match self.lines.get_mut(index) {
+            Some(Some(previous)) if *previous == line => LineAction::Skip,
+            Some(Some(previous)) if previous.area == line.area => {
+                *previous = line;
+                LineAction::Redraw
+            }
+            _ => {
+                *self.restart = true;
+                LineAction::Restart
+            }
+        }
+    }
+
+    /// Finishes the draw after `count` lines were displayed.
+    pub(crate) fn finish(&mut self, count: usize) {
+        if self.full {
+            *self.line_count = count;
+        } else if *self.line_count != count {
+            *self.restart = true;
+        }
+    }
+}
+
+/// The first and last printable ASCII characters.
+const FIRST_PRINTABLE: u8 = 0x20;
+const LAST_PRINTABLE: u8 = 0x7E;
+
+/// The number of printable ASCII characters.
+const PRINTABLE_COUNT: usize = (LAST_PRINTABLE - FIRST_PRINTABLE + 1) as usize;
+
+/// Character style that remembers the widths of printable ASCII characters.
+///
+/// Measuring text with a proportional font looks up every glyph in the font. This wrapper
+/// measures each printable ASCII character (`' '` to `'~'`) once, when it is first used, and
+/// measures text that only contains these characters using the recorded widths. Other text is
+/// measured by the wrapped character style. The recorded widths take less than 300 bytes.
+///
+/// Changing the colors of the character style doesn't change the widths. Use [`set_style`] to
+/// replace the wrapped character style, which discards the recorded widths.
+///
+/// The bounding box of text measured using the recorded widths is as wide as the text advances
+/// the position, and as high as the bounding box of its first character.
+///
+/// [`set_style`]: AsciiWidthCache::set_style()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{cache::AsciiWidthCache, TextBox};
+///
+/// // A monospace font is used here, but proportional fonts benefit the most.
+/// let character_style = AsciiWidthCache::new(MonoTextStyle::new(&FONT_6X9, BinaryColor::On));
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("Hello, World!", bounding_box, character_style);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AsciiWidthCache<S> {
+    style: S,
+
+    /// The advance and the width of each character, if they have been measured.
+    widths: [Cell<Option<(u8, u8)>>; PRINTABLE_COUNT],
+}
+
+impl<S> AsciiWidthCache<S> {
+    // Only used to initialize the array, which can't be done with `Cell::new` directly.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNKNOWN: Cell<Option<(u8, u8)>> = Cell::new(None);
+
+    /// Creates a new width cache for the given character style.
+    #[inline]
+    pub const fn new(style: S) -> Self {
+        Self {
+            style,
+            widths: [Self::UNKNOWN; PRINTABLE_COUNT],
+        }
+    }
+
+    /// Returns the wrapped character style.
+    #[inline]
+    pub const fn style(&self) -> &S {
+        &self.style
+    }
+
+    /// Replaces the wrapped character style and discards the recorded widths.
+    #[inline]
+    pub fn set_style(&mut self, style: S) {
+        self.style = style;
+        for width in self.widths.iter() {
+            width.set(None);
+        }
+    }
+
+    /// Returns the wrapped character style.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.style
+    }
+}
+
+impl<S: TextRenderer> AsciiWidthCache<S> {
+    /// Returns the advance and the width of a printable ASCII character.
+    ///
+    /// The advance includes the space that the font adds before the next character.
+    fn widths_of(&self, c: u8) -> (u32, u32) {
+        let entry = &self.widths[usize::from(c - FIRST_PRINTABLE)];
+        if let Some((advance, width)) = entry.get() {
+            return (u32::from(advance), u32::from(width));
+        }
+
+        let pair = [c, c];
+        let measure = |len| {
+            let text = core::str::from_utf8(&pair[..len]).unwrap_or_default();
+            self.style
+                .measure_string(text, Point::zero(), Baseline::Top)
+                .next_position
+                .x as u32
+        };
+        let width = measure(1);
+        let advance = measure(2).saturating_sub(width);
+
+        if let (Ok(a), Ok(w)) = (u8::try_from(advance), u8::try_from(width)) {
+            entry.set(Some((a, w)));
+        }
+
+        (advance, width)
+    }
+}
+
+impl<S: TextRenderer> TextRenderer for AsciiWidthCache<S> {
+    type Color = S::Color;
+
+    #[inline]
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.style.draw_string(text, position, baseline, target)
+    }
+
+    #[inline]
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.style
+            .draw_whitespace(width, position, baseline, target)
+    }
+
+    #[inline]
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bytes = text.as_bytes();
+        let last = match bytes.split_last() {
+            Some((&last, rest))
+                if (FIRST_PRINTABLE..=LAST_PRINTABLE).contains(&last)
+                    && rest
+                        .iter()
+                        .all(|c| (FIRST_PRINTABLE..=LAST_PRINTABLE).contains(c)) =>
+            {
+                last
+            }
+            _ => return self.style.measure_string(text, position, baseline),
+        };
+
+        let width = bytes[..bytes.len() - 1]
+            .iter()
+            .map(|&c| self.widths_of(c).0)
+            .sum::<u32>()
+            + self.widths_of(last).1;
+
+        let first = self.style.measure_string(&text[..1], position, baseline);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(
+                first.bounding_box.top_left,
+                Size::new(width, first.bounding_box.size.height),
+            ),
+            next_position: position + Point::new(width as i32, 0),
+        }
+    }
+
+    #[inline]
+    fn line_height(&self) -> u32 {
+        self.style.line_height()
+    }
+}
+
+impl<S: CharacterStyle> CharacterStyle for AsciiWidthCache<S> {
+    type Color = S::Color;
+
+    #[inline]
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.style.set_text_color(text_color);
+    }
+
+    #[inline]
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.style.set_background_color(background_color);
+    }
+
+    #[inline]
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.style.set_underline_color(underline_color);
+    }
+
+    #[inline]
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.style.set_strikethrough_color(strikethrough_color);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+        text::{renderer::TextRenderer, Baseline},
+    };
+
+    use crate::{
+        alignment::HorizontalAlignment,
+        cache::{AsciiWidthCache, MeasurementCache, RedrawState},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_same_as_uncached(
+        text_box: &TextBox<'_, MonoTextStyle<'_, BinaryColor>>,
+        cache: &MeasurementCache<2>,
+    ) {
+        let mut expected = MockDisplay::new();
+        text_box.draw(&mut expected).unwrap();
+
+        let mut display = MockDisplay::new();
+        text_box.draw_cached(&mut display, cache).unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn cached_text_box_is_drawn_the_same() {
+        let cache = MeasurementCache::new();
+        let text_box = TextBox::new(
+            "Lines beyond the cache are measured.",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 8, 5)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        );
+
+        assert_same_as_uncached(&text_box, &cache);
+        assert_same_as_uncached(&text_box, &cache);
+    }
+
+    #[test]
+    fn changes_invalidate_the_cache() {
+        let cache = MeasurementCache::new();
+        let mut text_box = TextBox::with_alignment(
+            "aaaa b",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+            HorizontalAlignment::Right,
+        );
+        assert_same_as_uncached(&text_box, &cache);
+
+        text_box.text = "a bbbb";
+        assert_same_as_uncached(&text_box, &cache);
+
+        text_box.bounds.size = size_for(&FONT_6X9, 3, 2);
+        assert_same_as_uncached(&text_box, &cache);
+
+        text_box.style.max_lines = Some(1);
+        assert_same_as_uncached(&text_box, &cache);
+    }
+
+    #[test]
+    fn incremental_draws_match_full_draws() {
+        let bounds = Rectangle::new(Point::new(1, 2), size_for(&FONT_6X9, 6, 3));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let mut state = RedrawState::<_, 2>::new();
+        for text in [
+            "12:00\nAlarm",
+            "12:01\nAlarm",
+            "12:01\nSnooze",
+            "1\n2\n3",
+            "12:01\nAlarm",
+            "12:01 Alarm",
+        ] {
+            let mut text_box = TextBox::new(text, bounds, character_style);
+            text_box.set_background_color(Some(BinaryColor::Off));
+            text_box.draw_incremental(&mut display, &mut state).unwrap();
+
+            let mut expected = MockDisplay::new();
+            expected.set_allow_overdraw(true);
+            text_box.draw(&mut expected).unwrap();
+
+            display.assert_eq(&expected);
+        }
+    }
+
+    #[test]
+    fn only_changed_lines_are_drawn() {
+        let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 2));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut state = RedrawState::<_, 2>::new();
+        for (text, drawn_area) in [
+            ("12:00\nAlarm", bounds),
+            (
+                "12:00\nSnooze",
+                Rectangle::new(Point::new(0, 9), size_for(&FONT_6X9, 6, 1)),
+            ),
+            ("12:00\nSnooze", Rectangle::zero()),
+        ] {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+
+            TextBox::new(text, bounds, character_style)
+                .set_background_color(Some(BinaryColor::Off))
+                .draw_incremental(&mut display, &mut state)
+                .unwrap();
+
+            assert_eq!(display.affected_area(), drawn_area, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn ascii_widths_match_the_style() {
+        let style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let mut font = FONT_6X9;
+        font.character_spacing = 2;
+        let spaced = MonoTextStyle::new(&font, BinaryColor::On);
+
+        for style in [style, spaced] {
+            let cached = AsciiWidthCache::new(style);
+            for text in ["", "a", "Hello, World!", "caf\u{e9}", "a\tb"] {
+                for _ in 0..2 {
+                    assert_eq!(
+                        cached.measure_string(text, Point::new(1, 2), Baseline::Middle),
+                        style.measure_string(text, Point::new(1, 2), Baseline::Middle),
+                        "{:?}",
+                        text
+                    );
+                }
+            }
+        }
+    }
+}
@@ -18,7 +18,7 @@
 //!  - non-breaking space (`\u{200b}`)
 //!  - zero-width space (`\u{a0}`)
 //!  - soft hyphen (`\u{ad}`)
-//!  - carriage return (`\r`)
+//!  - carriage return (`\r`), `\r\n` line endings are treated as a single line break
 //!  - tab (`\t`) with configurable tab size
 //!
 //! `TextBox` also supports text coloring using [ANSI escape codes](https://en.wikipedia.org/wiki/ANSI_escape_code).
@@ -93,6 +93,9 @@
 //!
 //! * `plugin` (*experimental*): allows implementing custom plugins.
 //! * `ansi` (default enabled): enables ANSI sequence support using the `Ansi` plugin.
+//! * `grapheme`: measures, breaks and hit tests text by grapheme clusters instead of characters,
+//!   so that combining marks and emoji sequences are kept together.
+//! * `serde`: implements `Serialize` and `Deserialize` for `TextBoxStyle`.
 //!
 //! [embedded-graphics]: https://github.com/embedded-graphics/embedded-graphics/
 //! [the embedded-graphics simulator]: https://github.com/embedded-graphics/embedded-graphics/tree/master/simulator
@@ -108,6 +111,8 @@
 #![allow(clippy::needless_doctest_main)]
 
 pub mod alignment;
+pub mod cache;
+pub mod marquee;
 mod parser;
 pub mod plugin;
 mod rendering;
@@ -117,19 +122,23 @@ mod utils;
 use crate::{
     alignment::{HorizontalAlignment, VerticalAlignment},
     plugin::{NoPlugin, PluginMarker as Plugin, PluginWrapper},
-    style::{HeightMode, TabSize, TextBoxStyle},
+    rendering::fonts::Fonts,
+    style::{FontMetrics, FontVariants, HeightMode, ScrollMetrics, TabSize, TextBoxStyle},
+    utils::baseline_offset,
 };
+use az::SaturatingAs;
 use embedded_graphics::{
-    geometry::{Dimensions, Point},
+    geometry::{Dimensions, Point, Size},
     primitives::Rectangle,
     text::{
         renderer::{CharacterStyle, TextRenderer},
-        LineHeight,
+        Baseline, LineHeight,
     },
     transform::Transform,
 };
 use object_chain::{Chain, ChainElement, Link};
 
+pub use crate::{parser::UnderlineStyle, rendering::pages::Pages};
 #[cfg(feature = "plugin")]
 pub use crate::{
     parser::{ChangeTextStyle, Token},
@@ -236,6 +245,24 @@ where
     /// Vertical offset applied to the text just before rendering.
     pub vertical_offset: i32,
 
+    /// Alternative character styles that can be selected while rendering the text.
+    pub fonts: &'a [S],
+
+    /// Bold and italic variants of the character style.
+    pub font_variants: FontVariants<'a, S>,
+
+    /// The character style of the drop cap, the first letter of the text that spans multiple
+    /// lines. `None` means the text has no drop cap.
+    pub drop_cap: Option<&'a S>,
+
+    /// The color used to fill the bounding box before the text is drawn. `None` means the box is
+    /// not filled.
+    pub background_color: Option<S::Color>,
+
+    /// The color of the border drawn around the text box. `None` means the border is not drawn,
+    /// but it still takes up space.
+    pub border_color: Option<S::Color>,
+
     plugin: PluginWrapper<'a, M, S::Color>,
 }
 
@@ -264,6 +291,11 @@ where
             character_style,
             style: textbox_style,
             vertical_offset: 0,
+            fonts: &[],
+            font_variants: FontVariants::new(),
+            drop_cap: None,
+            background_color: None,
+            border_color: None,
             plugin: PluginWrapper::new(NoPlugin::new()),
         };
 
@@ -380,6 +412,12 @@ where
     ///
     /// Vertical offset changes the vertical position of the displayed text within the bounding box.
     /// Setting a positive value moves the text down.
+    ///
+    /// To scroll text in a fixed viewport, use a negative offset together with
+    /// [`HeightMode::Exact`]`(`[`VerticalOverdraw::Hidden`]`)`. Lines that are scrolled out of the
+    /// bounding box are not drawn, lines that are partially visible are clipped.
+    ///
+    /// [`VerticalOverdraw::Hidden`]: crate::style::VerticalOverdraw::Hidden
     #[inline]
     pub fn set_vertical_offset(&mut self, offset: i32) -> &mut Self {
         self.vertical_offset = offset;
@@ -398,6 +436,11 @@ where
             character_style: self.character_style,
             style: self.style,
             vertical_offset: self.vertical_offset,
+            fonts: self.fonts,
+            font_variants: self.font_variants,
+            drop_cap: self.drop_cap,
+            background_color: self.background_color,
+            border_color: self.border_color,
             plugin: PluginWrapper::new(Chain::new(plugin)),
         };
         styled.style.height_mode.apply(&mut styled);
@@ -424,6 +467,11 @@ where
             character_style: self.character_style,
             style: self.style,
             vertical_offset: self.vertical_offset,
+            fonts: self.fonts,
+            font_variants: self.font_variants,
+            drop_cap: self.drop_cap,
+            background_color: self.background_color,
+            border_color: self.border_color,
             plugin: PluginWrapper::new(parent.append(plugin)),
         };
         styled.style.height_mode.apply(&mut styled);
@@ -435,6 +483,17 @@ where
     pub fn take_plugins(self) -> P {
         self.plugin.into_inner()
     }
+
+    /// Returns a copy of the plugins.
+    ///
+    /// Plugins are updated when the text box is drawn, so the returned plugins can be used to
+    /// read the state they were left in by the last [`draw`] call.
+    ///
+    /// [`draw`]: embedded_graphics::Drawable::draw()
+    #[inline]
+    pub fn plugins(&self) -> P {
+        self.plugin.plugin()
+    }
 }
 
 impl<'a, S, M> Transform for TextBox<'a, S, M>
@@ -474,32 +533,292 @@ where
     S: TextRenderer,
     M: Plugin<'a, S::Color>,
 {
+    /// Sets the alternative character styles of the text box.
+    ///
+    /// The text is rendered using the character style of the text box by default. Alternative
+    /// styles can be selected in the text using the `\x1b[11m` to `\x1b[19m` escape codes of the
+    /// ANSI plugin, or by emitting `ChangeTextStyle::Font` tokens from a custom plugin. Selecting a
+    /// character style replaces the current one, including its colors.
+    ///
+    /// The height of each line is adjusted to fit every font used in the line, with the text
+    /// aligned to the [`baseline`] set in the text box style. The width of spaces is determined by
+    /// the font that is active at the start of the line.
+    ///
+    /// [`baseline`]: TextBoxStyle::baseline
+    #[inline]
+    pub fn set_fonts(&mut self, fonts: &'a [S]) -> &mut Self {
+        self.fonts = fonts;
+        self.style.height_mode.apply(self);
+        self
+    }
+
+    /// Sets the background color of the text box.
+    ///
+    /// The whole bounding box is filled with the background color before the text is drawn,
+    /// including the space between lines and around the text. The background color of the
+    /// character style is still used behind the characters.
+    #[inline]
+    pub fn set_background_color(&mut self, color: Option<S::Color>) -> &mut Self {
+        self.background_color = color;
+        self
+    }
+
+    /// Sets the color of the border.
+    ///
+    /// The width of the border is set by [`TextBoxStyle::border_width`].
+    #[inline]
+    pub fn set_border_color(&mut self, color: Option<S::Color>) -> &mut Self {
+        self.border_color = color;
+        self
+    }
+
+    /// Sets the bold and italic variants of the character style.
+    ///
+    /// The variants are selected by the `ChangeTextStyle::Bold` and `ChangeTextStyle::Italic` text
+    /// style changes, e.g. using the ANSI plugin.
+    #[inline]
+    pub fn set_font_variants(&mut self, variants: FontVariants<'a, S>) -> &mut Self {
+        self.font_variants = variants;
+        self.style.height_mode.apply(self);
+        self
+    }
+
+    /// Sets the character style of the drop cap.
+    ///
+    /// If the text starts with a word, its first letter is drawn using the drop cap style in the
+    /// top left corner of the text area. The letter spans as many lines as its line height needs,
+    /// and these lines are indented by the width of the letter and a space, so that the text wraps
+    /// to the right of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{
+    /// #         ascii::{FONT_6X9, FONT_9X18_BOLD},
+    /// #         MonoTextStyle,
+    /// #     },
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// use embedded_text::TextBox;
+    ///
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// let drop_cap_style = MonoTextStyle::new(&FONT_9X18_BOLD, BinaryColor::On);
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(120, 60));
+    ///
+    /// let mut text_box = TextBox::new(
+    ///     "Once upon a time, there was a little text box.",
+    ///     bounding_box,
+    ///     character_style,
+    /// );
+    /// text_box.set_drop_cap(Some(&drop_cap_style));
+    /// ```
+    #[inline]
+    pub fn set_drop_cap(&mut self, style: Option<&'a S>) -> &mut Self {
+        self.drop_cap = style;
+        self.style.height_mode.apply(self);
+        self
+    }
+
     /// Sets the height of the [`TextBox`] to the height of the text.
     #[inline]
     fn fit_height(&mut self) -> &mut Self {
         self.fit_height_limited(u32::MAX)
     }
 
+    /// Returns the size of the text when wrapped to the given width.
+    ///
+    /// The width is the width of the widest line, the height includes the line and paragraph
+    /// spacing. The padding and border are subtracted from `max_width` and included in the result.
+    /// The result can be used to size and position the text box before drawing it. The text box's
+    /// own bounding box is not used or changed.
+    ///
+    /// The size is multiplied by the [`scale`](TextBoxStyle::scale) of the text box, but the
+    /// [`rotation`](TextBoxStyle::rotation) is not applied to it.
+    ///
+    /// An empty text is as tall as a single line and has zero width.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # use embedded_text::TextBox;
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// let mut text_box = TextBox::new("Hello,\nWorld!", Rectangle::zero(), character_style);
+    ///
+    /// let size = text_box.content_size(100);
+    /// assert_eq!(size, Size::new(6 * 6, 2 * 9));
+    ///
+    /// text_box.bounds = Rectangle::with_center(Point::new(50, 50), size);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn content_size(&self, max_width: u32) -> Size {
+        let insets = self.style.insets();
+        let scale = self.style.scale_factor();
+        let size = self
+            .style
+            .measure_text_impl(
+                self.plugin.clone(),
+                Fonts::new(&self.character_style, self.fonts, self.font_variants)
+                    .with_drop_cap(self.drop_cap),
+                self.text,
+                (max_width / scale).saturating_sub(insets.horizontal()),
+            )
+            .size;
+        let size = insets.expand(size);
+
+        Size::new(
+            size.width.saturating_mul(scale),
+            size.height.saturating_mul(scale).min(i32::MAX as u32),
+        )
+    }
+
+    /// Returns the vertical metrics of the character style of the text box.
+    ///
+    /// The text box lays out lines from the top: every line starts [`line_spacing`] pixels below
+    /// the top of the previous one, and the characters of a line are aligned by the
+    /// [`baseline`](TextBoxStyle::baseline) setting of the text box. For text that uses a single
+    /// font, the alphabetic baseline is [`ascent`] pixels below the top of the line, regardless of
+    /// the `baseline` setting, which only matters when fonts of different sizes share a line.
+    ///
+    /// The baseline of the first line of top aligned text is `ascent` pixels below the top of the
+    /// bounding box, plus the top padding and border width. This can be used to align the text
+    /// with other graphics.
+    ///
+    /// The metrics are multiplied by the [`scale`](TextBoxStyle::scale) of the text box.
+    ///
+    /// [`line_spacing`]: FontMetrics::line_spacing
+    /// [`ascent`]: FontMetrics::ascent
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # use embedded_text::TextBox;
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// let text_box = TextBox::new("Hello", Rectangle::zero(), character_style);
+    ///
+    /// let metrics = text_box.font_metrics();
+    /// assert_eq!((metrics.ascent, metrics.descent), (6, 3));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn font_metrics(&self) -> FontMetrics {
+        let scale = self.style.scale_factor();
+        let line_height = self.character_style.line_height();
+        let ascent = baseline_offset(&self.character_style, Baseline::Alphabetic)
+            .saturating_as::<u32>()
+            .min(line_height);
+
+        FontMetrics {
+            ascent: ascent.saturating_mul(scale),
+            descent: (line_height - ascent).saturating_mul(scale),
+            line_spacing: self.style.line_spacing(line_height).saturating_mul(scale),
+        }
+    }
+
+    /// Returns the height of the text, the height of the text area and the scroll position.
+    ///
+    /// The text is measured the same way as when it's drawn. The result can be used to draw a
+    /// scrollbar next to the text box, with a thumb that is `viewport_height / content_height`
+    /// times as long as the scrollbar, and starts `offset / content_height` of the way down.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # use embedded_text::TextBox;
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// let mut text_box = TextBox::new(
+    ///     "one\ntwo\nthree",
+    ///     Rectangle::new(Point::zero(), Size::new(60, 18)),
+    ///     character_style,
+    /// );
+    /// text_box.set_vertical_offset(-9);
+    ///
+    /// let metrics = text_box.scroll_metrics();
+    /// assert_eq!(metrics.content_height, 27);
+    /// assert_eq!(metrics.viewport_height, 18);
+    /// assert_eq!(metrics.offset, 9);
+    /// assert_eq!(metrics.max_offset(), 9);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn scroll_metrics(&self) -> ScrollMetrics {
+        let insets = self.style.insets();
+        let size = self.style.layout_size(self.bounds.size);
+
+        let content_height = self.style.measure_text_height_impl(
+            self.plugin.clone(),
+            Fonts::new(&self.character_style, self.fonts, self.font_variants)
+                .with_drop_cap(self.drop_cap),
+            self.text,
+            size.width.saturating_sub(insets.horizontal()),
+        );
+
+        ScrollMetrics {
+            content_height,
+            viewport_height: size.height.saturating_sub(insets.vertical()),
+            offset: self.vertical_offset.saturating_neg(),
+        }
+    }
+
+    /// Sets the size of the [`TextBox`] to the size of the text.
+    #[inline]
+    fn fit_size(&mut self) -> &mut Self {
+        let rotation = self.style.rotation;
+        let width = rotation.layout_size(self.bounds.size).width;
+        self.bounds.size = rotation.layout_size(self.content_size(width));
+
+        self
+    }
+
     /// Sets the height of the [`TextBox`] to the height of the text, limited to `max_height`.
     ///
     /// This method allows you to set a maximum height. The [`TextBox`] will take up at most
     /// `max_height` pixel vertical space.
     #[inline]
     fn fit_height_limited(&mut self, max_height: u32) -> &mut Self {
-        // Measure text given the width of the textbox
+        let insets = self.style.insets();
+        let rotation = self.style.rotation;
+        let scale = self.style.scale_factor();
+        let size = rotation.layout_size(self.bounds.size);
+
+        // Measure text given the width of the text area
         let text_height = self
             .style
             .measure_text_height_impl(
                 self.plugin.clone(),
-                &self.character_style,
+                Fonts::new(&self.character_style, self.fonts, self.font_variants)
+                    .with_drop_cap(self.drop_cap),
                 self.text,
-                self.bounding_box().size.width,
+                (size.width / scale).saturating_sub(insets.horizontal()),
             )
+            .saturating_add(insets.vertical())
+            .saturating_mul(scale)
             .min(max_height)
             .min(i32::MAX as u32);
 
         // Apply height
-        self.bounds.size.height = text_height;
+        self.bounds.size = rotation.layout_size(Size::new(size.width, text_height));
 
         self
     }
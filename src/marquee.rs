@@ -0,0 +1,335 @@
+//! Single line labels that scroll horizontally.
+
+use embedded_graphics::{
+    draw_target::{DrawTarget, DrawTargetExt},
+    geometry::{Dimensions, Point, Size},
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+    Drawable,
+};
+
+use crate::{
+    style::{TextBoxStyle, TextBoxStyleBuilder, WrapMode},
+    utils::str_width,
+    TextBox,
+};
+
+/// A single line label that scrolls horizontally when its text is wider than the label.
+///
+/// The text is never wrapped. If it doesn't fit the bounding box, it is drawn shifted to the left
+/// by the current offset, and repeated after a gap so that the text scrolls around continuously.
+/// Call [`advance`] before drawing each frame to scroll the text. Text that fits the bounding box
+/// is drawn in place and doesn't scroll.
+///
+/// By default, the gap between the end of the text and its repeated start is four spaces wide.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// use embedded_text::marquee::Marquee;
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounds = Rectangle::new(Point::zero(), Size::new(48, 9));
+///
+/// let mut title = Marquee::new("Never Gonna Give You Up", bounds, character_style);
+///
+/// for _frame in 0..10 {
+///     # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+///     title.draw(&mut display)?;
+///     title.advance(1);
+/// }
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`advance`]: Marquee::advance()
+#[derive(Clone, Debug, Hash)]
+#[must_use]
+pub struct Marquee<'a, S> {
+    /// The text to be displayed.
+    pub text: &'a str,
+
+    /// The bounding box of the label.
+    pub bounds: Rectangle,
+
+    /// The character style of the label.
+    pub character_style: S,
+
+    /// The width of the gap between the end of the text and its repeated start, in pixels.
+    pub gap: u32,
+
+    /// The distance the text is scrolled by, in pixels.
+    offset: u32,
+}
+
+impl<'a, S> Marquee<'a, S>
+where
+    S: TextRenderer,
+{
+    /// Creates a new scrolling label.
+    #[inline]
+    pub fn new(text: &'a str, bounds: Rectangle, character_style: S) -> Self {
+        let gap = str_width(&character_style, "    ");
+
+        Self {
+            text,
+            bounds,
+            character_style,
+            gap,
+            offset: 0,
+        }
+    }
+
+    /// Sets the width of the gap between the end of the text and its repeated start, in pixels.
+    #[inline]
+    pub fn set_gap(&mut self, gap: u32) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Returns the distance the text is scrolled by, in pixels.
+    ///
+    /// The offset is always less than the width of the text and the gap after it.
+    #[inline]
+    #[must_use]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Sets the distance the text is scrolled by, in pixels.
+    ///
+    /// Offsets that are larger than the width of the text and the gap wrap around. Text that fits
+    /// the bounding box is not scrolled.
+    #[inline]
+    pub fn set_offset(&mut self, offset: u32) -> &mut Self {
+        self.offset = match self.period() {
+            Some(period) => offset % period,
+            None => 0,
+        };
+        self
+    }
+
+    /// Scrolls the text to the left by `by` pixels.
+    ///
+    /// The offset wraps around after the text and the gap have scrolled by.
+    #[inline]
+    pub fn advance(&mut self, by: u32) -> &mut Self {
+        if let Some(period) = self.period() {
+            self.offset = (self.offset + by % period) % period;
+        }
+        self
+    }
+
+    /// Returns the width of the text, in pixels.
+    #[inline]
+    #[must_use]
+    pub fn text_width(&self) -> u32 {
+        Self::style()
+            .measure_text(&self.character_style, self.text, self.bounds.size.width)
+            .size
+            .width
+    }
+
+    /// Returns whether the text is wider than the label and needs to scroll.
+    #[inline]
+    #[must_use]
+    pub fn scrolls(&self) -> bool {
+        self.text_width() > self.bounds.size.width
+    }
+
+    /// Returns the distance after which the scrolled text repeats, or `None` if the text doesn't
+    /// scroll.
+    fn period(&self) -> Option<u32> {
+        let text_width = self.text_width();
+
+        if text_width > self.bounds.size.width {
+            Some(text_width.saturating_add(self.gap))
+        } else {
+            None
+        }
+    }
+
+    /// The style of the text boxes used to draw the label.
+    const fn style() -> TextBoxStyle {
+        TextBoxStyleBuilder::new().wrap_mode(WrapMode::None).build()
+    }
+}
+
+impl<S> Dimensions for Marquee<'_, S> {
+    #[inline]
+    fn bounding_box(&self) -> Rectangle {
+        self.bounds
+    }
+}
+
+impl<S> Drawable for Marquee<'_, S>
+where
+    S: TextRenderer<Color = <S as CharacterStyle>::Color> + CharacterStyle,
+    <S as CharacterStyle>::Color: Default,
+{
+    type Color = <S as CharacterStyle>::Color;
+    type Output = ();
+
+    #[inline]
+    fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let period = match self.period() {
+            Some(period) => period,
+            None => {
+                TextBox::with_textbox_style(
+                    self.text,
+                    self.bounds,
+                    self.character_style.clone(),
+                    Self::style(),
+                )
+                .draw(display)?;
+
+                return Ok(());
+            }
+        };
+
+        let mut display = display.clipped(&self.bounds);
+
+        let size = Size::new(self.text_width(), self.bounds.size.height);
+        let right = self.bounds.size.width as i32;
+
+        // Draw the text as many times as it's needed to fill the label.
+        let mut x = -(self.offset as i32);
+        while x < right {
+            TextBox::with_textbox_style(
+                self.text,
+                Rectangle::new(self.bounds.top_left + Point::new(x, 0), size),
+                self.character_style.clone(),
+                Self::style(),
+            )
+            .draw(&mut display)?;
+
+            x = x.saturating_add(period as i32);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{marquee::Marquee, utils::test::size_for};
+
+    #[track_caller]
+    fn assert_drawn(marquee: &Marquee<'_, MonoTextStyle<'_, BinaryColor>>, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+        marquee.draw(&mut display).unwrap();
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn text_that_fits_does_not_scroll() {
+        let mut marquee = Marquee::new(
+            "ab",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        );
+
+        assert!(!marquee.scrolls());
+        marquee.advance(5);
+        assert_eq!(marquee.offset(), 0);
+
+        assert_drawn(
+            &marquee,
+            &[
+                "           ",
+                "       #   ",
+                "       #   ",
+                "  ###  ### ",
+                " #  #  #  #",
+                " #  #  #  #",
+                "  ###  ### ",
+            ],
+        );
+    }
+
+    #[test]
+    fn offset_wraps_around() {
+        let mut marquee = Marquee::new(
+            "abc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        );
+        marquee.set_gap(6);
+
+        assert!(marquee.scrolls());
+        assert_eq!(marquee.text_width(), 18);
+
+        marquee.advance(20);
+        assert_eq!(marquee.offset(), 20);
+        marquee.advance(5);
+        assert_eq!(marquee.offset(), 1);
+
+        marquee.set_offset(50);
+        assert_eq!(marquee.offset(), 2);
+    }
+
+    #[test]
+    fn scrolled_text_is_clipped() {
+        let mut marquee = Marquee::new(
+            "abc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        );
+        marquee.set_gap(6);
+        marquee.set_offset(6);
+
+        assert_drawn(
+            &marquee,
+            &[
+                "           ",
+                " #         ",
+                " #         ",
+                " ###    ###",
+                " #  #  #   ",
+                " #  #  #   ",
+                " ###    ###",
+            ],
+        );
+    }
+
+    #[test]
+    fn text_is_repeated_after_the_gap() {
+        let mut marquee = Marquee::new(
+            "abc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        );
+        marquee.set_gap(6);
+        marquee.set_offset(15);
+
+        assert_drawn(
+            &marquee,
+            &[
+                "            ",
+                "            ",
+                "            ",
+                "##         #",
+                "          # ",
+                "          # ",
+                "##         #",
+            ],
+        );
+    }
+}
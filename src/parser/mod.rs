@@ -17,12 +17,17 @@
 //! );
 //! ```
 use core::{marker::PhantomData, str::Chars};
-use embedded_graphics::{prelude::PixelColor, text::DecorationColor};
+use embedded_graphics::{
+    prelude::{PixelColor, Size},
+    text::DecorationColor,
+};
 
 /// Change text style.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ChangeTextStyle<C> {
-    /// Reset text style. Disables decoration, removes background color and sets a default text color.
+    /// Reset text style. Disables decoration, removes background color, sets a default text color,
+    /// turns off reverse video, bold and italic text, returns the text to the baseline and sets
+    /// the underline style to solid.
     Reset,
 
     /// Change text color. `None` means transparent.
@@ -34,11 +39,128 @@ pub enum ChangeTextStyle<C> {
     /// Change color of underlining.
     Underline(DecorationColor<C>),
 
+    /// Change the style of underlining.
+    ///
+    /// Underlines that aren't [`UnderlineStyle::Solid`] are drawn by `embedded-text` along the
+    /// bottom of the line, instead of by the character style.
+    UnderlineStyle(UnderlineStyle),
+
     /// Change color of strikethrough decoration.
     Strikethrough(DecorationColor<C>),
+
+    /// Change the font.
+    ///
+    /// `None` selects the character style of the text box, `Some(n)` selects the `n`th
+    /// alternative character style set by `TextBox::set_fonts`. The selected character style
+    /// replaces the current one, including its colors. Invalid indices are ignored.
+    Font(Option<usize>),
+
+    /// Shift the text up to render superscript. `false` returns the text to the baseline.
+    Superscript(bool),
+
+    /// Shift the text down to render subscript. `false` returns the text to the baseline.
+    Subscript(bool),
+
+    /// Swap the text and background colors. `true` turns reverse video on, `false` turns it off.
+    ///
+    /// While reverse video is on, text color changes set the background color and vice versa.
+    ReverseVideo(bool),
+
+    /// Use the bold variant of the font. `false` turns bold text off.
+    ///
+    /// See `FontVariants` for more information.
+    Bold(bool),
+
+    /// Use the italic variant of the font. `false` turns italic text off.
+    ///
+    /// See `FontVariants` for more information.
+    Italic(bool),
+
+    /// Save the current text style, including the colors, decorations and the selected font.
+    ///
+    /// The saved style is restored by `PopStyle`, which makes it possible to nest style changes,
+    /// e.g. to end a bold span inside colored text without resetting the color. Up to 8 styles
+    /// can be saved at a time. Styles pushed beyond that are not saved, but their `PopStyle` is
+    /// still expected. `Reset` does not remove the saved styles.
+    PushStyle,
+
+    /// Restore the text style saved by the last `PushStyle`. Does nothing if no style is saved.
+    PopStyle,
+}
+
+/// The style of the line drawn under underlined text.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UnderlineStyle {
+    /// A single solid line, drawn by the character style. This is the default.
+    Solid,
+
+    /// Two solid lines.
+    Double,
+
+    /// A dotted line.
+    Dotted,
+
+    /// A dashed line.
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Returns the rows of the underline, relative to the bottom row of the line.
+    pub(crate) const fn rows(self) -> &'static [i32] {
+        match self {
+            UnderlineStyle::Double => &[-2, 0],
+            _ => &[0],
+        }
+    }
+
+    /// Returns whether the underline covers the given column.
+    pub(crate) const fn covers(self, x: i32) -> bool {
+        match self {
+            UnderlineStyle::Solid | UnderlineStyle::Double => true,
+            UnderlineStyle::Dotted => x.rem_euclid(2) == 0,
+            UnderlineStyle::Dashed => x.rem_euclid(6) < 4,
+        }
+    }
 }
 
 /// A text token
+///
+/// The text of a text box is split into tokens before it is laid out and rendered. Plugins can
+/// change, remove or insert tokens, see [`Plugin::next_token`]. Tokens can be created using the
+/// variants directly, or using the constructors, which compute the additional data of the
+/// variants from a string.
+///
+/// [`Plugin::next_token`]: crate::plugin::Plugin::next_token()
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "plugin")]
+/// # fn main() {
+/// use embedded_graphics::pixelcolor::BinaryColor;
+/// use embedded_text::Token;
+///
+/// let word: Token<'_, BinaryColor> = Token::word("Hello");
+/// assert_eq!(word, Token::Word("Hello"));
+///
+/// let spaces: Token<'_, BinaryColor> = Token::whitespace("  ");
+/// assert_eq!(spaces, Token::Whitespace(2, "  "));
+///
+/// // A string is split into the same tokens as the text of a text box.
+/// let tokens = Token::<BinaryColor>::tokenize("Hello, world!\n").collect::<Vec<_>>();
+/// assert_eq!(
+///     tokens,
+///     [
+///         Token::Word("Hello,"),
+///         Token::Whitespace(1, " "),
+///         Token::Word("world!"),
+///         Token::NewLine,
+///     ]
+/// );
+/// # }
+/// # #[cfg(not(feature = "plugin"))]
+/// # fn main() {}
+/// ```
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token<'a, C> {
     /// A newline character.
@@ -69,6 +191,83 @@ pub enum Token<'a, C> {
         /// True to draw over the area of movement with the background color.
         draw_background: bool,
     },
+
+    /// A horizontal line across the text box, drawn using the text color. Contains the characters
+    /// the rule was parsed from.
+    ///
+    /// Lines that only contain three or more `-` characters are parsed as horizontal rules, if
+    /// [`TextBoxStyle::horizontal_rules`] is enabled.
+    ///
+    /// [`TextBoxStyle::horizontal_rules`]: crate::style::TextBoxStyle::horizontal_rules
+    HorizontalRule(&'a str),
+
+    /// An object inline with the text, like an icon, that takes up a box of the given size.
+    ///
+    /// The object stands on the baseline, and the line is made tall enough to contain it. Objects
+    /// are not broken, so an object that doesn't fit the rest of the line is moved to the next
+    /// one. The text box doesn't draw the object, plugins draw it in
+    /// [`Plugin::render_object`].
+    ///
+    /// [`Plugin::render_object`]: crate::plugin::Plugin::render_object
+    InlineObject {
+        /// The identifier of the object, passed to [`Plugin::render_object`].
+        ///
+        /// [`Plugin::render_object`]: crate::plugin::Plugin::render_object
+        id: u32,
+        /// The size of the object.
+        size: Size,
+    },
+}
+
+impl<'a, C> Token<'a, C> {
+    /// Creates a word token.
+    ///
+    /// The word is never broken at its whitespace characters, so `text` should not contain
+    /// spaces. Use [`Token::tokenize`] to split a string into words and spaces.
+    #[inline]
+    pub const fn word(text: &'a str) -> Self {
+        Self::Word(text)
+    }
+
+    /// Creates a whitespace token.
+    ///
+    /// `text` should only contain whitespace characters. Every character counts as a space,
+    /// except zero-width spaces (`U+200B`).
+    #[inline]
+    pub fn whitespace(text: &'a str) -> Self {
+        let count = text.chars().filter(|&c| c != SPEC_CHAR_ZWSP).count();
+
+        Self::Whitespace(count as u32, text)
+    }
+
+    /// Creates a possible wrapping point, where `separator` is rendered if the line is wrapped.
+    ///
+    /// For example, a soft hyphen is parsed as `Token::wrap_point("-")`.
+    #[inline]
+    pub const fn wrap_point(separator: &'a str) -> Self {
+        Self::Break(separator)
+    }
+
+    /// Creates a token that changes the text style.
+    #[inline]
+    pub const fn change_style(change: ChangeTextStyle<C>) -> Self {
+        Self::ChangeTextStyle(change)
+    }
+}
+
+impl<'a, C: PixelColor> Token<'a, C> {
+    /// Splits a string into tokens, the same way as the text of a text box.
+    #[inline]
+    pub fn tokenize(text: &'a str) -> impl Iterator<Item = Self> + Clone {
+        Parser::parse(text)
+    }
+}
+
+impl<'a, C> From<ChangeTextStyle<C>> for Token<'a, C> {
+    #[inline]
+    fn from(change: ChangeTextStyle<C>) -> Self {
+        Self::ChangeTextStyle(change)
+    }
 }
 
 /// Text parser. Turns a string into a stream of [`Token`] objects.
@@ -78,6 +277,16 @@ where
     C: PixelColor,
 {
     inner: Chars<'a>,
+
+    /// True to parse lines of `-` characters as horizontal rules.
+    horizontal_rules: bool,
+
+    /// True if the next token starts a line.
+    line_start: bool,
+
+    /// True if the next word is the rest of a URL that was wrapped.
+    continues_url: bool,
+
     _marker: PhantomData<C>,
 }
 
@@ -107,11 +316,48 @@ where
     pub fn parse(text: &'a str) -> Self {
         Self {
             inner: text.chars(),
+            horizontal_rules: false,
+            line_start: true,
+            continues_url: false,
             _marker: PhantomData,
         }
     }
 
-    pub fn as_str(&self) -> &str {
+    /// Enables or disables parsing lines of `-` characters as horizontal rules.
+    #[inline]
+    #[must_use]
+    pub fn with_horizontal_rules(mut self, horizontal_rules: bool) -> Self {
+        self.horizontal_rules = horizontal_rules;
+        self
+    }
+
+    /// Sets whether the next word is the rest of a URL that was wrapped.
+    pub(crate) fn set_continues_url(&mut self, continues_url: bool) {
+        self.continues_url = continues_url;
+    }
+
+    /// Returns whether the next word is the rest of a URL that was wrapped, and clears the flag.
+    pub(crate) fn take_continues_url(&mut self) -> bool {
+        core::mem::replace(&mut self.continues_url, false)
+    }
+
+    /// Consumes a horizontal rule at the start of the remaining text.
+    ///
+    /// A horizontal rule is a line that only contains three or more `-` characters. The line
+    /// break after the rule is not consumed.
+    fn consume_horizontal_rule(&mut self) -> Option<&'a str> {
+        let text = self.inner.as_str();
+        let line = text.find(['\r', '\n']).map_or(text, |end| &text[..end]);
+
+        if line.len() < 3 || !line.bytes().all(|b| b == b'-') {
+            return None;
+        }
+
+        self.inner = text[line.len()..].chars();
+        Some(line)
+    }
+
+    pub fn as_str(&self) -> &'a str {
         self.inner.as_str()
     }
 
@@ -143,6 +389,13 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        let line_start = core::mem::replace(&mut self.line_start, false);
+        if line_start && self.horizontal_rules {
+            if let Some(rule) = self.consume_horizontal_rule() {
+                return Some(Token::HorizontalRule(rule));
+            }
+        }
+
         let string = self.inner.as_str();
 
         if let Some(c) = self.inner.next() {
@@ -160,7 +413,10 @@ where
             } else {
                 match c {
                     // special characters
-                    '\n' => Some(Token::NewLine),
+                    '\n' => {
+                        self.line_start = true;
+                        Some(Token::NewLine)
+                    }
                     '\r' => Some(Token::CarriageReturn),
                     '\t' => Some(Token::Tab),
                     SPEC_CHAR_ZWSP => Some(Token::Whitespace(0, unsafe {
@@ -211,6 +467,21 @@ mod test {
         )
     }
 
+    #[test]
+    fn constructors_match_parsed_tokens() {
+        assert_tokens("word", vec![Token::word("word")]);
+        assert_tokens("  \u{200B}", vec![Token::whitespace("  \u{200B}")]);
+        assert_tokens("\u{00AD}", vec![Token::wrap_point("-")]);
+        assert_eq!(
+            Token::<BinaryColor>::tokenize("a b").collect::<std::vec::Vec<_>>(),
+            vec![
+                Token::Word("a"),
+                Token::Whitespace(1, " "),
+                Token::Word("b")
+            ]
+        );
+    }
+
     #[test]
     fn test_parse() {
         assert_tokens(
@@ -271,6 +542,25 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_crlf() {
+        // The line iterator treats the pair as a single line break, the tokens keep the length of
+        // the source text.
+        assert_tokens(
+            "a\r\nb\rc\n\r",
+            vec![
+                Token::Word("a"),
+                Token::CarriageReturn,
+                Token::NewLine,
+                Token::Word("b"),
+                Token::CarriageReturn,
+                Token::Word("c"),
+                Token::NewLine,
+                Token::CarriageReturn,
+            ],
+        );
+    }
+
     #[test]
     fn parse_shy_issue_42() {
         assert_tokens(
@@ -278,4 +568,34 @@ mod test {
             vec![Token::Word("foo"), Token::Break("-"), Token::Word("bar")],
         );
     }
+
+    #[test]
+    fn parse_horizontal_rules() {
+        let text = "---\na ---\n--\n---- \r\n-----\r\n";
+        assert_eq!(
+            Parser::parse(text)
+                .with_horizontal_rules(true)
+                .collect::<std::vec::Vec<Token<BinaryColor>>>(),
+            vec![
+                Token::HorizontalRule("---"),
+                Token::NewLine,
+                Token::Word("a"),
+                Token::Whitespace(1, " "),
+                Token::Word("---"),
+                Token::NewLine,
+                Token::Word("--"),
+                Token::NewLine,
+                Token::Word("----"),
+                Token::Whitespace(1, " "),
+                Token::CarriageReturn,
+                Token::NewLine,
+                Token::HorizontalRule("-----"),
+                Token::CarriageReturn,
+                Token::NewLine,
+            ],
+        );
+
+        // Rules are only parsed if enabled.
+        assert_tokens("---", vec![Token::Word("---")]);
+    }
 }
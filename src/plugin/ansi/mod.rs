@@ -8,8 +8,8 @@
 //! text itself. This documentation does not aim to provide a full specification of all the ANSI
 //! escape codes, only describes the supported subset.
 //!
-//! > *Note:* if `embedded-text` fails to parse an escape sequence, it will ignore the `\x1b` character
-//! and display the rest as normal text.
+//! > *Note:* unsupported and malformed `\x1b[` sequences are ignored. If the `\x1b` character
+//! does not start such a sequence, it will be displayed as normal text.
 //!
 //! All escape sequences start with the `\x1b[` sequence, where `\x1b` is the ASCII `escape`
 //! character. `embedded-text` supports a subset of the `SGR` parameters, which are numeric codes
@@ -82,12 +82,24 @@
 //! The following SGR sequences are supported:
 //!
 //!  * `\x1b[0m`: Reset everything
+//!  * `\x1b[1m`: Bold text, using the bold font set by [`TextBox::set_font_variants`]
+//!  * `\x1b[22m`: Turn off bold text
+//!  * `\x1b[3m`: Italic text, using the italic font set by [`TextBox::set_font_variants`]
+//!  * `\x1b[23m`: Turn off italic text
 //!  * `\x1b[4m`: Underlined text
 //!  * `\x1b[24m`: Turn off text underline
+//!  * `\x1b[7m`: Swap the text and background colors
+//!  * `\x1b[27m`: Turn off swapping the text and background colors
 //!  * `\x1b[9m`: Crossed out/strikethrough text
 //!  * `\x1b[29m`: Turn off strikethrough
 //!  * `\x1b[39m`: Reset text color
 //!  * `\x1b[49m`: Reset background color
+//!  * `\x1b[10m`: Select the character style of the text box
+//!  * `\x1b[11m` - `\x1b[19m`: Select an alternative character style set by
+//!    [`TextBox::set_fonts`]. `\x1b[11m` selects the first one.
+//!  * `\x1b[73m`: Superscript text
+//!  * `\x1b[74m`: Subscript text
+//!  * `\x1b[75m`: Turn off superscript and subscript
 //!
 //! Reset style options to default
 //! ------------------------------
@@ -96,7 +108,8 @@
 //! `Default background color` (`\x1b[49m`) codes. These codes can be used to reset colors to
 //! *transparent* (i.e. no pixels drawn for text or background).
 //!
-//! In addition, `Reset all` turns off the underlined and crossed out styles.
+//! In addition, `Reset all` turns off the underlined, crossed out and reversed styles, and returns
+//! superscript and subscript text to the baseline.
 //!
 //! Other supported ANSI escape codes
 //! ---------------------------------
@@ -110,13 +123,19 @@
 //!    avoid this, make sure to reset the background color before moving the cursor!
 //!  - Move the cursor backward `<n>` characters: `\x1b[<n>D`. This command will stop at the start
 //!    of line.
+//!
+//! [`TextBox::set_fonts`]: crate::TextBox::set_fonts
+//! [`TextBox::set_font_variants`]: crate::TextBox::set_font_variants
 
 use ansi_parser::AnsiSequence;
 use embedded_graphics::{pixelcolor::Rgb888, prelude::PixelColor};
 
 use crate::{
     parser::Token,
-    plugin::{ansi::utils::try_parse_sgr, Plugin},
+    plugin::{
+        ansi::utils::{skip_malformed_sequence, try_parse_sgr},
+        Plugin,
+    },
 };
 
 mod utils;
@@ -135,7 +154,23 @@ impl<C: PixelColor> Ansi<'_, C> {
     }
 }
 
+impl<'a, C: PixelColor + From<Rgb888>> Ansi<'a, C> {
+    /// Ignores an escape sequence and continues with the text after it.
+    fn skip_sequence(
+        &mut self,
+        string: &'a str,
+        next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if !string.is_empty() {
+            self.carry = Some(Token::Word(string));
+        }
+
+        self.next_token(next_token)
+    }
+}
+
 impl<'a, C: PixelColor + From<Rgb888>> Plugin<'a, C> for Ansi<'a, C> {
+    #[inline]
     fn next_token(
         &mut self,
         mut next_token: impl FnMut() -> Option<Token<'a, C>>,
@@ -167,22 +202,26 @@ impl<'a, C: PixelColor + From<Rgb888>> Plugin<'a, C> for Ansi<'a, C> {
                                     draw_background: true,
                                 }
                             }
-                            AnsiSequence::SetGraphicsMode(sgr) => try_parse_sgr(&sgr)
-                                .map(|sgr| {
+                            AnsiSequence::SetGraphicsMode(sgr) => match try_parse_sgr(&sgr) {
+                                Some(sgr) => {
                                     self.carry = Some(Token::Word(string));
                                     Token::ChangeTextStyle(sgr.into())
-                                })
-                                .or_else(|| self.next_token(next_token))?,
+                                }
+                                None => self.skip_sequence(string, next_token)?,
+                            },
 
-                            _ => self.next_token(next_token)?,
+                            _ => self.skip_sequence(string, next_token)?,
                         };
 
                         Some(new_token)
                     }
-                    Err(_) => {
-                        self.carry = Some(Token::Word(chars.as_str()));
-                        Some(Token::Word("\u{1b}"))
-                    }
+                    Err(_) => match skip_malformed_sequence(text) {
+                        Some(string) => self.skip_sequence(string, next_token),
+                        None => {
+                            self.carry = Some(Token::Word(chars.as_str()));
+                            Some(Token::Word("\u{1b}"))
+                        }
+                    },
                 },
 
                 Some((idx, _)) => {
@@ -210,28 +249,30 @@ mod test {
     use embedded_graphics::{
         mock_display::MockDisplay,
         mono_font::{
-            ascii::{FONT_6X10, FONT_6X9},
+            ascii::{FONT_6X10, FONT_6X13, FONT_6X13_BOLD, FONT_6X9, FONT_9X15},
             MonoTextStyle, MonoTextStyleBuilder,
         },
         pixelcolor::{BinaryColor, Rgb888},
         prelude::{Point, Size},
         primitives::Rectangle,
+        text::Baseline,
         Drawable,
     };
 
     use crate::{
         alignment::{HorizontalAlignment, VerticalAlignment},
         parser::{ChangeTextStyle, Parser},
-        plugin::{ansi::Ansi, PluginWrapper},
+        plugin::{ansi::Ansi, tail::Tail, PluginWrapper},
         rendering::{
             cursor::LineCursor,
+            fonts::{Fonts, StyleStack, StyleState},
             line::{LineRenderState, StyledLineRenderer},
             line_iter::{
                 test::{assert_line_elements, RenderElement},
                 LineEndType,
             },
         },
-        style::{HeightMode, TabSize, TextBoxStyleBuilder},
+        style::{FontVariants, HeightMode, TabSize, TextBoxStyleBuilder},
         utils::test::size_for,
         TextBox,
     };
@@ -253,7 +294,8 @@ mod test {
         let mut plugin = PluginWrapper::new(Ansi::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            Fonts::single(&character_style),
+            &mut StyleState::new(),
             &mut text,
             5 * FONT_6X9.character_size.width,
         );
@@ -266,13 +308,37 @@ mod test {
         let mut plugin = PluginWrapper::new(Ansi::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            Fonts::single(&character_style),
+            &mut StyleState::new(),
             &mut text,
             5 * FONT_6X9.character_size.width,
         );
         assert_eq!(lm.width, 4 * FONT_6X9.character_size.width);
     }
 
+    #[test]
+    fn widow_with_style_change_in_last_word() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new().avoid_widows(true).build();
+
+        // "ddd" doesn't fit after "cc", so "cc" is moved to the next line.
+        let mut text = Parser::parse("aa bb cc d\x1b[4mdd");
+
+        let mut plugin = PluginWrapper::new(Ansi::new());
+        let lm = style.measure_line(
+            &mut plugin,
+            Fonts::single(&character_style),
+            &mut StyleState::new(),
+            &mut text,
+            10 * FONT_6X9.character_size.width,
+        );
+        assert_eq!(lm.width, 5 * FONT_6X9.character_size.width);
+    }
+
     #[test]
     fn colors() {
         let mut parser = Parser::parse("Lorem \x1b[92mIpsum");
@@ -293,6 +359,46 @@ mod test {
         );
     }
 
+    #[test]
+    fn eight_bit_and_true_colors() {
+        let mut parser = Parser::parse("\x1b[38;5;196ma\x1b[48;2;1;2;3mb");
+        let mw = PluginWrapper::new(Ansi::<Rgb888>::new());
+
+        assert_line_elements(
+            &mut parser,
+            100,
+            &[
+                RenderElement::ChangeTextStyle(ChangeTextStyle::TextColor(Some(Rgb888::new(
+                    255, 0, 0,
+                )))),
+                RenderElement::string("a", 6),
+                RenderElement::ChangeTextStyle(ChangeTextStyle::BackgroundColor(Some(
+                    Rgb888::new(1, 2, 3),
+                ))),
+                RenderElement::string("b", 6),
+            ],
+            &mw,
+        );
+    }
+
+    #[test]
+    fn malformed_sequences_are_ignored() {
+        let mut parser = Parser::parse("a\x1b[38;5mb\x1b[999mc\x1b[38;2;1m\x1b[12\u{e9}d");
+        let mw = PluginWrapper::new(Ansi::<Rgb888>::new());
+
+        assert_line_elements(
+            &mut parser,
+            100,
+            &[
+                RenderElement::string("a", 6),
+                RenderElement::string("b", 6),
+                RenderElement::string("c", 6),
+                RenderElement::string("\u{e9}d", 12),
+            ],
+            &mw,
+        );
+    }
+
     #[test]
     fn ansi_code_does_not_break_word() {
         let mut parser = Parser::parse("Lorem foo\x1b[92mbarum");
@@ -322,6 +428,35 @@ mod test {
         );
     }
 
+    #[test]
+    fn word_split_by_ansi_code_is_wrapped_as_a_whole() {
+        let mut parser = Parser::parse("Lorem a\x1b[92mbc");
+        let mw = PluginWrapper::new(Ansi::<Rgb888>::new());
+
+        assert_line_elements(
+            &mut parser,
+            8,
+            &[
+                RenderElement::string("Lorem", 30),
+                RenderElement::Space(1, false),
+            ],
+            &mw,
+        );
+
+        assert_line_elements(
+            &mut parser,
+            8,
+            &[
+                RenderElement::string("a", 6),
+                RenderElement::ChangeTextStyle(ChangeTextStyle::TextColor(Some(Rgb888::new(
+                    22, 198, 12,
+                )))),
+                RenderElement::string("bc", 12),
+            ],
+            &mw,
+        );
+    }
+
     #[test]
     fn ansi_cursor_backwards() {
         let mut display = MockDisplay::new();
@@ -339,20 +474,26 @@ mod test {
 
         let cursor = LineCursor::new(
             size_for(&FONT_6X9, 7, 1).width,
-            TabSize::Spaces(4).into_pixels(&text_renderer),
+            TabSize::Spaces(4).into_tab_stops(&text_renderer),
         );
 
         let plugin = PluginWrapper::new(Ansi::new());
         let mut state = LineRenderState {
             parser,
             text_renderer,
+            fonts: Fonts::single(&text_renderer),
+            style_state: StyleState::new(),
+            saved_styles: StyleStack::new(),
             end_type: LineEndType::EndOfText,
             plugin: &plugin,
         };
+        let measurement = state.measure_line(&style, cursor.line_width());
         StyledLineRenderer {
             cursor,
             state: &mut state,
             style: &style,
+            measurement,
+            truncate: false,
         }
         .draw(&mut display)
         .unwrap();
@@ -397,6 +538,32 @@ mod test {
         assert_eq!(3 * 9, tb.bounds.size.height);
     }
 
+    #[test]
+    fn tuple_of_plugins_renders_like_chain() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2));
+        let text = "word \x1b[4mword2\x1b[24m word3 word4";
+
+        let mut chained = MockDisplay::new();
+        TextBox::new(text, bounding_box, character_style)
+            .add_plugin(Ansi::new())
+            .add_plugin(Tail)
+            .draw(&mut chained)
+            .unwrap();
+
+        let mut tuple = MockDisplay::new();
+        TextBox::new(text, bounding_box, character_style)
+            .add_plugin((Ansi::new(), Tail))
+            .draw(&mut tuple)
+            .unwrap();
+
+        tuple.assert_eq(&chained);
+    }
+
     #[test]
     fn no_panic_when_word_is_broken() {
         let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
@@ -443,4 +610,181 @@ mod test {
             "############                                    ",
         ]);
     }
+
+    #[test]
+    fn mixed_fonts_line_height() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let fonts = [MonoTextStyle::new(&FONT_9X15, BinaryColor::On)];
+        let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 0));
+
+        let mut text_box = TextBox::with_height_mode(
+            "a\x1b[11mB\n\x1b[10mc d",
+            bounding_box,
+            character_style,
+            HeightMode::FitToText,
+        )
+        .add_plugin(Ansi::new());
+        text_box.set_fonts(&fonts);
+
+        assert_eq!(text_box.bounds.size.height, 15 + 9);
+    }
+
+    #[test]
+    fn mixed_fonts_alphabetic_baseline() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let fonts = [MonoTextStyle::new(&FONT_9X15, BinaryColor::On)];
+
+        TextBox::with_textbox_style(
+            "a\x1b[11mB\x1b[10mc\nd",
+            Rectangle::new(Point::zero(), Size::new(30, 30)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .baseline(Baseline::Alphabetic)
+                .build(),
+        )
+        .add_plugin(Ansi::new())
+        .set_fonts(&fonts)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                    ",
+            "                    ",
+            "       #####        ",
+            "       #    #       ",
+            "       #     #      ",
+            "       #    #       ",
+            "       #####        ",
+            "       #    #       ",
+            "  ###  #     #   ###",
+            " #  #  #     #  #   ",
+            " #  #  #    #   #   ",
+            "  ###  #####     ###",
+            "                    ",
+            "                    ",
+            "                    ",
+            "                    ",
+            "    #               ",
+            "    #               ",
+            "  ###               ",
+            " #  #               ",
+            " #  #               ",
+            "  ###               ",
+        ]);
+    }
+
+    #[test]
+    fn superscript_and_subscript() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::new(
+            "x\x1b[73m2\x1b[75my\x1b[74m3\x1b[0mz",
+            Rectangle::new(Point::zero(), Size::new(30, 20)),
+            character_style,
+        )
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                             ",
+            "        ##                   ",
+            "       #  #                  ",
+            "          #                  ",
+            "         #                   ",
+            "        #                    ",
+            " #  #  ####  #  #        ....",
+            "  ##         #  #  ####    . ",
+            "  ##         #  #    #    .  ",
+            " #  #         ###   ##   ....",
+            "             #  #     #      ",
+            "              ##      #      ",
+            "                   ###       ",
+        ]);
+    }
+
+    #[test]
+    fn superscript_increases_line_height() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 0));
+
+        let text_box = TextBox::with_height_mode(
+            "x\x1b[73m2\x1b[75m\nx\x1b[74m2",
+            bounding_box,
+            character_style,
+            HeightMode::FitToText,
+        )
+        .add_plugin(Ansi::new());
+
+        assert_eq!(text_box.bounds.size.height, (9 + 3) + (9 + 3));
+    }
+
+    #[test]
+    fn bold_font_variant() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyle::new(&FONT_6X13, BinaryColor::On);
+        let bold = MonoTextStyle::new(&FONT_6X13_BOLD, BinaryColor::Off);
+
+        let mut text_box = TextBox::new(
+            "l\x1b[1ml\x1b[22ml\x1b[1ml\x1b[0ml",
+            Rectangle::new(Point::zero(), Size::new(30, 13)),
+            character_style,
+        )
+        .add_plugin(Ansi::new());
+        text_box.set_font_variants(FontVariants {
+            bold: Some(&bold),
+            ..FontVariants::new()
+        });
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "                            ",
+            "                            ",
+            " ##    ###   ##    ###   .. ",
+            "  #     ##    #     ##    . ",
+            "  #     ##    #     ##    . ",
+            "  #     ##    #     ##    . ",
+            "  #     ##    #     ##    . ",
+            "  #     ##    #     ##    . ",
+            "  #     ##    #     ##    . ",
+            "  #     ##    #     ##    . ",
+            " ###   ####  ###   ####  ...",
+        ]);
+    }
+
+    #[test]
+    fn reverse_video() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "\x1b[7mERR\x1b[27m ok",
+            Rectangle::new(Point::zero(), Size::new(36, 9)),
+            character_style,
+        )
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "##################                 ",
+            "#    ##   ###   ##             #   ",
+            "# ##### ## ## ## #             #   ",
+            "#   ### ## ## ## #        ##   # # ",
+            "# #####   ###   ##       #  #  ##  ",
+            "# ##### ## ## ## #       #  #  # # ",
+            "#    ## ## ## ## #        ##   #  #",
+            "##################                 ",
+            "##################                 ",
+        ]);
+    }
 }
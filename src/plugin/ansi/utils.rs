@@ -10,12 +10,30 @@ pub(crate) enum Sgr {
     /// Reset all styling options
     Reset,
 
+    /// Bold text
+    Bold,
+
+    /// Italic text
+    Italic,
+
+    /// Disable bold text
+    NotBold,
+
+    /// Disable italic text
+    NotItalic,
+
     /// Draw a line under the text
     Underline,
 
     /// Cross out the text
     CrossedOut,
 
+    /// Swap the text and background colors
+    ReverseVideo,
+
+    /// Disable swapping the text and background colors
+    NotReversed,
+
     /// Disable drawing underline
     UnderlineOff,
 
@@ -33,6 +51,21 @@ pub(crate) enum Sgr {
 
     /// Reset the background color to transparent
     DefaultBackgroundColor,
+
+    /// Select the primary font
+    PrimaryFont,
+
+    /// Select an alternative font
+    AlternativeFont(u8),
+
+    /// Render superscript text
+    Superscript,
+
+    /// Render subscript text
+    Subscript,
+
+    /// Render neither superscript nor subscript text
+    NotSuperOrSubscript,
 }
 
 impl<C: PixelColor + From<Rgb888>> From<Sgr> for ChangeTextStyle<C> {
@@ -40,14 +73,25 @@ impl<C: PixelColor + From<Rgb888>> From<Sgr> for ChangeTextStyle<C> {
     fn from(sgr: Sgr) -> Self {
         match sgr {
             Sgr::Reset => ChangeTextStyle::Reset,
+            Sgr::Bold => ChangeTextStyle::Bold(true),
+            Sgr::Italic => ChangeTextStyle::Italic(true),
+            Sgr::NotBold => ChangeTextStyle::Bold(false),
+            Sgr::NotItalic => ChangeTextStyle::Italic(false),
             Sgr::Underline => ChangeTextStyle::Underline(DecorationColor::TextColor),
             Sgr::CrossedOut => ChangeTextStyle::Strikethrough(DecorationColor::TextColor),
+            Sgr::ReverseVideo => ChangeTextStyle::ReverseVideo(true),
+            Sgr::NotReversed => ChangeTextStyle::ReverseVideo(false),
             Sgr::UnderlineOff => ChangeTextStyle::Underline(DecorationColor::None),
             Sgr::NotCrossedOut => ChangeTextStyle::Strikethrough(DecorationColor::None),
             Sgr::ChangeTextColor(c) => ChangeTextStyle::TextColor(Some(c.into())),
             Sgr::DefaultTextColor => ChangeTextStyle::TextColor(None),
             Sgr::ChangeBackgroundColor(c) => ChangeTextStyle::BackgroundColor(Some(c.into())),
             Sgr::DefaultBackgroundColor => ChangeTextStyle::BackgroundColor(None),
+            Sgr::PrimaryFont => ChangeTextStyle::Font(None),
+            Sgr::AlternativeFont(n) => ChangeTextStyle::Font(Some(n as usize)),
+            Sgr::Superscript => ChangeTextStyle::Superscript(true),
+            Sgr::Subscript => ChangeTextStyle::Subscript(true),
+            Sgr::NotSuperOrSubscript => ChangeTextStyle::Superscript(false),
         }
     }
 }
@@ -127,18 +171,50 @@ fn try_parse_color(v: &[u8]) -> Option<Rgb888> {
     }
 }
 
+/// Returns the text after a malformed control sequence, or `None` if `text` doesn't start with
+/// a control sequence.
+///
+/// The sequence ends with its final byte, at the first character that can't be part of the
+/// sequence, or at the end of the text.
+pub(crate) fn skip_malformed_sequence(text: &str) -> Option<&str> {
+    let sequence = text.strip_prefix("\u{1b}[")?;
+
+    for (idx, c) in sequence.char_indices() {
+        match c {
+            // Parameter and intermediate bytes
+            '\u{20}'..='\u{3f}' => {}
+            // Final byte
+            '\u{40}'..='\u{7e}' => return Some(&sequence[idx + 1..]),
+            _ => return Some(&sequence[idx..]),
+        }
+    }
+
+    Some("")
+}
+
 /// Parse a set of SGR parameter numbers into a more convenient type
 #[inline]
 pub(crate) fn try_parse_sgr(v: &[u8]) -> Option<Sgr> {
     let code = *v.first()?;
     match code {
         0 => Some(Sgr::Reset),
+        1 => Some(Sgr::Bold),
+        3 => Some(Sgr::Italic),
         4 => Some(Sgr::Underline),
+        7 => Some(Sgr::ReverseVideo),
         9 => Some(Sgr::CrossedOut),
+        10 => Some(Sgr::PrimaryFont),
+        11..=19 => Some(Sgr::AlternativeFont(code - 11)),
+        22 => Some(Sgr::NotBold),
+        23 => Some(Sgr::NotItalic),
         24 => Some(Sgr::UnderlineOff),
+        27 => Some(Sgr::NotReversed),
         29 => Some(Sgr::NotCrossedOut),
         39 => Some(Sgr::DefaultTextColor),
         49 => Some(Sgr::DefaultBackgroundColor),
+        73 => Some(Sgr::Superscript),
+        74 => Some(Sgr::Subscript),
+        75 => Some(Sgr::NotSuperOrSubscript),
         30..=37 => Some(Sgr::ChangeTextColor(standard_to_rgb(code - 30))),
         38 => {
             let color = try_parse_color(&v[1..])?;
@@ -0,0 +1,267 @@
+//! Draw an insertion caret.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::{PixelColor, Size},
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::Token,
+    plugin::{highlight::ByteTracker, Plugin},
+    rendering::{
+        cursor::Cursor,
+        position::{CaretPosition, ElementObserver},
+        TextBoxProperties,
+    },
+    style::TextDirection,
+};
+
+/// Insertion caret plugin.
+///
+/// Draws a 1 pixel wide vertical line in front of the character at a [byte offset], over the
+/// whole height of the line. The caret is placed at the same position that is returned by
+/// [`TextBox::caret_position`].
+///
+/// To make the caret blink, toggle its visibility using [`Caret::with_visibility`] each time the
+/// text box is drawn, e.g. from a timer.
+///
+/// [byte offset]: crate::plugin#byte-ranges
+/// [`TextBox::caret_position`]: crate::TextBox::caret_position()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let blink_on = true;
+/// use embedded_text::{plugin::caret::Caret, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// // Place the caret after "Hello".
+/// let text_box = TextBox::new("Hello, World!", bounding_box, character_style)
+///     .add_plugin(Caret::new(5, BinaryColor::On).with_visibility(blink_on));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Caret<'a, C> {
+    offset: usize,
+    color: C,
+    visible: bool,
+
+    /// Finds the position of the caret while the text is rendered.
+    position: CaretPosition,
+
+    /// Tracks the byte offsets of the rendered text.
+    bytes: ByteTracker<'a>,
+
+    /// The byte offset following the last rendered piece of text.
+    rendered_end: usize,
+}
+
+impl<'a, C: PixelColor> Caret<'a, C> {
+    /// Creates a new, visible caret plugin.
+    ///
+    /// `offset` is the byte offset of the character the caret is placed in front of, `color` is
+    /// the color of the caret.
+    #[inline]
+    #[must_use]
+    pub const fn new(offset: usize, color: C) -> Self {
+        Self {
+            offset,
+            color,
+            visible: true,
+            position: CaretPosition::new(offset, TextDirection::LeftToRight),
+            bytes: ByteTracker::NEW,
+            rendered_end: 0,
+        }
+    }
+
+    /// Sets whether the caret is drawn.
+    #[inline]
+    #[must_use]
+    pub const fn with_visibility(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for Caret<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = next_token();
+        self.bytes.next_token(&token);
+        token
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        _draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if !self.visible {
+            return Ok(());
+        }
+
+        match text {
+            Some(st) => {
+                let (offset, source_len) = match self.bytes.offset_of(st) {
+                    Some(offset) => (offset, st.len()),
+
+                    // Control characters are reported using their own, static strings.
+                    None if matches!(st, "\n" | "\r" | "\t") => (self.rendered_end, st.len()),
+
+                    None => (self.rendered_end, 0),
+                };
+
+                self.position
+                    .element(character_style, st, offset, source_len, bounds);
+                self.rendered_end = offset + source_len;
+            }
+            None => self.position.end_of_text(self.rendered_end, bounds),
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.position = CaretPosition::new(self.offset, props.box_style.text_direction);
+        self.bytes.start_render(props.box_style);
+        self.rendered_end = 0;
+    }
+
+    #[inline]
+    fn post_render_box<D>(
+        &mut self,
+        draw_target: &mut D,
+        _bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        match self.position.result() {
+            Some(caret) if self.visible => draw_target.fill_solid(
+                &Rectangle::new(caret.top_left, Size::new(1, caret.size.height)),
+                self.color,
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{plugin::caret::Caret, utils::test::size_for, TextBox};
+
+    #[track_caller]
+    fn assert_caret(text: &str, caret: Caret<'_, BinaryColor>, size: Size, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::new(text, Rectangle::new(Point::zero(), size), character_style)
+            .add_plugin(caret)
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn caret_is_drawn_before_character() {
+        assert_caret(
+            "ab\ncd",
+            Caret::new(4, BinaryColor::On),
+            size_for(&FONT_6X9, 3, 2),
+            &[
+                "           ",
+                "       #   ",
+                "       #   ",
+                "  ###  ### ",
+                " #  #  #  #",
+                " #  #  #  #",
+                "  ###  ### ",
+                "           ",
+                "           ",
+                "      #    ",
+                "      #   #",
+                "      #   #",
+                "  ### # ###",
+                " #    ##  #",
+                " #    ##  #",
+                "  ### # ###",
+                "      #    ",
+                "      #    ",
+            ],
+        );
+    }
+
+    #[test]
+    fn caret_at_end_of_text() {
+        assert_caret(
+            "ab",
+            Caret::new(2, BinaryColor::On),
+            size_for(&FONT_6X9, 3, 1),
+            &[
+                "            #",
+                "       #    #",
+                "       #    #",
+                "  ###  ###  #",
+                " #  #  #  # #",
+                " #  #  #  # #",
+                "  ###  ###  #",
+                "            #",
+                "            #",
+            ],
+        );
+    }
+
+    #[test]
+    fn hidden_caret_is_not_drawn() {
+        assert_caret(
+            "ab",
+            Caret::new(1, BinaryColor::On).with_visibility(false),
+            size_for(&FONT_6X9, 3, 1),
+            &[
+                "           ",
+                "       #   ",
+                "       #   ",
+                "  ###  ### ",
+                " #  #  #  #",
+                " #  #  #  #",
+                "  ###  ### ",
+            ],
+        );
+    }
+}
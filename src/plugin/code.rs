@@ -0,0 +1,451 @@
+//! Draw text enclosed in backticks as inline code.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::{ChangeTextStyle, Token},
+    plugin::{
+        highlight::{draw_highlight, TextLayout},
+        queue::TokenQueue,
+        Plugin,
+    },
+    rendering::{cursor::Cursor, TextBoxProperties},
+};
+
+/// The number of tokens a code span may be split into, including its closing backtick.
+const SPAN_TOKENS: usize = 12;
+
+/// Returns the byte offset of the first backtick of `word` that is not part of a run of backticks.
+fn find_delimiter(word: &str) -> Option<usize> {
+    let bytes = word.as_bytes();
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+
+        let run = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+        if run == 1 {
+            return Some(i);
+        }
+        i += run;
+    }
+
+    None
+}
+
+/// Inline code plugin.
+///
+/// Removes the backticks from the text and draws the text between them as code, like `` `this` ``
+/// in Markdown. The background of the code is filled with a color, and the code can optionally
+/// be drawn in a different font, see [`InlineCode::with_font`]. The text style is saved at the
+/// start of a span and restored at its end.
+///
+/// Code spans end at the end of their paragraph. Backticks that are not closed in the same
+/// paragraph, or only after more than 12 words and spaces, are drawn as literal text, and so are
+/// runs of more than one backtick.
+///
+/// The closing backtick is found by reading ahead in the text. If the text box is full before a
+/// span ends, the text returned by [`TextBox::draw`] may start after the span.
+///
+/// [`TextBox::draw`]: crate::TextBox
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::code::InlineCode, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("Press `OK` to continue", bounding_box, character_style)
+///     .add_plugin(InlineCode::new(Rgb565::CSS_DARK_SLATE_GRAY));
+/// ```
+#[derive(Clone, Debug)]
+pub struct InlineCode<'a, C: PixelColor> {
+    color: C,
+    font: Option<usize>,
+
+    /// The tokens of the current code span, returned before the rest of the text.
+    queue: TokenQueue<'a, C, { SPAN_TOKENS + 4 }>,
+
+    /// The number of queued tokens that are part of the code.
+    code_tokens: usize,
+
+    /// The rest of a word that was split at a backtick.
+    carry: Option<Token<'a, C>>,
+
+    /// True if the last returned token is part of the code.
+    in_code: bool,
+
+    /// The layout of the rendered text.
+    layout: TextLayout,
+}
+
+impl<C: PixelColor> InlineCode<'_, C> {
+    /// Creates a new inline code plugin.
+    ///
+    /// `color` is the background color of the code.
+    #[inline]
+    #[must_use]
+    pub const fn new(color: C) -> Self {
+        Self {
+            color,
+            font: None,
+            queue: TokenQueue::new(),
+            code_tokens: 0,
+            carry: None,
+            in_code: false,
+            layout: TextLayout::DEFAULT,
+        }
+    }
+
+    /// Draws the code using an alternative character style.
+    ///
+    /// `index` selects a character style set by [`TextBox::set_fonts`], e.g. a monospace font.
+    ///
+    /// [`TextBox::set_fonts`]: crate::TextBox::set_fonts()
+    #[inline]
+    #[must_use]
+    pub const fn with_font(mut self, index: usize) -> Self {
+        self.font = Some(index);
+        self
+    }
+}
+
+impl<'a, C: PixelColor> InlineCode<'a, C> {
+    /// Looks for the end of a code span that starts with `rest`, the text after its opening
+    /// backtick.
+    ///
+    /// If the span is closed, its tokens are queued and the token that opens it is returned.
+    /// Otherwise the tokens that were read are queued as they are, and the word that contains the
+    /// opening backtick is returned.
+    fn open_span(
+        &mut self,
+        word: &'a str,
+        rest: &'a str,
+        next_token: &mut impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Token<'a, C> {
+        let mut span = TokenQueue::<'a, C, SPAN_TOKENS>::new();
+        let mut close = None;
+
+        // A span that closes in its first word only contains the text between the backticks.
+        let mut code_start = rest;
+        if let Some(idx) = find_delimiter(rest) {
+            close = Some((rest, idx));
+            code_start = "";
+        } else {
+            while span.len() < SPAN_TOKENS {
+                match next_token() {
+                    Some(Token::Word(w)) => match find_delimiter(w) {
+                        Some(idx) => {
+                            close = Some((w, idx));
+                            break;
+                        }
+                        None => span.push(Token::Word(w)).ok(),
+                    },
+                    Some(token @ Token::NewLine) => {
+                        span.push(token).ok();
+                        break;
+                    }
+                    Some(token) => span.push(token).ok(),
+                    None => break,
+                };
+            }
+        }
+
+        let (closing_word, idx) = match close {
+            Some(close) => close,
+            None => {
+                // The queue is empty, so there is room for every token that was read.
+                debug_assert!(self.queue.is_empty());
+                while let Some(token) = span.pop() {
+                    self.queue.push(token).ok();
+                }
+                return Token::Word(word);
+            }
+        };
+
+        let tokens = self
+            .font
+            .map(|index| Token::ChangeTextStyle(ChangeTextStyle::Font(Some(index))))
+            .into_iter()
+            .chain(Some(Token::Word(code_start)))
+            .chain(core::iter::from_fn(|| span.pop()))
+            .chain(Some(Token::Word(&closing_word[..idx])))
+            .filter(|token| !matches!(token, Token::Word("")));
+        for token in tokens {
+            self.queue.push(token).ok();
+        }
+        self.code_tokens = self.queue.len();
+        self.queue
+            .push(Token::ChangeTextStyle(ChangeTextStyle::PopStyle))
+            .ok();
+
+        let after = &closing_word[idx + 1..];
+        if !after.is_empty() {
+            self.carry = Some(Token::Word(after));
+        }
+
+        Token::ChangeTextStyle(ChangeTextStyle::PushStyle)
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for InlineCode<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if let Some(token) = self.queue.pop() {
+            self.in_code = self.code_tokens > 0;
+            self.code_tokens = self.code_tokens.saturating_sub(1);
+            return Some(token);
+        }
+
+        self.in_code = false;
+        let token = match self.carry.take() {
+            Some(token) => Some(token),
+            None => next_token(),
+        };
+
+        let word = match token {
+            Some(Token::Word(word)) => word,
+            _ => return token,
+        };
+
+        match find_delimiter(word) {
+            Some(0) => Some(self.open_span(word, &word[1..], &mut next_token)),
+            Some(idx) => {
+                // Return the text before the backtick first.
+                self.carry = Some(Token::Word(&word[idx..]));
+                Some(Token::Word(&word[..idx]))
+            }
+            None => Some(Token::Word(word)),
+        }
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        // Drop the rest of a span that wasn't drawn by the previous render.
+        self.queue.clear();
+        self.code_tokens = 0;
+        self.carry = None;
+        self.in_code = false;
+        self.layout = TextLayout::new(props.box_style);
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        match text {
+            Some(st) if self.in_code => draw_highlight(
+                draw_target,
+                character_style,
+                &self.layout,
+                st,
+                bounds,
+                0..st.len(),
+                self.color,
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{
+            ascii::{FONT_4X6, FONT_6X9},
+            MonoTextStyle, MonoTextStyleBuilder,
+        },
+        pixelcolor::Rgb888,
+        prelude::*,
+        primitives::Rectangle,
+        text::Baseline,
+    };
+
+    use crate::{
+        plugin::code::{find_delimiter, InlineCode},
+        style::TextBoxStyleBuilder,
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[test]
+    fn delimiters() {
+        assert_eq!(find_delimiter("`a"), Some(0));
+        assert_eq!(find_delimiter("a`"), Some(1));
+        assert_eq!(find_delimiter("``a`"), Some(3));
+        assert_eq!(find_delimiter("a``"), None);
+        assert_eq!(find_delimiter("a"), None);
+    }
+
+    #[test]
+    fn code_is_highlighted() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(Rgb888::WHITE)
+            .background_color(Rgb888::BLACK)
+            .build();
+
+        TextBox::new(
+            "a `b c`d",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 1)),
+            character_style,
+        )
+        .add_plugin(InlineCode::new(Rgb888::BLUE))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "KKKKKKKKKKKKBBBBBBBBBBBBBBBBBBKKKKKK",
+            "KKKKKKKKKKKKBWBBBBBBBBBBBBBBBBKKKKWK",
+            "KKKKKKKKKKKKBWBBBBBBBBBBBBBBBBKKKKWK",
+            "KKWWWKKKKKKKBWWWBBBBBBBBBBWWWBKKWWWK",
+            "KWKKWKKKKKKKBWBBWBBBBBBBBWBBBBKWKKWK",
+            "KWKKWKKKKKKKBWBBWBBBBBBBBWBBBBKWKKWK",
+            "KKWWWKKKKKKKBWWWBBBBBBBBBBWWWBKKWWWK",
+            "KKKKKKKKKKKKBBBBBBBBBBBBBBBBBBKKKKKK",
+            "KKKKKKKKKKKKBBBBBBBBBBBBBBBBBBKKKKKK",
+        ]);
+    }
+
+    #[test]
+    fn code_font() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE);
+        let fonts = [MonoTextStyle::new(&FONT_4X6, Rgb888::WHITE)];
+
+        TextBox::new(
+            "a`b`c",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            character_style,
+        )
+        .add_plugin(InlineCode::new(Rgb888::BLUE).with_font(0))
+        .set_fonts(&fonts)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "      WBBB     ",
+            "      WWBB     ",
+            "      WBWB     ",
+            "  WWW WBWB  WWW",
+            " W  W WWBB W   ",
+            " W  W BBBB W   ",
+            "  WWW       WWW",
+        ]);
+    }
+
+    #[test]
+    fn code_font_on_alphabetic_baseline() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE);
+        let fonts = [MonoTextStyle::new(&FONT_4X6, Rgb888::WHITE)];
+
+        TextBox::with_textbox_style(
+            "a`b`c",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .baseline(Baseline::Alphabetic)
+                .build(),
+        )
+        .add_plugin(InlineCode::new(Rgb888::BLUE).with_font(0))
+        .set_fonts(&fonts)
+        .draw(&mut display)
+        .unwrap();
+
+        // The code stands on the baseline of the rest of the line.
+        display.assert_pattern(&[
+            "               ",
+            "               ",
+            "      WBBB     ",
+            "  WWW WWBB  WWW",
+            " W  W WBWB W   ",
+            " W  W WBWB W   ",
+            "  WWW WWBB  WWW",
+            "      BBBB     ",
+        ]);
+    }
+
+    #[test]
+    fn span_cut_off_by_the_previous_draw_is_dropped() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE);
+
+        let text_box = TextBox::new(
+            "a `b c d`",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+            character_style,
+        )
+        .add_plugin(InlineCode::new(Rgb888::BLUE));
+
+        let mut first = MockDisplay::new();
+        first.set_allow_overdraw(true);
+        text_box.draw(&mut first).unwrap();
+
+        let mut second = MockDisplay::new();
+        second.set_allow_overdraw(true);
+        text_box.draw(&mut second).unwrap();
+
+        second.assert_eq(&first);
+    }
+
+    #[test]
+    fn unclosed_and_nested_backticks_are_literal() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE);
+
+        for text in ["a `b", "``b``", "a `b\nc`", "`", "a`` b `"] {
+            let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2));
+
+            let mut expected = MockDisplay::new();
+            TextBox::new(text, bounds, character_style)
+                .draw(&mut expected)
+                .unwrap();
+
+            let mut display = MockDisplay::new();
+            TextBox::new(text, bounds, character_style)
+                .add_plugin(InlineCode::new(Rgb888::BLUE))
+                .draw(&mut display)
+                .unwrap();
+
+            assert_eq!(display, expected, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Underline the text that is being composed by an input method.
+
+use core::ops::Range;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::{PixelColor, Point, Size},
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::{Token, UnderlineStyle},
+    plugin::{
+        highlight::{x_range, ByteTracker},
+        Plugin,
+    },
+    rendering::{cursor::Cursor, line::underline_pixels, TextBoxProperties},
+};
+
+/// Input method composition plugin.
+///
+/// Underlines the text in a [byte range], e.g. the text that is being composed by an input method
+/// and is not yet committed. The underline is drawn along the bottom row of the line, like the
+/// underlines of text styles that aren't [`UnderlineStyle::Solid`]. Nothing is drawn if the range
+/// is empty.
+///
+/// [byte range]: crate::plugin#byte-ranges
+/// [`UnderlineStyle::Solid`]: crate::UnderlineStyle::Solid
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::composition::Composition, TextBox, UnderlineStyle};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// // "ni hao" is being composed.
+/// let text_box = TextBox::new("Say ni hao", bounding_box, character_style)
+///     .add_plugin(Composition::new(4..10, UnderlineStyle::Dashed, Rgb565::WHITE));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Composition<'a, C> {
+    range: Range<usize>,
+    underline: UnderlineStyle,
+    color: C,
+
+    /// Tracks the byte offsets of the rendered text.
+    bytes: ByteTracker<'a>,
+}
+
+impl<'a, C: PixelColor> Composition<'a, C> {
+    /// Creates a new composition plugin.
+    ///
+    /// `range` is the byte range of the composed text, `underline` and `color` are the style and
+    /// the color of its underline.
+    #[inline]
+    #[must_use]
+    pub const fn new(range: Range<usize>, underline: UnderlineStyle, color: C) -> Self {
+        Self {
+            range,
+            underline,
+            color,
+            bytes: ByteTracker::NEW,
+        }
+    }
+
+    /// Draws the underline below the byte range `range` of a rendered piece of text.
+    fn draw_underline<T, D>(
+        &self,
+        draw_target: &mut D,
+        character_style: &T,
+        st: &str,
+        bounds: Rectangle,
+        range: Range<usize>,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        let (left, right) = x_range(character_style, &self.bytes.layout, st, bounds, range);
+        let area = Rectangle::new(
+            bounds.top_left + Point::new(left as i32, 0),
+            Size::new(right - left, bounds.size.height),
+        );
+
+        draw_target.draw_iter(underline_pixels(self.underline, area, self.color))
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for Composition<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = next_token();
+        self.bytes.next_token(&token);
+        token
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.bytes.start_render(props.box_style);
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        let st = match text {
+            Some(st) if !self.range.is_empty() => st,
+            _ => return Ok(()),
+        };
+
+        if let Some(range) = self.bytes.overlap(st, &self.range) {
+            self.draw_underline(draw_target, character_style, st, bounds, range)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::ops::Range;
+
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{
+        parser::UnderlineStyle, plugin::composition::Composition, utils::test::size_for, TextBox,
+    };
+
+    #[track_caller]
+    fn assert_composed(text: &str, range: Range<usize>, size: Size, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::new(text, Rectangle::new(Point::zero(), size), character_style)
+            .add_plugin(Composition::new(
+                range,
+                UnderlineStyle::Dashed,
+                BinaryColor::Off,
+            ))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn composed_text_is_underlined() {
+        assert_composed(
+            "ab cd",
+            1..5,
+            size_for(&FONT_6X9, 5, 1),
+            &[
+                "                             ",
+                "       #                    #",
+                "       #                    #",
+                "  ###  ###          ###   ###",
+                " #  #  #  #        #     #  #",
+                " #  #  #  #        #     #  #",
+                "  ###  ###          ###   ###",
+                "                             ",
+                "      ....  ....  ....  .... ",
+            ],
+        );
+    }
+
+    #[test]
+    fn empty_composition_is_not_drawn() {
+        assert_composed(
+            "ab cd",
+            2..2,
+            size_for(&FONT_6X9, 5, 1),
+            &[
+                "                             ",
+                "       #                    #",
+                "       #                    #",
+                "  ###  ###          ###   ###",
+                " #  #  #  #        #     #  #",
+                " #  #  #  #        #     #  #",
+                "  ###  ###          ###   ###",
+            ],
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Replace control characters with a visible placeholder.
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{parser::Token, plugin::Plugin};
+
+/// Control character plugin.
+///
+/// Draws a replacement string in place of every control character that isn't handled by the
+/// text box, e.g. a null character in data read from a serial port. By default, control characters
+/// are replaced by the replacement character (`U+FFFD`), which most fonts that don't contain it
+/// draw using their own replacement glyph.
+///
+/// Line breaks, carriage returns, tabs and other whitespace characters are not replaced. To keep
+/// using ANSI escape sequences, add this plugin after the `Ansi` plugin, so that the escape
+/// sequences are processed before the remaining control characters are replaced.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::control::ControlCharacters, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("Received: \u{0}\u{3}", bounding_box, character_style)
+///     .add_plugin(ControlCharacters::new().with_replacement("?"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ControlCharacters<'a, C: PixelColor> {
+    replacement: &'a str,
+
+    /// The rest of a word after a control character.
+    carry: Option<Token<'a, C>>,
+}
+
+impl<'a, C: PixelColor> ControlCharacters<'a, C> {
+    /// Creates a new control character plugin.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            replacement: "\u{FFFD}",
+            carry: None,
+        }
+    }
+
+    /// Sets the string that is drawn in place of each control character.
+    #[inline]
+    #[must_use]
+    pub const fn with_replacement(mut self, replacement: &'a str) -> Self {
+        self.replacement = replacement;
+        self
+    }
+}
+
+impl<C: PixelColor> Default for ControlCharacters<'_, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for ControlCharacters<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = match self.carry.take() {
+            Some(token) => Some(token),
+            None => next_token(),
+        };
+
+        let word = match token {
+            Some(Token::Word(word)) => word,
+            _ => return token,
+        };
+
+        match word.char_indices().find(|(_, c)| c.is_control()) {
+            Some((0, c)) => {
+                let rest = &word[c.len_utf8()..];
+                if !rest.is_empty() {
+                    self.carry = Some(Token::Word(rest));
+                }
+
+                Some(Token::Word(self.replacement))
+            }
+            Some((idx, _)) => {
+                // Return the text before the control character first.
+                self.carry = Some(Token::Word(&word[idx..]));
+                Some(Token::Word(&word[..idx]))
+            }
+            None => Some(Token::Word(word)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::control::ControlCharacters, utils::test::size_for, TextBox};
+
+    #[test]
+    fn control_characters_are_replaced() {
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("a?b?? c\n?", bounding_box, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            "a\u{0}b\u{1b}\u{7f} c\n\u{3}",
+            bounding_box,
+            character_style,
+        )
+        .add_plugin(ControlCharacters::new().with_replacement("?"))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_eq(&expected);
+    }
+}
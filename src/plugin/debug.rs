@@ -0,0 +1,131 @@
+//! Draw the bounds of the rendered text for debugging.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    prelude::PixelColor,
+    primitives::{Primitive, PrimitiveStyleBuilder, Rectangle, StrokeAlignment},
+    text::{renderer::TextRenderer, Baseline},
+    Drawable,
+};
+
+use crate::{plugin::Plugin, utils::baseline_offset};
+
+/// Debug overlay plugin.
+///
+/// Draws an outline around every piece of text and whitespace after it is rendered, and a line
+/// along its baseline. This helps to find out where the text box places each piece of text, for
+/// example to see the widths of the spaces in justified text.
+///
+/// The overlay is drawn over the text and doesn't change the layout.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::debug::DebugOverlay, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("Hello, world!", bounding_box, character_style)
+///     .add_plugin(DebugOverlay::new(Rgb565::MAGENTA));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DebugOverlay<C> {
+    color: C,
+}
+
+impl<C: PixelColor> DebugOverlay<C> {
+    /// Creates a new debug overlay plugin.
+    ///
+    /// `color` is the color of the outlines and baselines.
+    #[inline]
+    #[must_use]
+    pub const fn new(color: C) -> Self {
+        Self { color }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for DebugOverlay<C> {
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if text.is_none() || bounds.size.width == 0 {
+            return Ok(());
+        }
+
+        bounds
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(self.color)
+                    .stroke_width(1)
+                    .stroke_alignment(StrokeAlignment::Inside)
+                    .build(),
+            )
+            .draw(draw_target)?;
+
+        let baseline = baseline_offset(character_style, Baseline::Alphabetic);
+        draw_target.fill_solid(
+            &Rectangle::new(
+                bounds.top_left + Point::new(0, baseline),
+                Size::new(bounds.size.width, 1),
+            ),
+            self.color,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::debug::DebugOverlay, utils::test::size_for, TextBox};
+
+    #[test]
+    fn pieces_of_text_are_outlined() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        TextBox::new(
+            "a b",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(DebugOverlay::new(BinaryColor::Off))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "..................",
+            ".    ..    ..#   .",
+            ".    ..    ..#   .",
+            ". ###..    ..### .",
+            ".#  #..    ..#  #.",
+            ".#  #..    ..#  #.",
+            "..................",
+            ".    ..    ..    .",
+            "..................",
+        ]);
+    }
+}
@@ -0,0 +1,138 @@
+//! Replace characters that the font can't draw.
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{parser::Token, plugin::Plugin};
+
+/// Glyph fallback plugin.
+///
+/// Calls a function with every character of the text. If the function returns a replacement, the
+/// replacement is drawn, and measured, in place of the character. Returning `None` draws the
+/// character as it is.
+///
+/// The text box can't know which characters a font contains, so the function decides which
+/// characters are missing. For example, the replacement can be a character that the font
+/// contains, like `?`, or a tofu box for fonts that have one.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::fallback::GlyphFallback, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// // The ASCII fonts don't contain other characters.
+/// let text_box = TextBox::new("Grüße", bounding_box, character_style).add_plugin(
+///     GlyphFallback::new(|c| match c {
+///         'ü' => Some("u"),
+///         'ß' => Some("ss"),
+///         c if !c.is_ascii() => Some("?"),
+///         _ => None,
+///     }),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct GlyphFallback<'a, C: PixelColor> {
+    fallback: fn(char) -> Option<&'a str>,
+
+    /// The rest of a word after a replaced character.
+    carry: Option<Token<'a, C>>,
+}
+
+impl<'a, C: PixelColor> GlyphFallback<'a, C> {
+    /// Creates a new glyph fallback plugin.
+    ///
+    /// `fallback` returns the replacement of a character, or `None` if the character is drawn.
+    #[inline]
+    #[must_use]
+    pub const fn new(fallback: fn(char) -> Option<&'a str>) -> Self {
+        Self {
+            fallback,
+            carry: None,
+        }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for GlyphFallback<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = match self.carry.take() {
+            Some(token) => Some(token),
+            None => next_token(),
+        };
+
+        let word = match token {
+            Some(Token::Word(word)) => word,
+            _ => return token,
+        };
+
+        let fallback = self.fallback;
+        let missing = word
+            .char_indices()
+            .find_map(|(idx, c)| fallback(c).map(|replacement| (idx, c, replacement)));
+
+        match missing {
+            Some((0, c, replacement)) => {
+                let rest = &word[c.len_utf8()..];
+                if !rest.is_empty() {
+                    self.carry = Some(Token::Word(rest));
+                }
+
+                Some(Token::Word(replacement))
+            }
+            Some((idx, _, _)) => {
+                // Return the text before the missing character first.
+                self.carry = Some(Token::Word(&word[idx..]));
+                Some(Token::Word(&word[..idx]))
+            }
+            None => Some(Token::Word(word)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::fallback::GlyphFallback, utils::test::size_for, TextBox};
+
+    #[test]
+    fn missing_characters_are_replaced() {
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 2));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("Grusse ?", bounding_box, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("Grüße \u{2603}", bounding_box, character_style)
+            .add_plugin(GlyphFallback::new(|c| match c {
+                'ü' => Some("u"),
+                'ß' => Some("ss"),
+                c if !c.is_ascii() => Some("?"),
+                _ => None,
+            }))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_eq(&expected);
+    }
+}
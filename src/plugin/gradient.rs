@@ -0,0 +1,237 @@
+//! Color the text with a horizontal gradient.
+
+use embedded_graphics::{
+    pixelcolor::{raw::RawData, Rgb888},
+    prelude::{PixelColor, RgbColor},
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::Token,
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+};
+
+/// Gradient plugin.
+///
+/// Draws every character with its own text color, interpolated between the start color at the
+/// left edge of the text box and the end color at its right edge.
+///
+/// Colors with only two states, like [`BinaryColor`], can't display a gradient. With these, the
+/// text is drawn using the text color of the character style.
+///
+/// If you wish to use a different color type, the types needs to implement `From<Rgb888>` and
+/// `Into<Rgb888>`.
+///
+/// [`BinaryColor`]: embedded_graphics::pixelcolor::BinaryColor
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::gradient::Gradient, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("Settings", bounding_box, character_style)
+///     .add_plugin(Gradient::new(Rgb565::RED, Rgb565::BLUE));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Gradient<'a, C: PixelColor> {
+    start: C,
+    end: C,
+
+    /// The horizontal extent of the text area.
+    left: i32,
+    width: u32,
+
+    /// The rest of a word after its first character.
+    carry: Option<Token<'a, C>>,
+}
+
+impl<C: PixelColor> Gradient<'_, C> {
+    /// Creates a new gradient plugin.
+    ///
+    /// `start` is the text color at the left edge of the text box, `end` is the text color at
+    /// its right edge.
+    #[inline]
+    #[must_use]
+    pub const fn new(start: C, end: C) -> Self {
+        Self {
+            start,
+            end,
+            left: 0,
+            width: 0,
+            carry: None,
+        }
+    }
+
+    /// Returns whether the color type can only display two colors.
+    fn is_monochrome() -> bool {
+        C::Raw::BITS_PER_PIXEL == 1
+    }
+}
+
+impl<C: PixelColor + Into<Rgb888>> Gradient<'_, C> {
+    /// Returns the text color at the given horizontal position.
+    fn color_at(&self, x: i32) -> Rgb888 {
+        let max = self.width.saturating_sub(1).max(1) as i32;
+        let pos = (x - self.left).clamp(0, max);
+
+        let start: Rgb888 = self.start.into();
+        let end: Rgb888 = self.end.into();
+        let mix = |a: u8, b: u8| (a as i32 + (b as i32 - a as i32) * pos / max) as u8;
+
+        Rgb888::new(
+            mix(start.r(), end.r()),
+            mix(start.g(), end.g()),
+            mix(start.b(), end.b()),
+        )
+    }
+}
+
+impl<'a, C> Plugin<'a, C> for Gradient<'a, C>
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if Self::is_monochrome() {
+            return next_token();
+        }
+
+        let token = match self.carry.take() {
+            Some(token) => Some(token),
+            None => next_token(),
+        };
+
+        // Split words into characters, so that every character is drawn with its own color.
+        if let Some(Token::Word(word)) = token {
+            let mut chars = word.chars();
+            chars.next();
+            let rest = chars.as_str();
+
+            if !rest.is_empty() {
+                self.carry = Some(Token::Word(rest));
+                return Some(Token::Word(&word[..word.len() - rest.len()]));
+            }
+        }
+
+        token
+    }
+
+    #[inline]
+    fn pre_render<T>(&mut self, character_style: &mut T, _text: &str, bounds: Rectangle)
+    where
+        T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+    {
+        if !Self::is_monochrome() {
+            let color = self.color_at(bounds.top_left.x);
+            character_style.set_text_color(Some(color.into()));
+        }
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.left = props.bounding_box.top_left.x;
+        self.width = props.bounding_box.size.width;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::{BinaryColor, Rgb888},
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::gradient::Gradient, utils::test::size_for, TextBox};
+
+    #[test]
+    fn characters_are_colored_by_their_position() {
+        let mut display = MockDisplay::new();
+
+        TextBox::new(
+            "||||",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+            MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE),
+        )
+        .add_plugin(Gradient::new(Rgb888::BLACK, Rgb888::new(230, 115, 0)))
+        .draw(&mut display)
+        .unwrap();
+
+        // The bar of the `|` character is drawn in the third column of the glyph.
+        for (x, color) in [
+            (2, Rgb888::new(0, 0, 0)),
+            (8, Rgb888::new(60, 30, 0)),
+            (14, Rgb888::new(120, 60, 0)),
+            (20, Rgb888::new(180, 90, 0)),
+        ] {
+            assert_eq!(display.get_pixel(Point::new(x, 4)), Some(color));
+        }
+    }
+
+    #[test]
+    fn words_are_wrapped_as_a_whole() {
+        let text = "ab cd e";
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 2));
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(
+            text,
+            bounding_box,
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            text,
+            bounding_box,
+            MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE),
+        )
+        .add_plugin(Gradient::new(Rgb888::RED, Rgb888::BLUE))
+        .draw(&mut display)
+        .unwrap();
+
+        display.map(|_| BinaryColor::On).assert_eq(&expected);
+    }
+
+    #[test]
+    fn binary_color_keeps_the_text_color() {
+        let text = "ab c";
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(text, bounding_box, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(text, bounding_box, character_style)
+            .add_plugin(Gradient::new(BinaryColor::Off, BinaryColor::Off))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_eq(&expected);
+    }
+}
@@ -0,0 +1,261 @@
+//! Drawing highlighted pieces of text.
+//!
+//! Also contains the byte offset tracking shared by the plugins that refer to the text using byte
+//! ranges.
+
+use core::ops::Range;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::{renderer::TextRenderer, Baseline},
+    Pixel,
+};
+
+use crate::{
+    parser::Token,
+    style::{TextBoxStyle, TextDirection},
+    utils::{
+        background_color, baseline_offset, cluster_base, clusters, offset_in, str_width,
+        str_width_with_spacing,
+    },
+};
+
+/// Draw target that replaces a color with another one.
+struct ReplaceColor<'d, D: DrawTarget> {
+    target: &'d mut D,
+    from: Option<D::Color>,
+    to: D::Color,
+}
+
+impl<D: DrawTarget> Dimensions for ReplaceColor<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for ReplaceColor<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (from, to) = (self.from, self.to);
+        self.target
+            .draw_iter(pixels.into_iter().map(|Pixel(p, color)| {
+                if Some(color) == from {
+                    Pixel(p, to)
+                } else {
+                    Pixel(p, color)
+                }
+            }))
+    }
+}
+
+/// The settings of a text box style that determine where the characters of a piece of text are
+/// drawn.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct TextLayout {
+    /// The space between the characters of a word.
+    pub(crate) letter_spacing: i32,
+
+    /// The direction of the text. Right-to-left pieces of text are drawn mirrored.
+    pub(crate) text_direction: TextDirection,
+
+    /// The baseline used to draw the characters.
+    pub(crate) baseline: Baseline,
+}
+
+impl TextLayout {
+    /// The layout of text drawn using the default text box style.
+    pub(crate) const DEFAULT: Self = Self {
+        letter_spacing: 0,
+        text_direction: TextDirection::LeftToRight,
+        baseline: Baseline::Top,
+    };
+
+    /// Returns the layout of text drawn using `style`.
+    pub(crate) const fn new(style: &TextBoxStyle) -> Self {
+        Self {
+            letter_spacing: style.letter_spacing,
+            text_direction: style.text_direction,
+            baseline: style.baseline,
+        }
+    }
+}
+
+/// Returns the text of a token, if it has any, and the number of bytes it was parsed from.
+pub(crate) fn token_source<'a, C: PixelColor>(token: &Option<Token<'a, C>>) -> (&'a str, usize) {
+    match *token {
+        Some(Token::Word(st)) | Some(Token::Whitespace(_, st)) => (st, st.len()),
+        Some(Token::NewLine) | Some(Token::CarriageReturn) | Some(Token::Tab) => ("", 1),
+        // Soft hyphens are the only characters that produce a Break token.
+        Some(Token::Break(_)) => ("", '\u{ad}'.len_utf8()),
+        Some(Token::HorizontalRule(rule)) => ("", rule.len()),
+        _ => ("", 0),
+    }
+}
+
+/// Tracks the byte offsets of the rendered pieces of text.
+///
+/// The tracker counts the bytes of the tokens as they are processed, so it must see every token
+/// of the original text.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ByteTracker<'a> {
+    /// The text of the current token, if it has any.
+    token: &'a str,
+
+    /// The byte offset of the current token.
+    token_start: usize,
+
+    /// The byte offset of the next token.
+    next_token_start: usize,
+
+    /// The layout of the rendered text.
+    pub(crate) layout: TextLayout,
+}
+
+impl<'a> ByteTracker<'a> {
+    /// A tracker at the start of the text.
+    pub(crate) const NEW: Self = Self {
+        token: "",
+        token_start: 0,
+        next_token_start: 0,
+        layout: TextLayout::DEFAULT,
+    };
+
+    /// Restarts counting at the start of the text rendered using `style`.
+    pub(crate) fn start_render(&mut self, style: &TextBoxStyle) {
+        *self = Self {
+            layout: TextLayout::new(style),
+            ..Self::NEW
+        };
+    }
+
+    /// Counts the bytes of the next token.
+    pub(crate) fn next_token<C: PixelColor>(&mut self, token: &Option<Token<'a, C>>) {
+        let (text, len) = token_source(token);
+
+        self.token = text;
+        self.token_start = self.next_token_start;
+        self.next_token_start += len;
+    }
+
+    /// Returns the byte offset of a rendered piece of text, if it is part of the current token.
+    pub(crate) fn offset_of(&self, st: &str) -> Option<usize> {
+        offset_in(self.token, st).map(|offset| self.token_start + offset)
+    }
+
+    /// Returns the part of a rendered piece of text that is inside the byte range `range` of the
+    /// text, relative to the start of the piece.
+    pub(crate) fn overlap(&self, st: &str, range: &Range<usize>) -> Option<Range<usize>> {
+        match self.offset_of(st) {
+            Some(start) => {
+                let end = start + st.len();
+
+                let overlap_start = start.max(range.start);
+                let overlap_end = end.min(range.end);
+                (overlap_start < overlap_end).then(|| overlap_start - start..overlap_end - start)
+            }
+
+            // Strings that are not part of the text represent the whole token.
+            None if range.contains(&self.token_start) => Some(0..st.len()),
+
+            None => None,
+        }
+    }
+}
+
+/// Returns the horizontal extent of the bytes `range` of a rendered piece of text, relative to the
+/// left edge of `bounds`.
+///
+/// The characters are measured the same way the line renderer places them. If the text was
+/// stretched to fill `bounds`, e.g. by justification, the extent is scaled to the width of
+/// `bounds`.
+pub(crate) fn x_range<T: TextRenderer>(
+    character_style: &T,
+    layout: &TextLayout,
+    st: &str,
+    bounds: Rectangle,
+    range: Range<usize>,
+) -> (u32, u32) {
+    let measure = |st: &str| str_width_with_spacing(character_style, st, layout.letter_spacing);
+
+    // The first character of the range starts where the text up to and including it ends, minus
+    // its width.
+    let left = match clusters(&st[range.start..range.end]).next() {
+        Some((_, c)) => measure(&st[..range.start + c.len()]) - str_width(character_style, c),
+        None => measure(&st[..range.start]),
+    };
+    let right = measure(&st[..range.end]).max(left);
+
+    let width = bounds.size.width;
+    let measured_width = measure(st);
+    let (left, right) = if measured_width == 0 || measured_width == width {
+        (left, right)
+    } else {
+        let scale = |x: u32| (u64::from(x) * u64::from(width) / u64::from(measured_width)) as u32;
+        (scale(left), scale(right))
+    };
+
+    match layout.text_direction {
+        TextDirection::LeftToRight => (left, right),
+        TextDirection::RightToLeft => (width.saturating_sub(right), width.saturating_sub(left)),
+    }
+}
+
+/// Redraws a part of a rendered piece of text with a highlighted background.
+///
+/// `st` is the text that was rendered inside `bounds`, `range` is the byte range of the highlighted
+/// part of `st`.
+pub(crate) fn draw_highlight<T, D>(
+    draw_target: &mut D,
+    character_style: &T,
+    layout: &TextLayout,
+    st: &str,
+    bounds: Rectangle,
+    range: Range<usize>,
+    color: T::Color,
+) -> Result<(), D::Error>
+where
+    T: TextRenderer,
+    D: DrawTarget<Color = T::Color>,
+{
+    let (left, right) = x_range(character_style, layout, st, bounds, range.clone());
+    let area = Rectangle::new(
+        bounds.top_left + Point::new(left as i32, 0),
+        Size::new(right - left, bounds.size.height),
+    );
+    draw_target.fill_solid(&area, color)?;
+
+    // Find the background color of the text so that it can be replaced.
+    let mut target = ReplaceColor {
+        target: draw_target,
+        from: background_color(character_style),
+        to: color,
+    };
+    // The characters are drawn at the same position as the rest of the line.
+    let top = bounds.top_left.y + baseline_offset(character_style, layout.baseline);
+    let start = range.start;
+    for (idx, c) in clusters(&st[range]) {
+        if c.chars().all(char::is_whitespace) {
+            // Whitespace (and control characters) are only represented by their background.
+            continue;
+        }
+
+        let idx = start + idx;
+        let (left, _) = x_range(character_style, layout, st, bounds, idx..idx + c.len());
+        character_style.draw_string(
+            cluster_base(c),
+            Point::new(bounds.top_left.x + left as i32, top),
+            layout.baseline,
+            &mut target,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+//! Highlight a single line of the text.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+    style::{LineEndType, LineMeasurement},
+    utils::text_color,
+};
+
+/// Line highlight plugin.
+///
+/// Fills the background of one line with a color, e.g. to show the selected item of a menu. The
+/// highlight spans the whole width of the text area. The text of the highlighted line can be drawn
+/// in a different color, see [`LineHighlight::with_text_color`].
+///
+/// Lines are counted from the first line of the text, including wrapped lines and the lines that
+/// are scrolled out of view by [`TextBox::set_vertical_offset`]. Lines that are continued after a
+/// carriage return are not counted separately.
+///
+/// Text that is drawn with a background color covers the highlight.
+///
+/// [`TextBox::set_vertical_offset`]: crate::TextBox::set_vertical_offset()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::linehighlight::LineHighlight, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// // Highlight "Settings" with reversed colors.
+/// let text_box = TextBox::new("Start\nSettings\nExit", bounding_box, character_style)
+///     .add_plugin(LineHighlight::new(1, BinaryColor::On).with_text_color(BinaryColor::Off));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LineHighlight<C> {
+    selected: usize,
+    color: C,
+    text_color: Option<C>,
+
+    /// The index of the current line, and of the line that is started next.
+    line: usize,
+    next_line: usize,
+
+    /// True if the highlight of the current line has been drawn.
+    drawn: bool,
+
+    /// The text color to restore after the highlighted line.
+    saved_text_color: Option<Option<C>>,
+}
+
+impl<C: PixelColor> LineHighlight<C> {
+    /// Creates a new line highlight plugin.
+    ///
+    /// `line` is the index of the highlighted line, starting from 0, and `color` is the color of
+    /// the highlight.
+    #[inline]
+    #[must_use]
+    pub const fn new(line: usize, color: C) -> Self {
+        Self {
+            selected: line,
+            color,
+            text_color: None,
+            line: 0,
+            next_line: 0,
+            drawn: false,
+            saved_text_color: None,
+        }
+    }
+
+    /// Draws the text of the highlighted line using the given color.
+    #[inline]
+    #[must_use]
+    pub const fn with_text_color(mut self, color: C) -> Self {
+        self.text_color = Some(color);
+        self
+    }
+
+    /// Returns whether the current line is highlighted.
+    const fn is_selected(&self) -> bool {
+        self.line == self.selected
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for LineHighlight<C> {
+    #[inline]
+    fn new_line(&mut self) {
+        if self.line != self.next_line {
+            self.line = self.next_line;
+            self.drawn = false;
+        }
+    }
+
+    #[inline]
+    fn line_metrics(&mut self, metrics: &LineMeasurement) {
+        if metrics.line_end_type() != LineEndType::CarriageReturn {
+            self.next_line = self.line + 1;
+        }
+    }
+
+    #[inline]
+    fn post_line_start<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // Don't draw over the text of a line that is continued after a carriage return.
+        if !self.is_selected() || self.drawn {
+            return Ok(());
+        }
+        self.drawn = true;
+
+        draw_target.fill_solid(&bounds, self.color)
+    }
+
+    #[inline]
+    fn pre_render<T>(&mut self, character_style: &mut T, _text: &str, _bounds: Rectangle)
+    where
+        T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+    {
+        match self.text_color {
+            Some(color) if self.is_selected() => {
+                if self.saved_text_color.is_none() {
+                    self.saved_text_color = Some(text_color(character_style));
+                }
+                character_style.set_text_color(Some(color));
+            }
+            _ => {
+                if let Some(color) = self.saved_text_color.take() {
+                    character_style.set_text_color(color);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        self.line = 0;
+        self.next_line = 0;
+        self.drawn = false;
+        self.saved_text_color = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::linehighlight::LineHighlight, utils::test::size_for, TextBox};
+
+    #[test]
+    fn selected_line_is_highlighted() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        TextBox::new(
+            "a\nb\rc\nd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 3)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(LineHighlight::new(1, BinaryColor::On).with_text_color(BinaryColor::Off))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "            ",
+            "            ",
+            "            ",
+            "  ###       ",
+            " #  #       ",
+            " #  #       ",
+            "  ###       ",
+            "            ",
+            "            ",
+            "############",
+            "#.##########",
+            "#.##########",
+            "#....#######",
+            "#.##.#######",
+            "#.##.#######",
+            "#....#######",
+            "############",
+            "############",
+            "            ",
+            "    #       ",
+            "    #       ",
+            "  ###       ",
+            " #  #       ",
+            " #  #       ",
+            "  ###       ",
+        ]);
+    }
+
+    #[test]
+    fn scrolled_lines_are_counted() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let mut text_box = TextBox::new(
+            "a\nb\nc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 2)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        );
+        text_box.set_vertical_offset(-9);
+
+        text_box
+            .add_plugin(LineHighlight::new(2, BinaryColor::Off))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(&[
+            "            ",
+            " #          ",
+            " #          ",
+            " ###        ",
+            " #  #       ",
+            " #  #       ",
+            " ###        ",
+            "            ",
+            "            ",
+            "............",
+            "............",
+            "............",
+            "..###.......",
+            ".#..........",
+            ".#..........",
+            "..###.......",
+            "............",
+            "............",
+        ]);
+    }
+}
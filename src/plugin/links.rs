@@ -0,0 +1,403 @@
+//! Find links in the text and record where they are drawn.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Point,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        DecorationColor,
+    },
+};
+
+use crate::{
+    parser::{ChangeTextStyle, Token},
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+    utils::offset_in,
+};
+
+/// The beginnings of the words that are detected as links.
+const URL_PREFIXES: [&str; 3] = ["http://", "https://", "www."];
+
+/// Characters that end a sentence, and are not part of a link at the end of a word.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', '\'', '"'];
+
+/// A link found in the rendered text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinkRegion<'a> {
+    /// The URL of the link.
+    pub url: &'a str,
+
+    /// The area the link, or a part of it, was drawn to.
+    pub bounds: Rectangle,
+}
+
+impl LinkRegion<'_> {
+    const EMPTY: Self = Self {
+        url: "",
+        bounds: Rectangle::zero(),
+    };
+}
+
+/// Link detector plugin.
+///
+/// Finds URLs in the text, i.e. words that start with `http://`, `https://` or `www.`, and
+/// records the areas they are drawn to. Links that are broken into multiple lines are recorded as
+/// multiple regions with the same URL. The regions can be read after drawing the text box, using
+/// [`TextBox::plugins`], to find the link under a touch with [`LinkDetector::link_at`].
+///
+/// At most `N` regions are recorded. Links can optionally be drawn in a different color and
+/// underlined, see [`LinkDetector::with_link_color`].
+///
+/// [`TextBox::plugins`]: crate::TextBox::plugins()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+/// # display.set_allow_overdraw(true);
+/// use embedded_text::{plugin::links::LinkDetector, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("Docs: www.example.com", bounding_box, character_style)
+///     .add_plugin(LinkDetector::<_, 4>::new().with_link_color(Rgb565::CYAN));
+/// text_box.draw(&mut display)?;
+///
+/// let links = text_box.plugins().object;
+/// assert_eq!(links.link_at(Point::new(10, 12)), Some("www.example.com"));
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct LinkDetector<'a, C, const N: usize> {
+    regions: [LinkRegion<'a>; N],
+    region_count: usize,
+
+    link_color: Option<C>,
+
+    /// The last URL found in the text.
+    url: Option<&'a str>,
+
+    /// The rest of a word that was split at a link.
+    carry: Option<&'a str>,
+
+    /// Tokens to be returned before reading the next token.
+    queue: [Option<Token<'a, C>>; 4],
+}
+
+impl<'a, C: PixelColor, const N: usize> LinkDetector<'a, C, N> {
+    /// Creates a new link detector plugin.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            regions: [LinkRegion::EMPTY; N],
+            region_count: 0,
+            link_color: None,
+            url: None,
+            carry: None,
+            queue: [(); 4].map(|_| None),
+        }
+    }
+
+    /// Draws the links using the given text color, underlined.
+    #[inline]
+    #[must_use]
+    pub fn with_link_color(self, color: C) -> Self {
+        Self {
+            link_color: Some(color),
+            ..self
+        }
+    }
+
+    /// Returns the regions of the links found in the last drawn text.
+    #[inline]
+    pub fn links(&self) -> &[LinkRegion<'a>] {
+        &self.regions[..self.region_count]
+    }
+
+    /// Returns the URL of the link drawn at the given point, if any.
+    #[inline]
+    pub fn link_at(&self, point: Point) -> Option<&'a str> {
+        self.links()
+            .iter()
+            .find(|region| region.bounds.contains(point))
+            .map(|region| region.url)
+    }
+
+    /// Records the area a part of the current link was drawn to.
+    fn add_region(&mut self, url: &'a str, bounds: Rectangle) {
+        // Merge the pieces of a link that are drawn next to each other.
+        if let Some(last) = self.links().last() {
+            let adjacent = last.bounds.top_left.y == bounds.top_left.y
+                && last.bounds.size.height == bounds.size.height
+                && last.bounds.top_left.x + last.bounds.size.width as i32 == bounds.top_left.x;
+
+            if core::ptr::eq(last.url, url) && adjacent {
+                self.regions[self.region_count - 1].bounds.size.width += bounds.size.width;
+                return;
+            }
+        }
+
+        if let Some(region) = self.regions.get_mut(self.region_count) {
+            *region = LinkRegion { url, bounds };
+            self.region_count += 1;
+        }
+    }
+
+    /// Splits a link off the start of `word`, and returns the tokens that draw it.
+    fn link_tokens(&mut self, word: &'a str) -> Token<'a, C> {
+        let end = word.trim_end_matches(TRAILING_PUNCTUATION).len();
+        let (url, rest) = word.split_at(end);
+
+        self.url = Some(url);
+        if !rest.is_empty() {
+            self.carry = Some(rest);
+        }
+
+        match self.link_color {
+            Some(color) => {
+                self.queue = [
+                    Some(Token::ChangeTextStyle(ChangeTextStyle::TextColor(Some(
+                        color,
+                    )))),
+                    Some(Token::ChangeTextStyle(ChangeTextStyle::Underline(
+                        DecorationColor::TextColor,
+                    ))),
+                    Some(Token::Word(url)),
+                    Some(Token::ChangeTextStyle(ChangeTextStyle::PopStyle)),
+                ];
+
+                Token::ChangeTextStyle(ChangeTextStyle::PushStyle)
+            }
+            None => Token::Word(url),
+        }
+    }
+}
+
+impl<'a, C: PixelColor, const N: usize> Default for LinkDetector<'a, C, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns whether `st` starts with `prefix`, ignoring ASCII case.
+fn starts_with_ignore_case(st: &str, prefix: &str) -> bool {
+    st.len() >= prefix.len()
+        && st.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+/// Returns the byte offset of the first link in `word`.
+fn find_link(word: &str) -> Option<usize> {
+    word.char_indices().map(|(idx, _)| idx).find(|&idx| {
+        let rest = &word[idx..];
+        let url_len = rest.trim_end_matches(TRAILING_PUNCTUATION).len();
+
+        URL_PREFIXES
+            .iter()
+            .any(|prefix| url_len > prefix.len() && starts_with_ignore_case(rest, prefix))
+    })
+}
+
+impl<'a, C: PixelColor, const N: usize> Plugin<'a, C> for LinkDetector<'a, C, N> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if let Some(token) = self.queue.iter_mut().find_map(Option::take) {
+            return Some(token);
+        }
+
+        let token = match self.carry.take() {
+            Some(word) => Some(Token::Word(word)),
+            None => next_token(),
+        };
+
+        let word = match token {
+            Some(Token::Word(word)) => word,
+            _ => return token,
+        };
+
+        match find_link(word) {
+            Some(0) => Some(self.link_tokens(word)),
+            Some(idx) => {
+                // Return the text before the link first.
+                self.carry = Some(&word[idx..]);
+                Some(Token::Word(&word[..idx]))
+            }
+            None => Some(Token::Word(word)),
+        }
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        _draw_target: &mut D,
+        _character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if let (Some(text), Some(url)) = (text, self.url) {
+            if offset_in(url, text).is_some() && bounds.size.width > 0 {
+                self.add_region(url, bounds);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        self.region_count = 0;
+        self.url = None;
+        self.carry = None;
+        self.queue = [(); 4].map(|_| None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        plugin::links::{find_link, LinkDetector, LinkRegion},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[test]
+    fn links_are_found() {
+        assert_eq!(find_link("http://a"), Some(0));
+        assert_eq!(find_link("HTTPS://a.b"), Some(0));
+        assert_eq!(find_link("(www.a.b)"), Some(1));
+        assert_eq!(find_link("www."), None);
+        assert_eq!(find_link("http://."), None);
+        assert_eq!(find_link("example.com"), None);
+    }
+
+    #[test]
+    fn link_regions_are_recorded() {
+        let text_box = TextBox::new(
+            "see (www.ab.c), www.d.e",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 16, 2)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(LinkDetector::<_, 4>::new());
+
+        text_box.draw(&mut MockDisplay::new()).unwrap();
+
+        let links = text_box.plugins().object;
+        assert_eq!(
+            links.links(),
+            &[
+                LinkRegion {
+                    url: "www.ab.c",
+                    bounds: Rectangle::new(Point::new(5 * 6, 0), size_for(&FONT_6X9, 8, 1)),
+                },
+                LinkRegion {
+                    url: "www.d.e",
+                    bounds: Rectangle::new(Point::new(0, 9), size_for(&FONT_6X9, 7, 1)),
+                },
+            ]
+        );
+
+        assert_eq!(links.link_at(Point::new(31, 4)), Some("www.ab.c"));
+        assert_eq!(links.link_at(Point::new(1, 10)), Some("www.d.e"));
+        assert_eq!(links.link_at(Point::new(1, 1)), None);
+    }
+
+    #[test]
+    fn broken_link_has_multiple_regions() {
+        let text_box = TextBox::new(
+            "www.abcd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(LinkDetector::<_, 4>::new());
+
+        text_box.draw(&mut MockDisplay::new()).unwrap();
+
+        let links = text_box.plugins().object;
+        assert_eq!(
+            links.links(),
+            &[
+                LinkRegion {
+                    url: "www.abcd",
+                    bounds: Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+                },
+                LinkRegion {
+                    url: "www.abcd",
+                    bounds: Rectangle::new(Point::new(0, 9), size_for(&FONT_6X9, 3, 1)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn regions_that_do_not_fit_are_dropped() {
+        let text_box = TextBox::new(
+            "www.a.b www.c.d",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 20, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(LinkDetector::<_, 1>::new());
+
+        text_box.draw(&mut MockDisplay::new()).unwrap();
+
+        let links = text_box.plugins().object;
+        assert_eq!(links.links().len(), 1);
+        assert_eq!(links.links()[0].url, "www.a.b");
+    }
+
+    #[test]
+    fn links_are_styled() {
+        let mut display = MockDisplay::new();
+
+        TextBox::new(
+            "a www.b",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 8, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::Off),
+        )
+        .add_plugin(LinkDetector::<_, 1>::new().with_link_color(BinaryColor::On))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                                          ",
+            "                                     #    ",
+            "                                     #    ",
+            "  ...       #   # #   # #   #        ###  ",
+            " .  .       # # # # # # # # #        #  # ",
+            " .  .       # # # # # # # # #   ##   #  # ",
+            "  ...        # #   # #   # #    ##   ###  ",
+            "                                          ",
+            "            ##############################",
+        ]);
+    }
+}
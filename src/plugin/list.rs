@@ -0,0 +1,556 @@
+//! Lay out bulleted and numbered lists.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::{PixelColor, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Baseline,
+    },
+    Drawable,
+};
+
+use crate::{
+    parser::Token,
+    plugin::{queue::TokenQueue, Plugin},
+    rendering::{cursor::Cursor, TextBoxProperties},
+    utils::{baseline_offset, str_width},
+};
+
+/// The marker of a list item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Marker<'a> {
+    /// A bullet or a number.
+    Text(&'a str),
+
+    /// A checkbox, drawn by the plugin.
+    Checkbox,
+}
+
+/// The list item state of the current paragraph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ItemState<'a> {
+    /// At the start of a paragraph, after `spaces` spaces.
+    Start { spaces: u32 },
+
+    /// After a list marker, which needs to be followed by whitespace.
+    Marker { spaces: u32, marker: Marker<'a> },
+
+    /// Inside a list item.
+    Item { spaces: u32, marker: Marker<'a> },
+
+    /// Inside a paragraph that isn't a list item.
+    Text,
+}
+
+/// List layout plugin.
+///
+/// Detects paragraphs that start with a list marker followed by whitespace, and indents the
+/// wrapped lines of these paragraphs so that they line up with the text after the marker. The
+/// marker stays in a hanging gutter on the left.
+///
+/// The supported markers are `-`, `*` and numbers followed by a period, like `1.`. Whitespace
+/// before the marker is kept, so nested lists can be created by indenting them with spaces.
+///
+/// Checkboxes can be enabled using [`ListLayout::with_checkboxes`]. The `[ ]` and `[x]` markers
+/// are then replaced by an empty and a checked box, which are as tall as the capital letters of
+/// the font.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::list::ListLayout, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 60));
+///
+/// let text = "Steps:\n1. Open the lid\n2. Press the button\n  - Hold it for a second";
+/// let text_box =
+///     TextBox::new(text, bounding_box, character_style).add_plugin(ListLayout::new());
+/// ```
+///
+/// A to-do list:
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::list::ListLayout, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 60));
+///
+/// let text = "[x] Connect to Wi-Fi\n[ ] Set the time zone";
+/// let text_box = TextBox::new(text, bounding_box, character_style)
+///     .add_plugin(ListLayout::new().with_checkboxes(Rgb565::WHITE));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ListLayout<'a, C: PixelColor> {
+    state: ItemState<'a>,
+
+    /// The color of the checkboxes, if they are enabled.
+    checkbox_color: Option<C>,
+
+    /// The size of the checkboxes, for the character style of the current line.
+    checkbox_size: u32,
+
+    /// The tokens read while looking for an unchecked checkbox.
+    queue: TokenQueue<'a, C, 2>,
+}
+
+impl<C: PixelColor> ListLayout<'_, C> {
+    /// The id of the inline objects that are drawn as empty checkboxes.
+    ///
+    /// Plugins that draw their own inline objects should use different ids.
+    pub const UNCHECKED: u32 = u32::MAX - 1;
+
+    /// The id of the inline objects that are drawn as checked checkboxes.
+    ///
+    /// Plugins that draw their own inline objects should use different ids.
+    pub const CHECKED: u32 = u32::MAX;
+
+    /// Creates a new list layout plugin.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: ItemState::Start { spaces: 0 },
+            checkbox_color: None,
+            checkbox_size: 0,
+            queue: TokenQueue::new(),
+        }
+    }
+
+    /// Draws the `[ ]` and `[x]` list markers as checkboxes of the given color.
+    ///
+    /// The markers are replaced by inline objects, as large as the ascent of the font, with the ids
+    /// [`ListLayout::UNCHECKED`] and [`ListLayout::CHECKED`], and the plugin draws them. `[X]` is
+    /// also accepted as a checked checkbox.
+    #[inline]
+    #[must_use]
+    pub const fn with_checkboxes(mut self, color: C) -> Self {
+        self.checkbox_color = Some(color);
+        self
+    }
+}
+
+impl<C: PixelColor> Default for ListLayout<'_, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns whether `word` is a list marker.
+fn is_marker(word: &str) -> bool {
+    match word {
+        "-" | "*" => true,
+        _ => match word.strip_suffix('.') {
+            Some(number) => !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()),
+            None => false,
+        },
+    }
+}
+
+impl<'a, C: PixelColor> ListLayout<'a, C> {
+    /// Returns the next token, with checkbox markers at the start of a paragraph replaced by
+    /// inline objects.
+    fn read_token(
+        &mut self,
+        next_token: &mut impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if let Some(token) = self.queue.pop() {
+            return Some(token);
+        }
+
+        let token = next_token();
+        if self.checkbox_color.is_none() || !matches!(self.state, ItemState::Start { .. }) {
+            return token;
+        }
+
+        let id = match token {
+            Some(Token::Word("[x]" | "[X]")) => Self::CHECKED,
+            Some(Token::Word("[")) => {
+                // The space splits an empty checkbox into three tokens.
+                let space = next_token();
+                let close = match space {
+                    Some(Token::Whitespace(1, _)) => next_token(),
+                    _ => None,
+                };
+                if let Some(Token::Word("]")) = close {
+                    Self::UNCHECKED
+                } else {
+                    // The queue is empty, so there is room for both tokens.
+                    debug_assert!(self.queue.is_empty());
+                    for token in [space, close].into_iter().flatten() {
+                        self.queue.push(token).ok();
+                    }
+                    return token;
+                }
+            }
+            _ => return token,
+        };
+
+        let size = self.checkbox_size;
+        Some(Token::InlineObject {
+            id,
+            size: Size::new(size, size),
+        })
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for ListLayout<'a, C> {
+    #[inline]
+    fn line_indent<S: TextRenderer>(&mut self, character_style: &S) -> u32 {
+        self.checkbox_size = baseline_offset(character_style, Baseline::Alphabetic).max(3) as u32;
+
+        match self.state {
+            ItemState::Item { spaces, marker } => {
+                let marker_width = match marker {
+                    Marker::Text(marker) => str_width(character_style, marker),
+                    Marker::Checkbox => self.checkbox_size,
+                };
+                str_width(character_style, " ") * spaces + marker_width
+            }
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = self.read_token(&mut next_token);
+
+        self.state = match (self.state, &token) {
+            (_, Some(Token::NewLine)) => ItemState::Start { spaces: 0 },
+
+            // Style changes don't affect the structure of the text.
+            (state, Some(Token::ChangeTextStyle(_))) => state,
+
+            (ItemState::Start { spaces }, Some(Token::Whitespace(n, _))) => {
+                ItemState::Start { spaces: spaces + n }
+            }
+            (ItemState::Start { spaces }, Some(Token::Word(word))) if is_marker(word) => {
+                ItemState::Marker {
+                    spaces,
+                    marker: Marker::Text(word),
+                }
+            }
+            (ItemState::Start { spaces }, Some(Token::InlineObject { id, .. }))
+                if self.checkbox_color.is_some()
+                    && (*id == Self::UNCHECKED || *id == Self::CHECKED) =>
+            {
+                ItemState::Marker {
+                    spaces,
+                    marker: Marker::Checkbox,
+                }
+            }
+            (ItemState::Marker { spaces, marker }, Some(Token::Whitespace(n, _))) => {
+                ItemState::Item {
+                    spaces: spaces + n,
+                    marker,
+                }
+            }
+            (ItemState::Item { spaces, marker }, _) => ItemState::Item { spaces, marker },
+
+            _ => ItemState::Text,
+        };
+
+        token
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        // Drop the state of an item that wasn't finished by the previous render.
+        self.state = ItemState::Start { spaces: 0 };
+        self.queue.clear();
+    }
+
+    #[inline]
+    fn render_object<D>(
+        &mut self,
+        draw_target: &mut D,
+        id: u32,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let color = match self.checkbox_color {
+            Some(color) if id == Self::UNCHECKED || id == Self::CHECKED => color,
+            _ => return Ok(()),
+        };
+
+        bounds
+            .into_styled(PrimitiveStyle::with_stroke(color, 1))
+            .draw(draw_target)?;
+
+        if id == Self::CHECKED {
+            draw_target.fill_solid(&bounds.offset(-2), color)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        plugin::list::{is_marker, ListLayout},
+        style::{HeightMode, TextBoxStyleBuilder},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[test]
+    fn markers() {
+        for marker in ["-", "*", "1.", "23."] {
+            assert!(is_marker(marker), "{:?} should be a marker", marker);
+        }
+        for word in ["", ".", "a.", "1", "1.2", "--"] {
+            assert!(!is_marker(word), "{:?} should not be a marker", word);
+        }
+    }
+
+    #[test]
+    fn wrapped_lines_are_aligned_to_the_item_text() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "- a b\n1. c d\ne f",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 5)),
+            character_style,
+        )
+        .add_plugin(ListLayout::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                       ",
+            "                       ",
+            "                       ",
+            "              ###      ",
+            "#####        #  #      ",
+            "             #  #      ",
+            "              ###      ",
+            "                       ",
+            "                       ",
+            "                       ",
+            "             #         ",
+            "             #         ",
+            "             ###       ",
+            "             #  #      ",
+            "             #  #      ",
+            "             ###       ",
+            "                       ",
+            "                       ",
+            "                       ",
+            "  #                    ",
+            " ##                    ",
+            "  #                 ###",
+            "  #                #   ",
+            "  #     ##         #   ",
+            " ###    ##          ###",
+            "                       ",
+            "                       ",
+            "                       ",
+            "                      #",
+            "                      #",
+            "                    ###",
+            "                   #  #",
+            "                   #  #",
+            "                    ###",
+            "                       ",
+            "                       ",
+            "                       ",
+            "               #       ",
+            "              # #      ",
+            "  ##          #        ",
+            " # ##        ###       ",
+            " ##           #        ",
+            "  ###         #        ",
+        ]);
+    }
+
+    #[test]
+    fn item_cut_off_by_the_previous_draw_is_dropped() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new(
+            "- a b c",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+            character_style,
+        )
+        .add_plugin(ListLayout::new());
+
+        let mut first = MockDisplay::new();
+        text_box.draw(&mut first).unwrap();
+
+        let mut second = MockDisplay::new();
+        text_box.draw(&mut second).unwrap();
+
+        second.assert_eq(&first);
+    }
+
+    #[test]
+    fn nested_items_are_indented() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "  - a b",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2)),
+            character_style,
+        )
+        .add_plugin(ListLayout::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                             ",
+            "                             ",
+            "                             ",
+            "                          ###",
+            "            #####        #  #",
+            "                         #  #",
+            "                          ###",
+            "                             ",
+            "                             ",
+            "                             ",
+            "                         #   ",
+            "                         #   ",
+            "                         ### ",
+            "                         #  #",
+            "                         #  #",
+            "                         ### ",
+        ]);
+    }
+
+    #[test]
+    fn measured_height_includes_the_indentation() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "- aa bb cc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .build(),
+        )
+        .add_plugin(ListLayout::new());
+
+        assert_eq!(text_box.bounding_box().size, size_for(&FONT_6X9, 5, 3));
+    }
+
+    #[test]
+    fn checkboxes() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "[x] a b\n[ ] c",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 3)),
+            character_style,
+        )
+        .add_plugin(ListLayout::new().with_checkboxes(BinaryColor::On))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                 ",
+            "######           ",
+            "#    #           ",
+            "# ## #        ###",
+            "# ## #       #  #",
+            "#    #       #  #",
+            "######        ###",
+            "                 ",
+            "                 ",
+            "                 ",
+            "             #   ",
+            "             #   ",
+            "             ### ",
+            "             #  #",
+            "             #  #",
+            "             ### ",
+            "                 ",
+            "                 ",
+            "                 ",
+            "######           ",
+            "#    #           ",
+            "#    #        ###",
+            "#    #       #   ",
+            "#    #       #   ",
+            "######        ###",
+        ]);
+    }
+
+    #[test]
+    fn brackets_that_are_not_checkboxes_are_kept() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        for text in ["[ a]", "[  ]", "[", "a [x]", "[x]b"] {
+            let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1));
+
+            let mut expected = MockDisplay::new();
+            TextBox::new(text, bounds, character_style)
+                .draw(&mut expected)
+                .unwrap();
+
+            let mut display = MockDisplay::new();
+            TextBox::new(text, bounds, character_style)
+                .add_plugin(ListLayout::new().with_checkboxes(BinaryColor::On))
+                .draw(&mut display)
+                .unwrap();
+
+            assert_eq!(display, expected, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Highlight text enclosed in `==` marks.
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{
+    parser::{ChangeTextStyle, Token},
+    plugin::Plugin,
+};
+
+/// The string that opens and closes a marked span.
+const MARK: &str = "==";
+
+/// Mark highlight plugin.
+///
+/// Removes the `==` marks from the text and draws a background color behind the text between
+/// them, like `==this==` in some Markdown dialects. Marked spans may contain whitespace and line
+/// breaks. An unclosed span is highlighted until the end of the text.
+///
+/// The text style is saved at the start of a span and restored at its end, so style changes made
+/// by other plugins inside the span, e.g. ANSI escape sequences, don't leak out of it.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::mark::MarkHighlight, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("The ==quick brown== fox", bounding_box, character_style)
+///     .add_plugin(MarkHighlight::new(Rgb565::BLUE));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MarkHighlight<'a, C: PixelColor> {
+    color: C,
+
+    /// True if the current text is inside a marked span.
+    marked: bool,
+
+    /// True if the background color of a span that was just opened still needs to be set.
+    set_color: bool,
+
+    /// The rest of a word that was split at a mark.
+    carry: Option<Token<'a, C>>,
+}
+
+impl<C: PixelColor> MarkHighlight<'_, C> {
+    /// Creates a new mark highlight plugin.
+    ///
+    /// `color` is the background color of the marked text.
+    #[inline]
+    #[must_use]
+    pub const fn new(color: C) -> Self {
+        Self {
+            color,
+            marked: false,
+            set_color: false,
+            carry: None,
+        }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for MarkHighlight<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if self.set_color {
+            self.set_color = false;
+            return Some(Token::ChangeTextStyle(ChangeTextStyle::BackgroundColor(
+                Some(self.color),
+            )));
+        }
+
+        let token = match self.carry.take() {
+            Some(token) => Some(token),
+            None => next_token(),
+        };
+
+        let text = match token {
+            Some(Token::Word(text)) => text,
+            _ => return token,
+        };
+
+        match text.find(MARK) {
+            Some(0) => {
+                let rest = &text[MARK.len()..];
+                if !rest.is_empty() {
+                    self.carry = Some(Token::Word(rest));
+                }
+
+                self.marked = !self.marked;
+                let change = if self.marked {
+                    self.set_color = true;
+                    ChangeTextStyle::PushStyle
+                } else {
+                    ChangeTextStyle::PopStyle
+                };
+
+                Some(Token::ChangeTextStyle(change))
+            }
+            Some(idx) => {
+                // Return the text before the mark first.
+                self.carry = Some(Token::Word(&text[idx..]));
+                Some(Token::Word(&text[..idx]))
+            }
+            None => Some(Token::Word(text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::Rgb888,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::mark::MarkHighlight, utils::test::size_for, TextBox};
+
+    #[test]
+    fn marked_text_is_highlighted() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(Rgb888::WHITE)
+            .background_color(Rgb888::BLACK)
+            .build();
+
+        TextBox::new(
+            "a ==b c==d",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 1)),
+            character_style,
+        )
+        .add_plugin(MarkHighlight::new(Rgb888::BLUE))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "KKKKKKKKKKKKBBBBBBBBBBBBBBBBBBKKKKKK",
+            "KKKKKKKKKKKKBWBBBBBBBBBBBBBBBBKKKKWK",
+            "KKKKKKKKKKKKBWBBBBBBBBBBBBBBBBKKKKWK",
+            "KKWWWKKKKKKKBWWWBBBBBBBBBBWWWBKKWWWK",
+            "KWKKWKKKKKKKBWBBWBBBBBBBBWBBBBKWKKWK",
+            "KWKKWKKKKKKKBWBBWBBBBBBBBWBBBBKWKKWK",
+            "KKWWWKKKKKKKBWWWBBBBBBBBBBWWWBKKWWWK",
+            "KKKKKKKKKKKKBBBBBBBBBBBBBBBBBBKKKKKK",
+            "KKKKKKKKKKKKBBBBBBBBBBBBBBBBBBKKKKKK",
+        ]);
+    }
+
+    #[test]
+    fn unclosed_mark_highlights_the_rest_of_the_text() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(Rgb888::WHITE)
+            .background_color(Rgb888::BLACK)
+            .build();
+
+        TextBox::new(
+            "a==b\nc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 2)),
+            character_style,
+        )
+        .add_plugin(MarkHighlight::new(Rgb888::BLUE))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "KKKKKKBBBBBB",
+            "KKKKKKBWBBBB",
+            "KKKKKKBWBBBB",
+            "KKWWWKBWWWBB",
+            "KWKKWKBWBBWB",
+            "KWKKWKBWBBWB",
+            "KKWWWKBWWWBB",
+            "KKKKKKBBBBBB",
+            "KKKKKKBBBBBB",
+            "BBBBBB      ",
+            "BBBBBB      ",
+            "BBBBBB      ",
+            "BBWWWB      ",
+            "BWBBBB      ",
+            "BWBBBB      ",
+            "BBWWWB      ",
+            "BBBBBB      ",
+            "BBBBBB      ",
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "ansi")]
+    fn styles_inside_the_mark_are_restored() {
+        use crate::plugin::ansi::Ansi;
+
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(Rgb888::WHITE)
+            .background_color(Rgb888::BLACK)
+            .build();
+
+        TextBox::new(
+            "a==b\x1b[7mc==d",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+            character_style,
+        )
+        .add_plugin(MarkHighlight::new(Rgb888::BLUE))
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "KKKKKKBBBBBBWWWWWWKKKKKK",
+            "KKKKKKBWBBBBWWWWWWKKKKWK",
+            "KKKKKKBWBBBBWWWWWWKKKKWK",
+            "KKWWWKBWWWBBWWBBBWKKWWWK",
+            "KWKKWKBWBBWBWBWWWWKWKKWK",
+            "KWKKWKBWBBWBWBWWWWKWKKWK",
+            "KKWWWKBWWWBBWWBBBWKKWWWK",
+            "KKKKKKBBBBBBWWWWWWKKKKKK",
+            "KKKKKKBBBBBBWWWWWWKKKKKK",
+        ]);
+    }
+}
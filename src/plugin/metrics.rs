@@ -0,0 +1,228 @@
+//! Collect statistics about the rendered text.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+    style::LineMeasurement,
+};
+
+/// Metrics plugin.
+///
+/// Counts the glyphs, words and lines drawn by the text box, and measures the width of the drawn
+/// whitespace. The plugin only observes the rendering and doesn't change the layout.
+///
+/// The counters are reset when the text box starts drawing, and can be read after drawing using
+/// [`TextBox::plugins`] or [`TextBox::take_plugins`]. Only the lines that are rendered are counted.
+///
+/// [`TextBox::plugins`]: crate::TextBox::plugins()
+/// [`TextBox::take_plugins`]: crate::TextBox::take_plugins()
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// use embedded_text::{plugin::metrics::Metrics, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(100, 30));
+///
+/// let text_box =
+///     TextBox::new("Hello, world!", bounding_box, character_style).add_plugin(Metrics::new());
+/// text_box.draw(&mut display)?;
+///
+/// let metrics = text_box.plugins().object;
+/// assert_eq!(metrics.glyphs(), 12);
+/// assert_eq!(metrics.words(), 2);
+/// assert_eq!(metrics.lines(), 1);
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    glyphs: usize,
+    words: usize,
+    lines: usize,
+    whitespace_width: u32,
+
+    /// True if the last drawn piece of text was part of a word.
+    in_word: bool,
+}
+
+impl Metrics {
+    /// Creates a new metrics plugin.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            glyphs: 0,
+            words: 0,
+            lines: 0,
+            whitespace_width: 0,
+            in_word: false,
+        }
+    }
+
+    /// Returns the number of glyphs drawn.
+    ///
+    /// Whitespace characters are not counted.
+    #[inline]
+    #[must_use]
+    pub const fn glyphs(&self) -> usize {
+        self.glyphs
+    }
+
+    /// Returns the number of words drawn.
+    ///
+    /// Words that are broken into multiple lines are counted once.
+    #[inline]
+    #[must_use]
+    pub const fn words(&self) -> usize {
+        self.words
+    }
+
+    /// Returns the number of lines rendered.
+    #[inline]
+    #[must_use]
+    pub const fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Returns the total width of the drawn whitespace, in pixels.
+    #[inline]
+    #[must_use]
+    pub const fn whitespace_width(&self) -> u32 {
+        self.whitespace_width
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for Metrics {
+    #[inline]
+    fn line_metrics(&mut self, _metrics: &LineMeasurement) {
+        self.lines += 1;
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        _draw_target: &mut D,
+        _character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        let text = match text {
+            Some(text) => text,
+            None => return Ok(()),
+        };
+
+        if text.chars().all(char::is_whitespace) {
+            self.whitespace_width += bounds.size.width;
+            self.in_word = false;
+        } else {
+            self.glyphs += text.chars().filter(|c| !c.is_whitespace()).count();
+            if !self.in_word {
+                self.words += 1;
+                self.in_word = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        plugin::metrics::Metrics,
+        style::{HeightMode, VerticalOverdraw},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[test]
+    fn counts_are_collected() {
+        let text_box = TextBox::new(
+            "foo bar\nlongword x",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 4)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(Metrics::new());
+
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+
+        let metrics = text_box.plugins().object;
+        assert_eq!(metrics.glyphs(), 15);
+        assert_eq!(metrics.words(), 4);
+        assert_eq!(metrics.lines(), 4);
+        assert_eq!(metrics.whitespace_width(), 6);
+    }
+
+    #[test]
+    fn counts_are_reset_when_drawing_again() {
+        let text_box = TextBox::new(
+            "foo bar",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 7, 1)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+        )
+        .add_plugin(Metrics::new());
+
+        text_box.draw(&mut MockDisplay::new()).unwrap();
+        text_box.draw(&mut MockDisplay::new()).unwrap();
+
+        let metrics = text_box.plugins().object;
+        assert_eq!(metrics.glyphs(), 6);
+        assert_eq!(metrics.words(), 2);
+        assert_eq!(metrics.lines(), 1);
+    }
+
+    #[test]
+    fn only_rendered_lines_are_counted() {
+        let text_box = TextBox::with_height_mode(
+            "a\nb\nc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 1, 2)),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+            HeightMode::Exact(VerticalOverdraw::FullRowsOnly),
+        )
+        .add_plugin(Metrics::new());
+
+        text_box.draw(&mut MockDisplay::new()).unwrap();
+
+        let metrics = text_box.plugins().object;
+        assert_eq!(metrics.glyphs(), 2);
+        assert_eq!(metrics.lines(), 2);
+    }
+}
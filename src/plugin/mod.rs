@@ -2,6 +2,13 @@
 //!
 //! Note: Custom plugins are experimental. If you wish to implement custom plugins,
 //! you need to activate the `plugin` feature.
+//!
+//! # Byte ranges
+//!
+//! Some plugins, like [`selection::SelectionHighlight`] and [`caret::Caret`], refer to parts of
+//! the text using byte offsets. These plugins count the bytes of the text as it is processed. If
+//! the text box uses other plugins that remove parts of the text, like the ANSI plugin, add these
+//! plugins first so that they can see the original text.
 
 use core::{
     cell::UnsafeCell,
@@ -11,14 +18,16 @@ use core::{
 };
 use embedded_graphics::{
     draw_target::DrawTarget,
-    prelude::PixelColor,
+    prelude::{PixelColor, Point},
     primitives::Rectangle,
     text::renderer::{CharacterStyle, TextRenderer},
 };
 
 use crate::{
+    alignment::SpaceConfig,
     parser::{Parser, Token},
     rendering::{cursor::Cursor, TextBoxProperties},
+    style::{LineEndType, LineMeasurement},
 };
 
 #[cfg(feature = "plugin")]
@@ -27,13 +36,37 @@ pub mod private;
 pub use private::Plugin;
 
 #[cfg(not(feature = "plugin"))]
-mod private;
+pub(crate) mod private;
 #[cfg(not(feature = "plugin"))]
 use private::Plugin;
 
 #[cfg(feature = "ansi")]
 pub mod ansi;
+pub mod caret;
+pub mod code;
+pub mod composition;
+pub mod control;
+pub mod debug;
+pub mod fallback;
+pub mod gradient;
+pub(crate) mod highlight;
+pub mod linehighlight;
+pub mod links;
+pub mod list;
+pub mod mark;
+pub mod metrics;
+pub mod nobreak;
+#[cfg(feature = "plugin")]
+pub mod queue;
+#[cfg(not(feature = "plugin"))]
+pub(crate) mod queue;
+pub mod quote;
+pub mod search;
+pub mod selection;
+pub mod spellcheck;
 pub mod tail;
+pub mod typewriter;
+pub mod zebra;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub(crate) enum ProcessingState {
@@ -78,6 +111,8 @@ pub(crate) struct PluginInner<'a, M, C> {
     plugin: M,
     state: ProcessingState,
     peeked_token: Option<Token<'a, C>>,
+    /// The source text starting with the peeked token.
+    peeked_text: &'a str,
 }
 
 #[derive(Debug)]
@@ -92,6 +127,7 @@ impl<'a, M: Clone, C: Clone> Clone for PluginWrapper<'a, M, C> {
                 plugin: this.plugin.clone(),
                 state: this.state,
                 peeked_token: unsafe { addr_of!(this.peeked_token).read() },
+                peeked_text: this.peeked_text,
             })),
         }
     }
@@ -113,6 +149,7 @@ impl<'a, M, C> PluginWrapper<'a, M, C> {
                 plugin,
                 state: ProcessingState::Measure,
                 peeked_token: None,
+                peeked_text: "",
             }),
         }
     }
@@ -121,6 +158,13 @@ impl<'a, M, C> PluginWrapper<'a, M, C> {
         self.inner.into_inner().plugin
     }
 
+    pub fn plugin(&self) -> M
+    where
+        M: Clone,
+    {
+        self.with(|this| this.plugin.clone())
+    }
+
     fn with<R>(&self, cb: impl FnOnce(&PluginInner<'a, M, C>) -> R) -> R {
         let inner = unsafe {
             // SAFETY: This is safe because we aren't exposing the reference.
@@ -149,10 +193,40 @@ where
         self.with_mut(|this| this.plugin.new_line());
     }
 
+    pub fn line_indent<S: TextRenderer>(&self, character_style: &S) -> u32 {
+        self.with_mut(|this| this.plugin.line_indent(character_style))
+    }
+
     pub fn set_state(&self, state: ProcessingState) {
         self.with_mut(|this| this.state = state);
     }
 
+    pub fn line_metrics(&self, metrics: &LineMeasurement) {
+        self.with_mut(|this| this.plugin.line_metrics(metrics));
+    }
+
+    pub fn place_line(
+        &self,
+        metrics: &LineMeasurement,
+        offset: i32,
+        space_config: SpaceConfig,
+    ) -> (i32, SpaceConfig) {
+        self.with_mut(|this| this.plugin.place_line(metrics, offset, space_config))
+    }
+
+    #[inline]
+    pub fn line_end(&self, end_type: LineEndType, end_pos: Point, metrics: &LineMeasurement) {
+        self.with_mut(|this| this.plugin.line_end(end_type, end_pos, metrics));
+    }
+
+    #[inline]
+    pub fn post_line_start<D>(&self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.with_mut(|this| this.plugin.post_line_start(draw_target, bounds))
+    }
+
     #[inline]
     pub fn render_token(&self, token: Token<'a, C>) -> Option<Token<'a, C>> {
         self.with_mut(|this| match this.state {
@@ -164,6 +238,7 @@ where
     pub fn peek_token(&self, source: &mut Parser<'a, C>) -> Option<Token<'a, C>> {
         self.with_mut(|this| {
             if this.peeked_token.is_none() {
+                this.peeked_text = source.as_str();
                 this.peeked_token = this.plugin.next_token(|| source.next());
             }
 
@@ -171,6 +246,14 @@ where
         })
     }
 
+    /// Returns the part of the source text that has not been consumed yet.
+    pub fn remaining_text(&self, source: &Parser<'a, C>) -> &'a str {
+        self.with(|this| match this.peeked_token {
+            Some(_) => this.peeked_text,
+            None => source.as_str(),
+        })
+    }
+
     pub fn consume_peeked_token(&self) {
         self.with_mut(|this| this.peeked_token = None);
     }
@@ -192,14 +275,22 @@ where
             };
 
             if let Some(token) = this.peeked_token.take() {
-                let token = match token {
+                let (token, consumed_bytes) = match token {
                     Token::Whitespace(count, seq) => {
-                        Token::Whitespace(count - len as u32, skip_chars(seq, len))
+                        let rest = skip_chars(seq, len);
+                        (
+                            Token::Whitespace(count - len as u32, rest),
+                            seq.len() - rest.len(),
+                        )
+                    }
+                    Token::Word(w) => {
+                        let rest = skip_chars(w, len);
+                        (Token::Word(rest), w.len() - rest.len())
                     }
-                    Token::Word(w) => Token::Word(skip_chars(w, len)),
                     _ => return,
                 };
 
+                this.peeked_text = this.peeked_text.get(consumed_bytes..).unwrap_or("");
                 this.peeked_token.replace(token);
             }
         })
@@ -217,10 +308,46 @@ where
         });
     }
 
+    #[inline]
+    pub fn pre_render_box<D>(&self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.with_mut(|this| this.plugin.pre_render_box(draw_target, bounds))
+    }
+
+    #[inline]
+    pub fn render_object<D>(
+        &self,
+        draw_target: &mut D,
+        id: u32,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.with_mut(|this| this.plugin.render_object(draw_target, id, bounds))
+    }
+
+    #[inline]
+    pub fn post_render_box<D>(&self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.with_mut(|this| this.plugin.post_render_box(draw_target, bounds))
+    }
+
     pub fn on_rendering_finished(&self) {
         self.with_mut(|this| this.plugin.on_rendering_finished());
     }
 
+    pub fn pre_render<T>(&self, character_style: &mut T, text: &str, bounds: Rectangle)
+    where
+        T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+    {
+        self.with_mut(|this| this.plugin.pre_render(character_style, text, bounds));
+    }
+
     pub fn post_render<T, D>(
         &self,
         draw_target: &mut D,
@@ -0,0 +1,170 @@
+//! Keep spans of text enclosed in `{{` and `}}` in a single line.
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{parser::Token, plugin::Plugin};
+
+/// The string that opens a non-breaking span.
+const OPEN: &str = "{{";
+
+/// The string that closes a non-breaking span.
+const CLOSE: &str = "}}";
+
+/// Non-breaking span plugin.
+///
+/// Removes the `{{` and `}}` marks from the text and keeps the text between them together, like
+/// `{{10 kg}}`. The spaces in a span work like non-breaking spaces: the span is wrapped to the
+/// next line as a whole if it doesn't fit in the current one. Spans that are wider than a line
+/// are broken up like long words.
+///
+/// Soft hyphens and zero-width spaces don't break spans. Line break characters still start a new
+/// line.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::nobreak::NoBreak, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("The box weighs {{10 kg}}.", bounding_box, character_style)
+///     .add_plugin(NoBreak::new());
+/// ```
+#[derive(Clone, Debug)]
+pub struct NoBreak<'a, C: PixelColor> {
+    /// True if the current text is inside a span.
+    in_span: bool,
+
+    /// The rest of a word that was split at a mark.
+    carry: Option<Token<'a, C>>,
+}
+
+impl<C: PixelColor> NoBreak<'_, C> {
+    /// Creates a new non-breaking span plugin.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            in_span: false,
+            carry: None,
+        }
+    }
+
+    /// Returns the mark that changes the state of the current span.
+    const fn mark(&self) -> &'static str {
+        if self.in_span {
+            CLOSE
+        } else {
+            OPEN
+        }
+    }
+}
+
+impl<C: PixelColor> Default for NoBreak<'_, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for NoBreak<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        loop {
+            let token = match self.carry.take() {
+                Some(token) => Some(token),
+                None => next_token(),
+            };
+
+            let text = match token {
+                Some(Token::Word(text)) => text,
+
+                // Spaces in a span are part of the word, so that the span is wrapped as a whole.
+                Some(Token::Whitespace(0, _)) | Some(Token::Break(_)) if self.in_span => continue,
+                Some(Token::Whitespace(_, text)) if self.in_span => return Some(Token::Word(text)),
+
+                _ => return token,
+            };
+
+            let mark = self.mark();
+            match text.find(mark) {
+                Some(0) => {
+                    let rest = &text[mark.len()..];
+                    if !rest.is_empty() {
+                        self.carry = Some(Token::Word(rest));
+                    }
+
+                    self.in_span = !self.in_span;
+                }
+                Some(idx) => {
+                    // Return the text before the mark first.
+                    self.carry = Some(Token::Word(&text[idx..]));
+                    return Some(Token::Word(&text[..idx]));
+                }
+                None => return Some(Token::Word(text)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::nobreak::NoBreak, utils::test::size_for, TextBox};
+
+    #[track_caller]
+    fn assert_same_as(text: &str, expected_text: &str, columns: u32, rows: u32) {
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, columns, rows));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(expected_text, bounding_box, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(text, bounding_box, character_style)
+            .add_plugin(NoBreak::new())
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn span_is_wrapped_as_a_whole() {
+        assert_same_as("weighs {{10 kg}}", "weighs\n10 kg", 9, 2);
+    }
+
+    #[test]
+    fn span_that_fits_is_not_moved() {
+        assert_same_as("a {{b c}} d", "a b c d", 7, 1);
+    }
+
+    #[test]
+    fn text_around_marks_is_kept() {
+        assert_same_as("x{{a b}}y z", "xa by\nz", 6, 2);
+    }
+
+    #[test]
+    fn soft_hyphens_do_not_break_spans() {
+        assert_same_as("a {{b c\u{AD}d}}", "a\nb cd", 5, 2);
+    }
+}
@@ -2,15 +2,17 @@
 
 use embedded_graphics::{
     draw_target::DrawTarget,
-    prelude::PixelColor,
+    prelude::{PixelColor, Point},
     primitives::Rectangle,
     text::renderer::{CharacterStyle, TextRenderer},
 };
 use object_chain::{Chain, ChainElement, Link};
 
 use crate::{
+    alignment::SpaceConfig,
     parser::Token,
     rendering::{cursor::Cursor, TextBoxProperties},
+    style::{LineEndType, LineMeasurement},
 };
 
 /// Plugin trait.
@@ -29,7 +31,20 @@ where
     #[inline]
     fn new_line(&mut self) {}
 
+    /// Returns the extra indentation of the line that is being started, in pixels.
+    ///
+    /// Called at the start of every line, after [`Plugin::new_line`]. The returned value is added
+    /// to the indentation set by the text box style. `character_style` is the character style of
+    /// the text box.
+    #[inline]
+    fn line_indent<S: TextRenderer>(&mut self, _character_style: &S) -> u32 {
+        0
+    }
+
     /// Generate the next text token.
+    ///
+    /// `next_token` returns the next token of the text. To return more than one token for a token
+    /// of the text, use a [`TokenQueue`](crate::plugin::queue::TokenQueue).
     #[inline]
     fn next_token(
         &mut self,
@@ -38,15 +53,72 @@ where
         next_token()
     }
 
+    /// Called after a line is measured, before it is rendered.
+    #[inline]
+    fn line_metrics(&mut self, _metrics: &LineMeasurement) {}
+
+    /// Changes the horizontal placement of a line, e.g. to implement a custom alignment.
+    ///
+    /// Called after [`Plugin::line_metrics`], with the offset and the space configuration
+    /// calculated by [`HorizontalAlignment::place_line`] for the line. The returned values are
+    /// used to render the line, see [`HorizontalAlignment::place_line`] for their meaning.
+    ///
+    /// [`HorizontalAlignment::place_line`]: crate::alignment::HorizontalAlignment::place_line()
+    #[inline]
+    fn place_line(
+        &mut self,
+        _metrics: &LineMeasurement,
+        offset: i32,
+        space_config: SpaceConfig,
+    ) -> (i32, SpaceConfig) {
+        (offset, space_config)
+    }
+
+    /// Called after a line is rendered.
+    ///
+    /// `end_type` is the reason the line ended, and `metrics` is the measurement of the line.
+    /// `end_pos` is the position after the last character of the line, at the top of the line,
+    /// after the line was aligned. In right-to-left text, this is on the left side of the line.
+    #[inline]
+    fn line_end(&mut self, _end_type: LineEndType, _end_pos: Point, _metrics: &LineMeasurement) {}
+
+    /// Called when a line is started, before the line is rendered.
+    ///
+    /// `bounds` is the area of the line, spanning the whole width of the text area. Anything drawn
+    /// to `draw_target` is drawn behind the text of the line.
+    #[inline]
+    fn post_line_start<D>(
+        &mut self,
+        _draw_target: &mut D,
+        _bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        Ok(())
+    }
+
     /// Modify the current token immediately before it is rendered.
     ///
     /// This function must return the same token type as the input, otherwise the returned token
-    /// is ignored.
+    /// is ignored. Words can be shortened to hide the rest of them, and words and whitespace can be
+    /// hidden by returning `None`. Hidden text still takes up space in the line.
     #[inline]
     fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
         Some(token)
     }
 
+    /// Called before a piece of text is rendered.
+    ///
+    /// `character_style` is the style the text will be drawn with. Changes made to it are kept for
+    /// the rest of the text. `bounds` is the area the text will be drawn to.
+    #[inline]
+    fn pre_render<T>(&mut self, _character_style: &mut T, _text: &str, _bounds: Rectangle)
+    where
+        T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+    {
+    }
+
     /// Called after a piece of text is rendered.
     #[inline]
     fn post_render<T, D>(
@@ -72,6 +144,57 @@ where
     ) {
     }
 
+    /// Called before the text is rendered, after the background and the border of the text box
+    /// are drawn.
+    ///
+    /// `bounds` is the bounding box of the whole text box. Anything drawn to `draw_target` is
+    /// drawn behind the text.
+    #[inline]
+    fn pre_render_box<D>(
+        &mut self,
+        _draw_target: &mut D,
+        _bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        Ok(())
+    }
+
+    /// Draws an inline object.
+    ///
+    /// Called for every [`Token::InlineObject`] that is rendered. `id` is the identifier of the
+    /// object and `bounds` is the area reserved for it. The plugins that don't draw the object
+    /// should ignore it.
+    #[inline]
+    fn render_object<D>(
+        &mut self,
+        _draw_target: &mut D,
+        _id: u32,
+        _bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        Ok(())
+    }
+
+    /// Called after the text is rendered, before [`Plugin::on_rendering_finished`].
+    ///
+    /// `bounds` is the bounding box of the whole text box. Anything drawn to `draw_target` is
+    /// drawn over the text.
+    #[inline]
+    fn post_render_box<D>(
+        &mut self,
+        _draw_target: &mut D,
+        _bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        Ok(())
+    }
+
     /// Called after rendering has finished.
     #[inline]
     fn on_rendering_finished(&mut self) {}
@@ -85,10 +208,17 @@ where
     C: PixelColor,
     Chain<P>: Clone,
 {
+    #[inline]
     fn new_line(&mut self) {
         self.object.new_line();
     }
 
+    #[inline]
+    fn line_indent<S: TextRenderer>(&mut self, character_style: &S) -> u32 {
+        self.object.line_indent(character_style)
+    }
+
+    #[inline]
     fn next_token(
         &mut self,
         next_token: impl FnMut() -> Option<Token<'a, C>>,
@@ -96,10 +226,48 @@ where
         self.object.next_token(next_token)
     }
 
+    #[inline]
+    fn line_metrics(&mut self, metrics: &LineMeasurement) {
+        self.object.line_metrics(metrics);
+    }
+
+    #[inline]
+    fn place_line(
+        &mut self,
+        metrics: &LineMeasurement,
+        offset: i32,
+        space_config: SpaceConfig,
+    ) -> (i32, SpaceConfig) {
+        self.object.place_line(metrics, offset, space_config)
+    }
+
+    #[inline]
+    fn line_end(&mut self, end_type: LineEndType, end_pos: Point, metrics: &LineMeasurement) {
+        self.object.line_end(end_type, end_pos, metrics);
+    }
+
+    #[inline]
+    fn post_line_start<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.object.post_line_start(draw_target, bounds)
+    }
+
+    #[inline]
     fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
         self.object.render_token(token)
     }
 
+    #[inline]
+    fn pre_render<T>(&mut self, character_style: &mut T, text: &str, bounds: Rectangle)
+    where
+        T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+    {
+        self.object.pre_render(character_style, text, bounds);
+    }
+
+    #[inline]
     fn post_render<T, D>(
         &mut self,
         draw_target: &mut D,
@@ -115,6 +283,7 @@ where
             .post_render(draw_target, character_style, text, bounds)
     }
 
+    #[inline]
     fn on_start_render<S: CharacterStyle + TextRenderer>(
         &mut self,
         cursor: &mut Cursor,
@@ -123,66 +292,215 @@ where
         self.object.on_start_render(cursor, props);
     }
 
-    fn on_rendering_finished(&mut self) {
-        self.object.on_rendering_finished();
-    }
-}
-
-impl<'a, C, P, CE> Plugin<'a, C> for Link<P, CE>
-where
-    CE: ChainElement + Plugin<'a, C>,
-    P: Plugin<'a, C>,
-    C: PixelColor,
-    Link<P, CE>: Clone,
-{
-    fn new_line(&mut self) {
-        self.parent.new_line();
-        self.object.new_line();
-    }
-
-    fn next_token(
-        &mut self,
-        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
-    ) -> Option<Token<'a, C>> {
-        let parent = &mut self.parent;
-        let next_token = || parent.next_token(&mut next_token);
-        self.object.next_token(next_token)
-    }
-
-    fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
-        self.parent
-            .render_token(token)
-            .and_then(|t| self.object.render_token(t))
+    #[inline]
+    fn pre_render_box<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.object.pre_render_box(draw_target, bounds)
     }
 
-    fn post_render<T, D>(
+    #[inline]
+    fn render_object<D>(
         &mut self,
         draw_target: &mut D,
-        character_style: &T,
-        text: Option<&str>,
+        id: u32,
         bounds: Rectangle,
     ) -> Result<(), D::Error>
     where
-        T: TextRenderer<Color = C>,
         D: DrawTarget<Color = C>,
     {
-        self.parent
-            .post_render(draw_target, character_style, text, bounds)?;
-        self.object
-            .post_render(draw_target, character_style, text, bounds)
+        self.object.render_object(draw_target, id, bounds)
     }
 
-    fn on_start_render<S: CharacterStyle + TextRenderer>(
-        &mut self,
-        cursor: &mut Cursor,
-        props: &TextBoxProperties<'_, S>,
-    ) {
-        self.parent.on_start_render(cursor, props);
-        self.object.on_start_render(cursor, props);
+    #[inline]
+    fn post_render_box<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.object.post_render_box(draw_target, bounds)
     }
 
+    #[inline]
     fn on_rendering_finished(&mut self) {
-        self.parent.on_rendering_finished();
         self.object.on_rendering_finished();
     }
 }
+
+/// Implements the hooks of a plugin that combines two plugins, `self.$first` and `self.$second`.
+///
+/// The hooks of the first plugin are called before the hooks of the second one, and the second
+/// plugin receives the tokens produced by the first one.
+macro_rules! impl_plugin_pair {
+    ($first:tt, $second:tt) => {
+        #[inline]
+        fn new_line(&mut self) {
+            self.$first.new_line();
+            self.$second.new_line();
+        }
+
+        #[inline]
+        fn line_indent<S: TextRenderer>(&mut self, character_style: &S) -> u32 {
+            self.$first
+                .line_indent(character_style)
+                .saturating_add(self.$second.line_indent(character_style))
+        }
+
+        #[inline]
+        fn next_token(
+            &mut self,
+            mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+        ) -> Option<Token<'a, C>> {
+            let first = &mut self.$first;
+            let next_token = || first.next_token(&mut next_token);
+            self.$second.next_token(next_token)
+        }
+
+        #[inline]
+        fn line_metrics(&mut self, metrics: &LineMeasurement) {
+            self.$first.line_metrics(metrics);
+            self.$second.line_metrics(metrics);
+        }
+
+        #[inline]
+        fn place_line(
+            &mut self,
+            metrics: &LineMeasurement,
+            offset: i32,
+            space_config: SpaceConfig,
+        ) -> (i32, SpaceConfig) {
+            let (offset, space_config) = self.$first.place_line(metrics, offset, space_config);
+            self.$second.place_line(metrics, offset, space_config)
+        }
+
+        #[inline]
+        fn line_end(&mut self, end_type: LineEndType, end_pos: Point, metrics: &LineMeasurement) {
+            self.$first.line_end(end_type, end_pos, metrics);
+            self.$second.line_end(end_type, end_pos, metrics);
+        }
+
+        #[inline]
+        fn post_line_start<D>(
+            &mut self,
+            draw_target: &mut D,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = C>,
+        {
+            self.$first.post_line_start(draw_target, bounds)?;
+            self.$second.post_line_start(draw_target, bounds)
+        }
+
+        #[inline]
+        fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
+            self.$first
+                .render_token(token)
+                .and_then(|t| self.$second.render_token(t))
+        }
+
+        #[inline]
+        fn pre_render<T>(&mut self, character_style: &mut T, text: &str, bounds: Rectangle)
+        where
+            T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+        {
+            self.$first.pre_render(character_style, text, bounds);
+            self.$second.pre_render(character_style, text, bounds);
+        }
+
+        #[inline]
+        fn post_render<T, D>(
+            &mut self,
+            draw_target: &mut D,
+            character_style: &T,
+            text: Option<&str>,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            T: TextRenderer<Color = C>,
+            D: DrawTarget<Color = C>,
+        {
+            self.$first
+                .post_render(draw_target, character_style, text, bounds)?;
+            self.$second
+                .post_render(draw_target, character_style, text, bounds)
+        }
+
+        #[inline]
+        fn on_start_render<S: CharacterStyle + TextRenderer>(
+            &mut self,
+            cursor: &mut Cursor,
+            props: &TextBoxProperties<'_, S>,
+        ) {
+            self.$first.on_start_render(cursor, props);
+            self.$second.on_start_render(cursor, props);
+        }
+
+        #[inline]
+        fn pre_render_box<D>(
+            &mut self,
+            draw_target: &mut D,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = C>,
+        {
+            self.$first.pre_render_box(draw_target, bounds)?;
+            self.$second.pre_render_box(draw_target, bounds)
+        }
+
+        #[inline]
+        fn render_object<D>(
+            &mut self,
+            draw_target: &mut D,
+            id: u32,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = C>,
+        {
+            self.$first.render_object(draw_target, id, bounds)?;
+            self.$second.render_object(draw_target, id, bounds)
+        }
+
+        #[inline]
+        fn post_render_box<D>(
+            &mut self,
+            draw_target: &mut D,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = C>,
+        {
+            self.$first.post_render_box(draw_target, bounds)?;
+            self.$second.post_render_box(draw_target, bounds)
+        }
+
+        #[inline]
+        fn on_rendering_finished(&mut self) {
+            self.$first.on_rendering_finished();
+            self.$second.on_rendering_finished();
+        }
+    };
+}
+
+impl<'a, C, P, CE> Plugin<'a, C> for Link<P, CE>
+where
+    CE: ChainElement + Plugin<'a, C>,
+    P: Plugin<'a, C>,
+    C: PixelColor,
+    Link<P, CE>: Clone,
+{
+    impl_plugin_pair!(parent, object);
+}
+
+/// Combines two plugins. The hooks of `A` are called before the hooks of `B`, and `B` receives
+/// the tokens produced by `A`.
+impl<'a, C, A, B> Plugin<'a, C> for (A, B)
+where
+    A: Plugin<'a, C>,
+    B: Plugin<'a, C>,
+    C: PixelColor,
+{
+    impl_plugin_pair!(0, 1);
+}
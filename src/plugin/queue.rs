@@ -0,0 +1,216 @@
+//! Emit more than one token for a token of the text.
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::parser::Token;
+
+/// A fixed size queue of tokens.
+///
+/// [`Plugin::next_token`] returns a single token per call. To insert tokens into the text, e.g.
+/// an icon before a word, a plugin can push the tokens to a queue and return them one by one
+/// from the following calls, before reading the next token of the text.
+///
+/// The text box peeks the next token while it lays out a line, and a peeked token is not read
+/// again. This means that a token is taken from the queue only once, even if it doesn't fit into
+/// the current line and is rendered in the next one. However, the source text is read ahead of
+/// the queued tokens: if rendering stops while tokens are queued, e.g. because the text box is
+/// full, the text returned by [`TextBox::draw`] starts after the token of the text that the
+/// queued tokens were created for.
+///
+/// [`Plugin::next_token`]: crate::plugin::Plugin::next_token()
+/// [`TextBox::draw`]: crate::TextBox
+///
+/// # Example
+///
+/// ```rust
+/// use embedded_graphics::prelude::PixelColor;
+/// use embedded_text::{
+///     plugin::{queue::TokenQueue, Plugin},
+///     Token,
+/// };
+///
+/// /// Draws an arrow in front of every word that starts with `#`.
+/// #[derive(Clone)]
+/// struct Tags<'a, C: PixelColor> {
+///     queue: TokenQueue<'a, C, 1>,
+/// }
+///
+/// impl<'a, C: PixelColor> Plugin<'a, C> for Tags<'a, C> {
+///     fn next_token(
+///         &mut self,
+///         mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+///     ) -> Option<Token<'a, C>> {
+///         if let Some(token) = self.queue.pop() {
+///             return Some(token);
+///         }
+///
+///         match next_token() {
+///             Some(Token::Word(word)) if word.starts_with('#') => {
+///                 // The queue is empty, so there is room for the word.
+///                 self.queue.push(Token::Word(&word[1..])).ok();
+///                 Some(Token::Word(">"))
+///             }
+///             token => token,
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TokenQueue<'a, C: PixelColor, const N: usize> {
+    tokens: [Option<Token<'a, C>>; N],
+
+    /// The index of the first queued token.
+    start: usize,
+
+    /// The number of queued tokens.
+    len: usize,
+}
+
+impl<'a, C: PixelColor, const N: usize> TokenQueue<'a, C, N> {
+    const EMPTY: Option<Token<'a, C>> = None;
+
+    /// Creates a new, empty queue that holds up to `N` tokens.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tokens: [Self::EMPTY; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Adds a token to the end of the queue.
+    ///
+    /// If the queue is full, the token is returned as an error.
+    #[inline]
+    pub fn push(&mut self, token: Token<'a, C>) -> Result<(), Token<'a, C>> {
+        if self.len == N {
+            return Err(token);
+        }
+
+        self.tokens[(self.start + self.len) % N] = Some(token);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes and returns the first token of the queue.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Token<'a, C>> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let token = self.tokens[self.start].take();
+        self.start = (self.start + 1) % N;
+        self.len -= 1;
+
+        token
+    }
+
+    /// Returns the number of queued tokens.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no tokens are queued.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all queued tokens.
+    #[inline]
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<C: PixelColor, const N: usize> Default for TokenQueue<'_, C, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        parser::Token,
+        plugin::{private::Plugin, queue::TokenQueue},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[test]
+    fn tokens_are_returned_in_order() {
+        let mut queue = TokenQueue::<BinaryColor, 2>::new();
+        assert_eq!(queue.push(Token::Word("a")), Ok(()));
+        assert_eq!(queue.push(Token::Word("b")), Ok(()));
+        assert_eq!(queue.push(Token::Word("c")), Err(Token::Word("c")));
+        assert_eq!(queue.pop(), Some(Token::Word("a")));
+        assert_eq!(queue.push(Token::Word("c")), Ok(()));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some(Token::Word("b")));
+        assert_eq!(queue.pop(), Some(Token::Word("c")));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    /// Inserts a `>` and a space before every word that starts with `#`.
+    #[derive(Clone)]
+    struct Tags<'a> {
+        queue: TokenQueue<'a, BinaryColor, 2>,
+    }
+
+    impl<'a> Plugin<'a, BinaryColor> for Tags<'a> {
+        fn next_token(
+            &mut self,
+            mut next_token: impl FnMut() -> Option<Token<'a, BinaryColor>>,
+        ) -> Option<Token<'a, BinaryColor>> {
+            if let Some(token) = self.queue.pop() {
+                return Some(token);
+            }
+
+            match next_token() {
+                Some(Token::Word(word)) if word.starts_with('#') => {
+                    self.queue.push(Token::Whitespace(1, " ")).unwrap();
+                    self.queue.push(Token::Word(&word[1..])).unwrap();
+                    Some(Token::Word(">"))
+                }
+                token => token,
+            }
+        }
+    }
+
+    #[test]
+    fn queued_tokens_are_rendered() {
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 3));
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("a > b\n> cd", bounding_box, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("a #b\n#cd", bounding_box, character_style)
+            .add_plugin(Tags {
+                queue: TokenQueue::new(),
+            })
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_eq(&expected);
+    }
+}
@@ -0,0 +1,348 @@
+//! Indent block quotes and draw bars next to them.
+
+use az::SaturatingAs;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::Token,
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+    utils::str_width,
+};
+
+/// The width of a quote level, in spaces.
+const LEVEL_WIDTH: u32 = 2;
+
+/// The quote state of the current paragraph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QuoteState {
+    /// At the start of a paragraph, after `depth` quote markers.
+    Start { depth: u32 },
+
+    /// After a quote marker that may be followed by whitespace.
+    Marker { depth: u32 },
+
+    /// Inside a paragraph that is quoted `depth` times. `depth` is 0 for paragraphs that are not
+    /// quoted.
+    Text { depth: u32 },
+}
+
+/// Block quote plugin.
+///
+/// Paragraphs that start with `>` are block quotes. The `>` marker and the whitespace after it
+/// are removed, the lines of the paragraph are indented, and a vertical bar is drawn in the
+/// indentation. Quotes can be nested by repeating the marker, like `> > text` or `>> text`, which
+/// indents the text further and draws a bar for every level.
+///
+/// Every quote level is two spaces wide.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::quote::BlockQuote, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 60));
+///
+/// let text = "Alice wrote:\n> Are we meeting today?";
+/// let text_box = TextBox::new(text, bounding_box, character_style)
+///     .add_plugin(BlockQuote::new(Rgb565::CSS_GRAY));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlockQuote<'a, C: PixelColor> {
+    color: C,
+    state: QuoteState,
+
+    /// The token that follows the indentation of the first line of a quote.
+    carry: Option<Token<'a, C>>,
+
+    /// The left edge of the text area.
+    left: i32,
+
+    /// True if the bars of the current line have been drawn.
+    bars_drawn: bool,
+}
+
+impl<C: PixelColor> BlockQuote<'_, C> {
+    /// Creates a new block quote plugin.
+    ///
+    /// `color` is the color of the bars drawn next to the quotes.
+    #[inline]
+    #[must_use]
+    pub const fn new(color: C) -> Self {
+        Self {
+            color,
+            state: QuoteState::Start { depth: 0 },
+            carry: None,
+            left: 0,
+            bars_drawn: false,
+        }
+    }
+
+    /// Returns the number of quote levels of the current paragraph.
+    const fn depth(&self) -> u32 {
+        match self.state {
+            QuoteState::Text { depth } => depth,
+            _ => 0,
+        }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for BlockQuote<'a, C> {
+    #[inline]
+    fn new_line(&mut self) {
+        self.bars_drawn = false;
+    }
+
+    #[inline]
+    fn line_indent<S: TextRenderer>(&mut self, character_style: &S) -> u32 {
+        // The first line of a quote is indented by the token that replaces the marker.
+        self.depth() * LEVEL_WIDTH * str_width(character_style, " ")
+    }
+
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if let Some(token) = self.carry.take() {
+            return Some(token);
+        }
+
+        loop {
+            let token = next_token();
+
+            let depth = match (self.state, &token) {
+                (_, Some(Token::NewLine)) => {
+                    self.state = QuoteState::Start { depth: 0 };
+                    return token;
+                }
+                (QuoteState::Text { .. }, _) | (_, Some(Token::ChangeTextStyle(_))) => {
+                    return token;
+                }
+                (QuoteState::Start { depth }, Some(Token::Word(word)))
+                | (QuoteState::Marker { depth }, Some(Token::Word(word)))
+                    if word.starts_with('>') =>
+                {
+                    let rest = word.trim_start_matches('>');
+                    let depth = depth + (word.len() - rest.len()) as u32;
+                    if rest.is_empty() {
+                        self.state = QuoteState::Marker { depth };
+                        continue;
+                    }
+
+                    self.carry = Some(Token::Word(rest));
+                    depth
+                }
+                (QuoteState::Marker { depth }, Some(Token::Whitespace(_, _))) => {
+                    self.state = QuoteState::Start { depth };
+                    continue;
+                }
+                (QuoteState::Start { depth }, _) | (QuoteState::Marker { depth }, _) => {
+                    self.carry = token;
+                    depth
+                }
+            };
+
+            self.state = QuoteState::Text { depth };
+            if depth == 0 {
+                return self.carry.take();
+            }
+
+            return Some(Token::MoveCursor {
+                chars: (depth * LEVEL_WIDTH).saturating_as(),
+                draw_background: false,
+            });
+        }
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if self.bars_drawn || text.is_none() {
+            return Ok(());
+        }
+        self.bars_drawn = true;
+
+        let space_width = str_width(character_style, " ");
+        let level_width = (LEVEL_WIDTH * space_width).saturating_as::<i32>();
+        let bar_size = Size::new((space_width / 3).max(1), bounds.size.height);
+
+        for level in 0..self.depth().saturating_as::<i32>() {
+            let bar = Rectangle::new(
+                Point::new(self.left + level * level_width, bounds.top_left.y),
+                bar_size,
+            );
+            draw_target.fill_solid(&bar, self.color)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.left = props.bounding_box.top_left.x;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        plugin::quote::BlockQuote,
+        style::{HeightMode, TextBoxStyleBuilder},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_rendered(text: &str, size: Size, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(text, Rectangle::new(Point::zero(), size), character_style)
+            .add_plugin(BlockQuote::new(BinaryColor::Off))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn quote() {
+        assert_rendered(
+            "a\n> b c",
+            size_for(&FONT_6X9, 3, 3),
+            &[
+                "                 ",
+                "                 ",
+                "                 ",
+                "  ###            ",
+                " #  #            ",
+                " #  #            ",
+                "  ###            ",
+                "                 ",
+                "                 ",
+                "..               ",
+                "..           #   ",
+                "..           #   ",
+                "..           ### ",
+                "..           #  #",
+                "..           #  #",
+                "..           ### ",
+                "..               ",
+                "..               ",
+                "..               ",
+                "..               ",
+                "..               ",
+                "..            ###",
+                "..           #   ",
+                "..           #   ",
+                "..            ###",
+                "..               ",
+                "..               ",
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_quote() {
+        assert_rendered(
+            "> > a\n>>b",
+            size_for(&FONT_6X9, 5, 2),
+            &[
+                "..          ..               ",
+                "..          ..               ",
+                "..          ..               ",
+                "..          ..            ###",
+                "..          ..           #  #",
+                "..          ..           #  #",
+                "..          ..            ###",
+                "..          ..               ",
+                "..          ..               ",
+                "..          ..               ",
+                "..          ..           #   ",
+                "..          ..           #   ",
+                "..          ..           ### ",
+                "..          ..           #  #",
+                "..          ..           #  #",
+                "..          ..           ### ",
+                "..          ..               ",
+                "..          ..               ",
+            ],
+        );
+    }
+
+    #[test]
+    fn text_that_is_not_a_quote() {
+        assert_rendered(
+            "a >b",
+            size_for(&FONT_6X9, 4, 1),
+            &[
+                "                       ",
+                "                   #   ",
+                "            ##     #   ",
+                "  ###         ##   ### ",
+                " #  #           #  #  #",
+                " #  #         ##   #  #",
+                "  ###       ##     ### ",
+            ],
+        );
+    }
+
+    #[test]
+    fn measured_height_includes_the_indentation() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "> aa b cc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .build(),
+        )
+        .add_plugin(BlockQuote::new(BinaryColor::Off));
+
+        assert_eq!(text_box.bounding_box().size, size_for(&FONT_6X9, 5, 3));
+    }
+}
@@ -0,0 +1,310 @@
+//! Highlight the occurrences of a piece of text.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::Token,
+    plugin::{
+        highlight::{draw_highlight, TextLayout},
+        Plugin,
+    },
+    rendering::{cursor::Cursor, TextBoxProperties},
+    utils::offset_in,
+};
+
+/// Search highlight plugin.
+///
+/// Draws a background color behind every occurrence of a piece of text. Occurrences that are
+/// split into multiple lines are highlighted on every line.
+///
+/// Matches are searched for inside words, so the searched text should not contain whitespace.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::search::SearchHighlight, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("The quick brown fox", bounding_box, character_style)
+///     .add_plugin(SearchHighlight::new("the", Rgb565::BLUE, true));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct SearchHighlight<'a, C> {
+    needle: &'a str,
+    color: C,
+    ignore_case: bool,
+
+    /// The word that is currently being rendered.
+    word: &'a str,
+
+    /// The layout of the rendered text.
+    layout: TextLayout,
+}
+
+impl<'a, C: PixelColor> SearchHighlight<'a, C> {
+    /// Creates a new search highlight plugin.
+    ///
+    /// `needle` is the text to search for, `color` is the background color of the occurrences.
+    /// If `ignore_case` is `true`, the search is case-insensitive.
+    #[inline]
+    #[must_use]
+    pub const fn new(needle: &'a str, color: C, ignore_case: bool) -> Self {
+        Self {
+            needle,
+            color,
+            ignore_case,
+            word: "",
+            layout: TextLayout::DEFAULT,
+        }
+    }
+
+    /// Returns the byte length of the occurrence at the start of `haystack`, if there is one.
+    fn match_at(&self, haystack: &str) -> Option<usize> {
+        let mut chars = haystack.char_indices();
+        for n in self.needle.chars() {
+            let (_, c) = chars.next()?;
+            let equal = if self.ignore_case {
+                c.to_lowercase().eq(n.to_lowercase())
+            } else {
+                c == n
+            };
+
+            if !equal {
+                return None;
+            }
+        }
+
+        Some(chars.next().map_or(haystack.len(), |(idx, _)| idx))
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for SearchHighlight<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = next_token();
+        if let Some(Token::Word(word)) = token {
+            self.word = word;
+        }
+        token
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.layout = TextLayout::new(props.box_style);
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if self.needle.is_empty() {
+            return Ok(());
+        }
+
+        // Only parts of the current word are searched.
+        let (st, offset) = match text.and_then(|st| Some((st, offset_in(self.word, st)?))) {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+        let end = offset + st.len();
+
+        let mut search_from = 0;
+        for (idx, _) in self.word.char_indices() {
+            if idx >= end {
+                break;
+            }
+            if idx < search_from {
+                continue;
+            }
+
+            if let Some(len) = self.match_at(&self.word[idx..]) {
+                search_from = idx + len;
+
+                let start = idx.max(offset);
+                let stop = (idx + len).min(end);
+                if start < stop {
+                    draw_highlight(
+                        draw_target,
+                        character_style,
+                        &self.layout,
+                        st,
+                        bounds,
+                        start - offset..stop - offset,
+                        self.color,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{plugin::search::SearchHighlight, utils::test::size_for, TextBox};
+
+    #[track_caller]
+    fn assert_highlighted(
+        text: &str,
+        needle: &str,
+        ignore_case: bool,
+        size: Size,
+        pattern: &[&str],
+    ) {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::new(text, Rectangle::new(Point::zero(), size), character_style)
+            .add_plugin(SearchHighlight::new(needle, BinaryColor::Off, ignore_case))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn highlight_every_occurrence() {
+        assert_highlighted(
+            "the quick brown fox",
+            "o",
+            false,
+            size_for(&FONT_6X9, 10, 2),
+            &[
+                "                                                     ",
+                "  #    #                              #          #   ",
+                "  #    #                                         #   ",
+                " ###   ###    ##          ###  #  #  ##     ###  # # ",
+                "  #    #  #  # ##        #  #  #  #   #    #     ##  ",
+                "  # #  #  #  ##          #  #  #  #   #    #     # # ",
+                "   #   #  #   ###         ###   ###  ###    ###  #  #",
+                "                            #                        ",
+                "                            #                        ",
+                "            ......                        ......     ",
+                " #          ......                     #  ......     ",
+                " #          ......                    # # ......     ",
+                " ###   # #  ..##..#   #  ###          #   ..##.. #  #",
+                " #  #  ## # .#..#.# # #  #  #        ###  .#..#.  ## ",
+                " #  #  #    .#..#.# # #  #  #         #   .#..#.  ## ",
+                " ###   #    ..##.. # #   #  #         #   ..##.. #  #",
+                "            ......                        ......     ",
+                "            ......                        ......     ",
+            ],
+        );
+    }
+
+    #[test]
+    fn highlight_split_occurrences() {
+        assert_highlighted(
+            "fooboObOo",
+            "oob",
+            true,
+            size_for(&FONT_6X9, 3, 3),
+            &[
+                "      ............",
+                "   #  ............",
+                "  # # ............",
+                "  #   ..##....##..",
+                " ###  .#..#..#..#.",
+                "  #   .#..#..#..#.",
+                "  #   ..##....##..",
+                "      ............",
+                "      ............",
+                "..................",
+                ".#...........###..",
+                ".#..........#...#.",
+                ".###....##..#...#.",
+                ".#..#..#..#.#...#.",
+                ".#..#..#..#.#...#.",
+                ".###....##...###..",
+                "..................",
+                "..................",
+                "......            ",
+                ".#.... ###        ",
+                ".#....#   #       ",
+                ".###..#   #   ##  ",
+                ".#..#.#   #  #  # ",
+                ".#..#.#   #  #  # ",
+                ".###.. ###    ##  ",
+                "......            ",
+                "......            ",
+            ],
+        );
+    }
+
+    #[test]
+    fn highlight_case_sensitive() {
+        assert_highlighted(
+            "fooboObOo",
+            "oob",
+            false,
+            size_for(&FONT_6X9, 3, 3),
+            &[
+                "      ............",
+                "   #  ............",
+                "  # # ............",
+                "  #   ..##....##..",
+                " ###  .#..#..#..#.",
+                "  #   .#..#..#..#.",
+                "  #   ..##....##..",
+                "      ............",
+                "      ............",
+                "......            ",
+                ".#....       ###  ",
+                ".#....      #   # ",
+                ".###..  ##  #   # ",
+                ".#..#. #  # #   # ",
+                ".#..#. #  # #   # ",
+                ".###..  ##   ###  ",
+                "......            ",
+                "......            ",
+                "                  ",
+                " #     ###        ",
+                " #    #   #       ",
+                " ###  #   #   ##  ",
+                " #  # #   #  #  # ",
+                " #  # #   #  #  # ",
+                " ###   ###    ##  ",
+            ],
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Highlight a selected range of the text.
+
+use core::ops::Range;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::Token,
+    plugin::{
+        highlight::{draw_highlight, ByteTracker},
+        Plugin,
+    },
+    rendering::{cursor::Cursor, TextBoxProperties},
+};
+
+/// Text selection highlight plugin.
+///
+/// Draws a background color behind the characters in a [byte range] of the text.
+///
+/// [byte range]: crate::plugin#byte-ranges
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::selection::SelectionHighlight, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// // Select "quick".
+/// let text_box = TextBox::new("The quick brown fox", bounding_box, character_style)
+///     .add_plugin(SelectionHighlight::new(4..9, Rgb565::BLUE));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SelectionHighlight<'a, C> {
+    range: Range<usize>,
+    color: C,
+
+    /// Tracks the byte offsets of the rendered text.
+    bytes: ByteTracker<'a>,
+}
+
+impl<'a, C: PixelColor> SelectionHighlight<'a, C> {
+    /// Creates a new selection highlight plugin.
+    ///
+    /// `range` is the byte range of the selected text, `color` is the background color of the
+    /// selection.
+    #[inline]
+    #[must_use]
+    pub const fn new(range: Range<usize>, color: C) -> Self {
+        Self {
+            range,
+            color,
+            bytes: ByteTracker::NEW,
+        }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for SelectionHighlight<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = next_token();
+        self.bytes.next_token(&token);
+        token
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.bytes.start_render(props.box_style);
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        let st = match text {
+            Some(st) => st,
+            None => return Ok(()),
+        };
+
+        if let Some(range) = self.bytes.overlap(st, &self.range) {
+            draw_highlight(
+                draw_target,
+                character_style,
+                &self.bytes.layout,
+                st,
+                bounds,
+                range,
+                self.color,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::ops::Range;
+
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{
+        plugin::selection::SelectionHighlight,
+        style::{TextBoxStyle, TextBoxStyleBuilder, TextDirection},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_selected(text: &str, range: Range<usize>, size: Size, pattern: &[&str]) {
+        assert_styled_selected(TextBoxStyle::default(), text, range, size, pattern);
+    }
+
+    #[track_caller]
+    fn assert_styled_selected(
+        style: TextBoxStyle,
+        text: &str,
+        range: Range<usize>,
+        size: Size,
+        pattern: &[&str],
+    ) {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::with_textbox_style(
+            text,
+            Rectangle::new(Point::zero(), size),
+            character_style,
+            style,
+        )
+        .add_plugin(SelectionHighlight::new(range, BinaryColor::Off))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn selection_starts_and_ends_inside_words() {
+        assert_selected(
+            "hello world",
+            3..8,
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "                  ............",
+                " #           ##   .##.........",
+                " #            #   ..#.........",
+                " ###    ##    #   ..#.....##..",
+                " #  #  # ##   #   ..#....#..#.",
+                " #  #  ##     #   ..#....#..#.",
+                " #  #   ###  ###  .###....##..",
+                "                  ............",
+                "                  ............",
+                "............                  ",
+                "............       ##       # ",
+                "............        #       # ",
+                "#...#...##.. # #    #     ### ",
+                "#.#.#..#..#. ## #   #    #  # ",
+                "#.#.#..#..#. #      #    #  # ",
+                ".#.#....##.. #     ###    ### ",
+                "............                  ",
+                "............                  ",
+            ],
+        );
+    }
+
+    #[test]
+    fn selection_after_special_characters() {
+        assert_selected(
+            "a\tb\nc\u{a0}d",
+            2..7,
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "                        ......",
+                "                        .#....",
+                "                        .#....",
+                "  ###                   .###..",
+                " #  #                   .#..#.",
+                " #  #                   .#..#.",
+                "  ###                   .###..",
+                "                        ......",
+                "                        ......",
+                "............                  ",
+                "............    #             ",
+                "............    #             ",
+                "..###.......  ###             ",
+                ".#.......... #  #             ",
+                ".#.......... #  #             ",
+                "..###.......  ###             ",
+                "............                  ",
+                "............                  ",
+            ],
+        );
+    }
+
+    #[test]
+    fn selection_with_letter_spacing() {
+        assert_styled_selected(
+            TextBoxStyleBuilder::new().letter_spacing(2).build(),
+            "abc",
+            1..2,
+            size_for(&FONT_6X9, 4, 1),
+            &[
+                "        ......          ",
+                "        .#....          ",
+                "        .#....          ",
+                "  ###   .###..    ###   ",
+                " #  #   .#..#.   #      ",
+                " #  #   .#..#.   #      ",
+                "  ###   .###..    ###   ",
+                "        ......          ",
+                "        ......          ",
+            ],
+        );
+    }
+
+    #[test]
+    fn selection_right_to_left() {
+        assert_styled_selected(
+            TextBoxStyleBuilder::new()
+                .text_direction(TextDirection::RightToLeft)
+                .build(),
+            "abc",
+            0..1,
+            size_for(&FONT_6X9, 4, 1),
+            &[
+                "                  ......",
+                "             #    ......",
+                "             #    ......",
+                "        ###  ###  ..###.",
+                "       #     #  # .#..#.",
+                "       #     #  # .#..#.",
+                "        ###  ###  ..###.",
+                "                  ......",
+                "                  ......",
+            ],
+        );
+    }
+
+    #[test]
+    fn selection_after_crlf() {
+        assert_selected(
+            "ab\r\ncd",
+            4..5,
+            size_for(&FONT_6X9, 2, 2),
+            &[
+                "            ",
+                "       #    ",
+                "       #    ",
+                "  ###  ###  ",
+                " #  #  #  # ",
+                " #  #  #  # ",
+                "  ###  ###  ",
+                "            ",
+                "            ",
+                "......      ",
+                "......    # ",
+                "......    # ",
+                "..###.  ### ",
+                ".#.... #  # ",
+                ".#.... #  # ",
+                "..###.  ### ",
+                "......      ",
+                "......      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn selection_after_horizontal_rule() {
+        assert_styled_selected(
+            TextBoxStyleBuilder::new().horizontal_rules(true).build(),
+            "---\nab",
+            5..6,
+            size_for(&FONT_6X9, 2, 2),
+            &[
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "############",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "      ......",
+                "      .#....",
+                "      .#....",
+                "  ### .###..",
+                " #  # .#..#.",
+                " #  # .#..#.",
+                "  ### .###..",
+                "      ......",
+                "      ......",
+            ],
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Mark misspelled words with a wavy underline.
+
+use core::ops::Range;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Baseline,
+    },
+    Pixel,
+};
+
+use crate::{
+    parser::Token,
+    plugin::{
+        highlight::{x_range, ByteTracker},
+        Plugin,
+    },
+    rendering::{cursor::Cursor, TextBoxProperties},
+    utils::baseline_offset,
+};
+
+/// Spell check plugin.
+///
+/// Draws a wavy underline beneath the characters in a list of [byte ranges] of the text, e.g. the
+/// misspelled words reported by a spell checker. The underline is drawn in the two pixel rows below
+/// the baseline of the text. Words that are broken into more than one line are underlined on
+/// every line.
+///
+/// [byte ranges]: crate::plugin#byte-ranges
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::spellcheck::SpellCheck, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// // Mark "quikc" and "bronw".
+/// let misspelled = [4..9, 10..15];
+/// let text_box = TextBox::new("The quikc bronw fox", bounding_box, character_style)
+///     .add_plugin(SpellCheck::new(&misspelled, Rgb565::RED));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpellCheck<'a, C> {
+    ranges: &'a [Range<usize>],
+    color: C,
+
+    /// Tracks the byte offsets of the rendered text.
+    bytes: ByteTracker<'a>,
+}
+
+impl<'a, C: PixelColor> SpellCheck<'a, C> {
+    /// Creates a new spell check plugin.
+    ///
+    /// `ranges` are the byte ranges of the misspelled parts of the text, `color` is the color of
+    /// the underline.
+    #[inline]
+    #[must_use]
+    pub const fn new(ranges: &'a [Range<usize>], color: C) -> Self {
+        Self {
+            ranges,
+            color,
+            bytes: ByteTracker::NEW,
+        }
+    }
+
+    /// Draws the wavy underline below the byte range `range` of a rendered piece of text.
+    fn draw_squiggle<T, D>(
+        &self,
+        draw_target: &mut D,
+        character_style: &T,
+        st: &str,
+        bounds: Rectangle,
+        range: Range<usize>,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        let (left, right) = x_range(character_style, &self.bytes.layout, st, bounds, range);
+        let (left, right) = (
+            bounds.top_left.x + left as i32,
+            bounds.top_left.x + right as i32,
+        );
+        let top = bounds.top_left.y + baseline_offset(character_style, Baseline::Alphabetic) + 1;
+
+        // The phase of the wave depends on the absolute position, so that the underlines of
+        // neighbouring pieces of text are continuous.
+        let color = self.color;
+        draw_target.draw_iter(
+            (left..right)
+                .map(|x| Point::new(x, top + x.rem_euclid(2)))
+                .filter(|&p| bounds.contains(p))
+                .map(|p| Pixel(p, color)),
+        )
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for SpellCheck<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = next_token();
+        self.bytes.next_token(&token);
+        token
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.bytes.start_render(props.box_style);
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        let st = match text {
+            Some(st) => st,
+            None => return Ok(()),
+        };
+
+        for range in self.ranges {
+            if let Some(range) = self.bytes.overlap(st, range) {
+                self.draw_squiggle(draw_target, character_style, st, bounds, range)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::ops::Range;
+
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{plugin::spellcheck::SpellCheck, utils::test::size_for, TextBox};
+
+    #[track_caller]
+    fn assert_marked(text: &str, ranges: &[Range<usize>], size: Size, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::new(text, Rectangle::new(Point::zero(), size), character_style)
+            .add_plugin(SpellCheck::new(ranges, BinaryColor::Off))
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn misspelled_words_are_underlined() {
+        assert_marked(
+            "ab cd ef",
+            &[3..5, 7..8],
+            size_for(&FONT_6X9, 8, 1),
+            &[
+                "                                                ",
+                "       #                    #                #  ",
+                "       #                    #               # # ",
+                "  ###  ###          ###   ###         ##    #   ",
+                " #  #  #  #        #     #  #        # ##  ###  ",
+                " #  #  #  #        #     #  #        ##     #   ",
+                "  ###  ###          ###   ###         ###   #   ",
+                "                  . . . . . .             . . . ",
+                "                   . . . . . .             . . .",
+            ],
+        );
+    }
+
+    #[test]
+    fn underline_follows_broken_words() {
+        assert_marked(
+            "abcd ef",
+            &[1..4, 5..7],
+            size_for(&FONT_6X9, 2, 3),
+            &[
+                "            ",
+                "       #    ",
+                "       #    ",
+                "  ###  ###  ",
+                " #  #  #  # ",
+                " #  #  #  # ",
+                "  ###  ###  ",
+                "      . . . ",
+                "       . . .",
+                "            ",
+                "          # ",
+                "          # ",
+                "  ###   ### ",
+                " #     #  # ",
+                " #     #  # ",
+                "  ###   ### ",
+                ". . . . . . ",
+                " . . . . . .",
+                "            ",
+                "         #  ",
+                "        # # ",
+                "  ##    #   ",
+                " # ##  ###  ",
+                " ##     #   ",
+                "  ###   #   ",
+                ". . . . . . ",
+                " . . . . . .",
+            ],
+        );
+    }
+}
@@ -19,6 +19,7 @@ use crate::{
 pub struct Tail;
 
 impl<'a, C: PixelColor> Plugin<'a, C> for Tail {
+    #[inline]
     fn on_start_render<S: CharacterStyle + TextRenderer>(
         &mut self,
         cursor: &mut Cursor,
@@ -0,0 +1,194 @@
+//! Reveal the text character by character.
+
+use embedded_graphics::{
+    prelude::PixelColor,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    parser::Token,
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+};
+
+/// Typewriter plugin.
+///
+/// Only draws the first `n` printable characters of the text, as if the rest was not typed yet.
+/// Whitespace and control characters don't count toward `n`. The hidden text is laid out as
+/// usual, so the revealed characters don't move when `n` is increased.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// use embedded_text::{plugin::typewriter::Typewriter, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// for frame in 0..10 {
+///     # let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+///     # display.set_allow_overdraw(true);
+///     TextBox::new("Hello, world!", bounding_box, character_style)
+///         .add_plugin(Typewriter::new(frame))
+///         .draw(&mut display)?;
+/// }
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Typewriter {
+    visible: usize,
+
+    /// The number of characters drawn so far.
+    drawn: usize,
+}
+
+impl Typewriter {
+    /// Creates a new typewriter plugin that draws the first `visible` printable characters.
+    #[inline]
+    #[must_use]
+    pub const fn new(visible: usize) -> Self {
+        Self { visible, drawn: 0 }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for Typewriter {
+    #[inline]
+    fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
+        match token {
+            Token::Word(word) => {
+                let remaining = self.visible - self.drawn;
+                match word.char_indices().nth(remaining) {
+                    Some((end, _)) => {
+                        self.drawn = self.visible;
+                        Some(Token::Word(&word[..end]))
+                    }
+                    None => {
+                        self.drawn += word.chars().count();
+                        Some(token)
+                    }
+                }
+            }
+            _ if self.drawn < self.visible => Some(token),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        self.drawn = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::typewriter::Typewriter, utils::test::size_for, TextBox};
+
+    #[track_caller]
+    fn assert_rendered(visible: usize, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::new(
+            "ab c\nd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 2)),
+            character_style,
+        )
+        .add_plugin(Typewriter::new(visible))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn nothing_is_drawn_before_typing() {
+        assert_rendered(0, &[]);
+    }
+
+    #[test]
+    fn part_of_a_word() {
+        assert_rendered(
+            1,
+            &[
+                "......                  ",
+                "......                  ",
+                "......                  ",
+                "..###.                  ",
+                ".#..#.                  ",
+                ".#..#.                  ",
+                "..###.                  ",
+                "......                  ",
+                "......                  ",
+            ],
+        );
+    }
+
+    #[test]
+    fn whitespace_is_not_counted() {
+        assert_rendered(
+            3,
+            &[
+                "........................",
+                ".......#................",
+                ".......#................",
+                "..###..###..........###.",
+                ".#..#..#..#........#....",
+                ".#..#..#..#........#....",
+                "..###..###..........###.",
+                "........................",
+                "........................",
+            ],
+        );
+    }
+
+    #[test]
+    fn characters_are_counted_across_lines() {
+        assert_rendered(
+            4,
+            &[
+                "........................",
+                ".......#................",
+                ".......#................",
+                "..###..###..........###.",
+                ".#..#..#..#........#....",
+                ".#..#..#..#........#....",
+                "..###..###..........###.",
+                "........................",
+                "........................",
+                "......                  ",
+                "....#.                  ",
+                "....#.                  ",
+                "..###.                  ",
+                ".#..#.                  ",
+                ".#..#.                  ",
+                "..###.                  ",
+                "......                  ",
+                "......                  ",
+            ],
+        );
+    }
+}
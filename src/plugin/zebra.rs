@@ -0,0 +1,152 @@
+//! Fill alternating lines with different background colors.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+};
+
+/// Zebra stripes plugin.
+///
+/// Fills the background of the rendered lines with two alternating colors, to make tables and
+/// logs easier to read. The stripes span the whole width of the text area, regardless of the
+/// length of the text in the line.
+///
+/// Lines that are continued after a carriage return are drawn over the previous line, so they
+/// keep its stripe.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+/// #     pixelcolor::Rgb565,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// use embedded_text::{plugin::zebra::ZebraStripes, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 30));
+///
+/// let text_box = TextBox::new("CPU\t12%\nRAM\t48%\nDisk\t71%", bounding_box, character_style)
+///     .add_plugin(ZebraStripes::new(Rgb565::BLACK, Rgb565::CSS_DARK_SLATE_GRAY));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ZebraStripes<C> {
+    colors: [C; 2],
+
+    /// The number of stripes drawn.
+    stripes: usize,
+
+    /// The vertical position of the last stripe.
+    last_y: Option<i32>,
+}
+
+impl<C: PixelColor> ZebraStripes<C> {
+    /// Creates a new zebra stripes plugin.
+    ///
+    /// The first line is filled with `first`, the second line with `second`, and so on.
+    #[inline]
+    #[must_use]
+    pub const fn new(first: C, second: C) -> Self {
+        Self {
+            colors: [first, second],
+            stripes: 0,
+            last_y: None,
+        }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for ZebraStripes<C> {
+    #[inline]
+    fn post_line_start<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // Don't draw over the text of a line that is continued after a carriage return.
+        if self.last_y == Some(bounds.top_left.y) {
+            return Ok(());
+        }
+        self.last_y = Some(bounds.top_left.y);
+
+        let color = self.colors[self.stripes % 2];
+        self.stripes += 1;
+
+        draw_target.fill_solid(&bounds, color)
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        self.stripes = 0;
+        self.last_y = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::Rgb888,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{plugin::zebra::ZebraStripes, utils::test::size_for, TextBox};
+
+    #[test]
+    fn lines_are_striped() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        TextBox::new(
+            "a\nb\rc\nd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 3)),
+            MonoTextStyle::new(&FONT_6X9, Rgb888::WHITE),
+        )
+        .add_plugin(ZebraStripes::new(Rgb888::BLACK, Rgb888::BLUE))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "KKKKKKKKKKKK",
+            "KKKKKKKKKKKK",
+            "KKKKKKKKKKKK",
+            "KKWWWKKKKKKK",
+            "KWKKWKKKKKKK",
+            "KWKKWKKKKKKK",
+            "KKWWWKKKKKKK",
+            "KKKKKKKKKKKK",
+            "KKKKKKKKKKKK",
+            "BBBBBBBBBBBB",
+            "BWBBBBBBBBBB",
+            "BWBBBBBBBBBB",
+            "BWWWWBBBBBBB",
+            "BWBBWBBBBBBB",
+            "BWBBWBBBBBBB",
+            "BWWWWBBBBBBB",
+            "BBBBBBBBBBBB",
+            "BBBBBBBBBBBB",
+            "KKKKKKKKKKKK",
+            "KKKKWKKKKKKK",
+            "KKKKWKKKKKKK",
+            "KKWWWKKKKKKK",
+            "KWKKWKKKKKKK",
+            "KWKKWKKKKKKK",
+            "KKWWWKKKKKKK",
+            "KKKKKKKKKKKK",
+            "KKKKKKKKKKKK",
+        ]);
+    }
+}
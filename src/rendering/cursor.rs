@@ -3,22 +3,62 @@ use embedded_graphics::{geometry::Point, primitives::Rectangle, text::LineHeight
 
 use az::SaturatingAs;
 
+/// Positions of the tab stops in a line, relative to the start of the line.
+#[derive(Debug, Clone, Copy)]
+pub struct TabStops {
+    stops: &'static [u32],
+    width: u32,
+}
+
+impl TabStops {
+    /// Creates tab stops with uniform spacing.
+    pub const fn uniform(width: u32) -> Self {
+        Self { stops: &[], width }
+    }
+
+    /// Creates tab stops at the given positions.
+    ///
+    /// After the last stop, stops are repeated with the distance between the last two stops.
+    pub const fn at(stops: &'static [u32]) -> Self {
+        let width = match stops {
+            [] => 0,
+            [single] => *single,
+            [.., before_last, last] => last.saturating_sub(*before_last),
+        };
+
+        Self { stops, width }
+    }
+
+    /// Returns the position of the first tab stop after the given position.
+    fn next(&self, position: u32) -> u32 {
+        if let Some(stop) = self.stops.iter().find(|stop| **stop > position) {
+            return *stop;
+        }
+
+        let last = self.stops.last().copied().unwrap_or(0);
+        match position.saturating_sub(last).checked_div(self.width) {
+            Some(stops) => last.saturating_add(stops.saturating_add(1).saturating_mul(self.width)),
+            None => position,
+        }
+    }
+}
+
 /// Tracks position within a line.
 #[derive(Debug, Clone)]
 pub struct LineCursor {
     start: Point,
     width: u32,
     position: u32,
-    tab_width: u32,
+    tab_stops: TabStops,
 }
 
 impl LineCursor {
     /// Creates a new object whose position isn't important.
-    pub const fn new(width: u32, tab_width: u32) -> Self {
+    pub const fn new(width: u32, tab_stops: TabStops) -> Self {
         Self {
             start: Point::zero(),
             width,
-            tab_width,
+            tab_stops,
             position: 0,
         }
     }
@@ -28,13 +68,8 @@ impl LineCursor {
     }
 
     /// Returns the distance to the next tab position.
-    pub const fn next_tab_width(&self) -> u32 {
-        let next_tab_pos = if self.tab_width == 0 {
-            self.position
-        } else {
-            (self.position / self.tab_width + 1) * self.tab_width
-        };
-        next_tab_pos - self.position
+    pub fn next_tab_width(&self) -> u32 {
+        self.tab_stops.next(self.position) - self.position
     }
 
     /// Returns the width of the text box.
@@ -42,6 +77,24 @@ impl LineCursor {
         self.width
     }
 
+    /// Moves the start of the line to the right, reducing the usable width of the line.
+    pub fn indent(&mut self, by: u32) {
+        let by = by.min(self.width);
+        self.start.x += by.saturating_as::<i32>();
+        self.width -= by;
+    }
+
+    /// Removes the limit on the width of the line.
+    pub fn unlimit_width(&mut self) {
+        // The cursor can be moved by signed amounts, so the width must fit in an `i32`.
+        self.width = i32::MAX as u32;
+    }
+
+    /// Reduces the usable width of the line.
+    pub fn shrink_width(&mut self, by: u32) {
+        self.width = self.width.saturating_sub(by).max(self.position);
+    }
+
     /// Returns whether the current line has enough space to also include an object of given width.
     pub const fn fits_in_line(&self, width: u32) -> bool {
         width <= self.space()
@@ -101,7 +154,7 @@ pub struct Cursor {
     line_width: u32,
     line_height: u32,
     line_spacing: i32,
-    tab_width: u32,
+    tab_stops: TabStops,
 }
 
 impl Cursor {
@@ -112,7 +165,7 @@ impl Cursor {
         bounds: Rectangle,
         base_line_height: u32,
         line_height: LineHeight,
-        tab_width: u32,
+        tab_stops: TabStops,
     ) -> Self {
         Self {
             y: bounds.top_left.y,
@@ -124,7 +177,7 @@ impl Cursor {
             line_width: bounds.size.width,
             line_height: base_line_height,
             line_spacing: line_height.to_absolute(base_line_height).saturating_as(),
-            tab_width,
+            tab_stops,
         }
     }
 
@@ -134,7 +187,7 @@ impl Cursor {
             start: self.line_start(),
             width: self.line_width,
             position: 0,
-            tab_width: self.tab_width,
+            tab_stops: self.tab_stops,
         }
     }
 
@@ -168,6 +221,14 @@ impl Cursor {
         self.line_height
     }
 
+    /// Sets the height of the current line and the distance to the next line.
+    #[inline]
+    pub(crate) fn set_line_height(&mut self, line_height: u32, line_spacing: u32) {
+        self.bottom += self.line_height.saturating_as::<i32>() - line_height.saturating_as::<i32>();
+        self.line_height = line_height;
+        self.line_spacing = line_spacing.saturating_as();
+    }
+
     /// Starts a new line.
     #[inline]
     pub fn new_line(&mut self) {
@@ -186,3 +247,24 @@ impl Cursor {
         self.top_left.y <= self.y && self.y <= self.bottom
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::TabStops;
+
+    #[test]
+    fn tab_stops_repeat_after_the_last_stop() {
+        let stops = TabStops::at(&[4, 10]);
+
+        assert_eq!(stops.next(0), 4);
+        assert_eq!(stops.next(4), 10);
+        assert_eq!(stops.next(10), 16);
+        assert_eq!(stops.next(17), 22);
+    }
+
+    #[test]
+    fn tab_stops_saturate_at_the_end_of_the_range() {
+        assert_eq!(TabStops::uniform(8).next(u32::MAX - 3), u32::MAX);
+        assert_eq!(TabStops::uniform(0).next(5), 5);
+    }
+}
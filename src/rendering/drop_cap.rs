@@ -0,0 +1,86 @@
+//! Drop cap at the start of the text.
+
+use embedded_graphics::text::renderer::TextRenderer;
+
+use crate::{
+    parser::{Parser, Token},
+    plugin::{PluginMarker as Plugin, PluginWrapper},
+    rendering::fonts::Fonts,
+    style::TextBoxStyle,
+    utils::{clusters, str_width},
+};
+
+/// The first letter of the text, displayed in a larger character style next to the first lines.
+#[derive(Debug)]
+pub(crate) struct DropCap<'a, 'f, S> {
+    /// The character style of the letter.
+    pub style: &'f S,
+
+    /// The letter.
+    pub letter: &'a str,
+
+    /// The indentation of the lines next to the letter, in pixels.
+    pub indent: u32,
+
+    /// The number of lines next to the letter.
+    pub lines: usize,
+}
+
+impl<S> Clone for DropCap<'_, '_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for DropCap<'_, '_, S> {}
+
+impl<'a, 'f, S: TextRenderer> DropCap<'a, 'f, S> {
+    /// Removes the first letter of the text and returns it as a drop cap.
+    ///
+    /// Returns `None` without consuming anything if there is no drop cap style, or if the text
+    /// doesn't start with a word.
+    pub fn take<M>(
+        box_style: &TextBoxStyle,
+        fonts: Fonts<'f, S>,
+        plugin: &PluginWrapper<'a, M, S::Color>,
+        parser: &mut Parser<'a, S::Color>,
+    ) -> Option<Self>
+    where
+        M: Plugin<'a, S::Color>,
+    {
+        let style = fonts.drop_cap()?;
+        let word = match plugin.peek_token(parser) {
+            Some(Token::Word(word)) => word,
+            _ => return None,
+        };
+        let (_, letter) = clusters(word).next()?;
+
+        if letter.len() == word.len() {
+            plugin.consume_peeked_token();
+        } else {
+            plugin.consume_partial(letter.chars().count());
+        }
+
+        // The lines next to the letter are indented by its width and a space.
+        let primary = fonts.primary();
+        let line_spacing = box_style.line_spacing(primary.line_height()).max(1);
+        let height = style.line_height();
+        let lines = height / line_spacing + (height % line_spacing != 0) as u32;
+
+        Some(Self {
+            style,
+            letter,
+            indent: str_width(style, letter).saturating_add(str_width(primary, " ")),
+            lines: lines as usize,
+        })
+    }
+
+    /// Returns the indentation of the line with the given index.
+    pub const fn line_indent(&self, line: usize) -> u32 {
+        if line < self.lines {
+            self.indent
+        } else {
+            0
+        }
+    }
+}
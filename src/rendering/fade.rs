@@ -0,0 +1,93 @@
+//! Draw target that fades out the end of a line.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// 4x4 ordered dithering thresholds.
+const BAYER: [[u32; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Draw target that fades the pixels of an area into the background, using ordered dithering.
+///
+/// The fade starts at one side of the area and gets stronger towards the other side. Faded pixels
+/// are replaced by the background color, or not drawn if there is no background color.
+pub(crate) struct Fade<'d, D: DrawTarget> {
+    target: &'d mut D,
+    area: FadeArea,
+    background: Option<D::Color>,
+}
+
+impl<'d, D: DrawTarget> Fade<'d, D> {
+    /// Creates a new fade over `area`.
+    ///
+    /// If `reversed` is true, the fade gets stronger towards the left side of the area.
+    pub(crate) fn new(
+        target: &'d mut D,
+        area: Rectangle,
+        reversed: bool,
+        background: Option<D::Color>,
+    ) -> Self {
+        Self {
+            target,
+            area: FadeArea { area, reversed },
+            background,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct FadeArea {
+    area: Rectangle,
+    reversed: bool,
+}
+
+impl FadeArea {
+    /// Returns whether the pixel at `p` is faded out.
+    fn is_faded(&self, p: Point) -> bool {
+        if !self.area.contains(p) {
+            return false;
+        }
+
+        let width = self.area.size.width;
+        let offset = (p.x - self.area.top_left.x) as u32;
+        let distance = if self.reversed {
+            width - 1 - offset
+        } else {
+            offset
+        };
+        let level = (distance + 1) * 16 / (width + 1);
+
+        BAYER[p.y.rem_euclid(4) as usize][p.x.rem_euclid(4) as usize] < level
+    }
+}
+
+impl<D: DrawTarget> Dimensions for Fade<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for Fade<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+        let background = self.background;
+
+        self.target
+            .draw_iter(pixels.into_iter().filter_map(|Pixel(p, color)| {
+                if area.is_faded(p) {
+                    background.map(|background| Pixel(p, background))
+                } else {
+                    Some(Pixel(p, color))
+                }
+            }))
+    }
+}
@@ -0,0 +1,309 @@
+//! Character styles that can be selected while rendering.
+
+use crate::{
+    parser::{ChangeTextStyle, UnderlineStyle},
+    style::FontVariants,
+};
+
+/// The character styles of a text box.
+#[derive(Debug)]
+pub(crate) struct Fonts<'a, S> {
+    primary: &'a S,
+    alternatives: &'a [S],
+    variants: FontVariants<'a, S>,
+    drop_cap: Option<&'a S>,
+}
+
+impl<S> Clone for Fonts<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Fonts<'_, S> {}
+
+impl<'a, S> Fonts<'a, S> {
+    /// Creates a new font set.
+    pub const fn new(primary: &'a S, alternatives: &'a [S], variants: FontVariants<'a, S>) -> Self {
+        Self {
+            primary,
+            alternatives,
+            variants,
+            drop_cap: None,
+        }
+    }
+
+    /// Sets the character style of the first letter of the text.
+    pub const fn with_drop_cap(mut self, drop_cap: Option<&'a S>) -> Self {
+        self.drop_cap = drop_cap;
+        self
+    }
+
+    /// Creates a font set that only contains the given character style.
+    pub const fn single(primary: &'a S) -> Self {
+        Self::new(primary, &[], FontVariants::new())
+    }
+
+    /// Returns the character style of the text box.
+    pub const fn primary(&self) -> &'a S {
+        self.primary
+    }
+
+    /// Returns the character style of the first letter of the text, if it has one.
+    pub const fn drop_cap(&self) -> Option<&'a S> {
+        self.drop_cap
+    }
+
+    /// Returns the selected font, or `None` if the selection is invalid.
+    fn get(&self, font: Option<usize>) -> Option<&'a S> {
+        match font {
+            None => Some(self.primary),
+            Some(idx) => self.alternatives.get(idx),
+        }
+    }
+
+    /// Returns the character style selected by the given state.
+    pub fn select(&self, state: &StyleState) -> &'a S {
+        match state.font {
+            None => self
+                .variants
+                .get(state.bold, state.italic)
+                .unwrap_or(self.primary),
+            Some(idx) => &self.alternatives[idx],
+        }
+    }
+}
+
+/// Vertical position of the text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Script {
+    /// Text is drawn on the baseline.
+    Normal,
+
+    /// Text is shifted up.
+    Superscript,
+
+    /// Text is shifted down.
+    Subscript,
+}
+
+impl Script {
+    /// Returns the vertical offset of the text, for a font with the given line height.
+    pub const fn offset(self, line_height: u32) -> i32 {
+        let shift = (line_height / 3) as i32;
+        match self {
+            Script::Normal => 0,
+            Script::Superscript => -shift,
+            Script::Subscript => shift,
+        }
+    }
+}
+
+/// The maximum number of text styles saved by [`ChangeTextStyle::PushStyle`].
+const STYLE_STACK_DEPTH: usize = 8;
+
+/// Text styles saved by [`ChangeTextStyle::PushStyle`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct StyleStack<T> {
+    items: [Option<T>; STYLE_STACK_DEPTH],
+
+    /// The number of saved styles, including the ones that didn't fit in the stack.
+    depth: usize,
+}
+
+impl<T> StyleStack<T> {
+    /// Creates an empty stack.
+    pub fn new() -> Self {
+        Self {
+            items: [(); STYLE_STACK_DEPTH].map(|_| None),
+            depth: 0,
+        }
+    }
+
+    /// Saves a style. The style is dropped if the stack is full.
+    pub fn push(&mut self, item: T) {
+        if let Some(slot) = self.items.get_mut(self.depth) {
+            *slot = Some(item);
+        }
+        self.depth = self.depth.saturating_add(1);
+    }
+
+    /// Returns the last saved style, or `None` if it wasn't saved.
+    pub fn pop(&mut self) -> Option<T> {
+        self.depth = self.depth.checked_sub(1)?;
+        self.items.get_mut(self.depth)?.take()
+    }
+}
+
+/// Text style state that carries over from one line to the next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct StyleState {
+    /// The selected font. `None` is the character style of the text box.
+    pub font: Option<usize>,
+
+    /// The vertical position of the text.
+    pub script: Script,
+
+    /// True if the text and background colors are swapped.
+    pub reverse: bool,
+
+    /// True to use the bold variant of the font.
+    pub bold: bool,
+
+    /// True to use the italic variant of the font.
+    pub italic: bool,
+
+    /// The style of underlines.
+    pub underline: UnderlineStyle,
+
+    /// The states saved by [`ChangeTextStyle::PushStyle`].
+    saved: StyleStack<(Option<usize>, Script, bool, bool, bool, UnderlineStyle)>,
+}
+
+impl StyleState {
+    /// Returns the state at the start of the text.
+    pub fn new() -> Self {
+        Self {
+            font: None,
+            script: Script::Normal,
+            reverse: false,
+            bold: false,
+            italic: false,
+            underline: UnderlineStyle::Solid,
+            saved: StyleStack::new(),
+        }
+    }
+
+    /// Updates the state. Returns the selected character style if the font is changed.
+    pub fn update<'a, S, C>(
+        &mut self,
+        fonts: &Fonts<'a, S>,
+        change: ChangeTextStyle<C>,
+    ) -> Option<&'a S> {
+        let previous = fonts.select(self);
+
+        match change {
+            ChangeTextStyle::Reset => {
+                self.script = Script::Normal;
+                self.reverse = false;
+                self.bold = false;
+                self.italic = false;
+                self.underline = UnderlineStyle::Solid;
+            }
+            ChangeTextStyle::Bold(bold) => self.bold = bold,
+            ChangeTextStyle::Italic(italic) => self.italic = italic,
+            ChangeTextStyle::ReverseVideo(reverse) => self.reverse = reverse,
+            ChangeTextStyle::UnderlineStyle(underline) => self.underline = underline,
+            ChangeTextStyle::PushStyle => self.saved.push((
+                self.font,
+                self.script,
+                self.reverse,
+                self.bold,
+                self.italic,
+                self.underline,
+            )),
+            ChangeTextStyle::PopStyle => {
+                if let Some((font, script, reverse, bold, italic, underline)) = self.saved.pop() {
+                    self.font = font;
+                    self.script = script;
+                    self.reverse = reverse;
+                    self.bold = bold;
+                    self.italic = italic;
+                    self.underline = underline;
+                }
+            }
+            ChangeTextStyle::Superscript(true) => self.script = Script::Superscript,
+            ChangeTextStyle::Subscript(true) => self.script = Script::Subscript,
+            ChangeTextStyle::Superscript(false) | ChangeTextStyle::Subscript(false) => {
+                self.script = Script::Normal
+            }
+            ChangeTextStyle::Font(font) => {
+                // Selecting a font always replaces the character style.
+                fonts.get(font)?;
+                self.font = font;
+                return Some(fonts.select(self));
+            }
+            _ => {}
+        }
+
+        let selected = fonts.select(self);
+        if core::ptr::eq(previous, selected) {
+            None
+        } else {
+            Some(selected)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mono_font::{
+            ascii::{FONT_6X10, FONT_6X9},
+            MonoTextStyle,
+        },
+        pixelcolor::BinaryColor,
+    };
+
+    use crate::{
+        parser::ChangeTextStyle,
+        rendering::fonts::{Fonts, StyleState},
+        style::FontVariants,
+    };
+
+    #[test]
+    fn pop_style_restores_the_pushed_state() {
+        let primary = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let alternatives = [MonoTextStyle::new(&FONT_6X10, BinaryColor::On)];
+        let fonts = Fonts::new(&primary, &alternatives, FontVariants::new());
+
+        let mut state = StyleState::new();
+        let initial = state;
+
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PushStyle);
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::Bold(true));
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PushStyle);
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::Font(Some(0)));
+        assert!(state.bold);
+
+        let restored = state.update(&fonts, ChangeTextStyle::<BinaryColor>::PopStyle);
+        assert!(core::ptr::eq(restored.unwrap(), &primary));
+        assert_eq!(state.font, None);
+        assert!(state.bold);
+
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PopStyle);
+        assert_eq!(state, initial);
+
+        // Popping more than was pushed has no effect.
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PopStyle);
+        assert_eq!(state, initial);
+    }
+
+    #[test]
+    fn styles_pushed_to_a_full_stack_are_not_saved() {
+        let primary = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let fonts = Fonts::single(&primary);
+
+        let mut state = StyleState::new();
+        for _ in 0..7 {
+            state.update(&fonts, ChangeTextStyle::<BinaryColor>::PushStyle);
+        }
+
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::Bold(true));
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PushStyle);
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::ReverseVideo(true));
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PushStyle);
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::Bold(false));
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::ReverseVideo(false));
+
+        // The ninth style was not saved, so nothing is restored.
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PopStyle);
+        assert!(!state.bold);
+        assert!(!state.reverse);
+
+        // The eighth style is restored by the matching pop.
+        state.update(&fonts, ChangeTextStyle::<BinaryColor>::PopStyle);
+        assert!(state.bold);
+        assert!(!state.reverse);
+    }
+}
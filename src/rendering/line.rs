@@ -1,24 +1,34 @@
 //! Line rendering.
 
 use crate::{
-    parser::{ChangeTextStyle, Parser},
-    plugin::{PluginMarker as Plugin, PluginWrapper, ProcessingState},
+    parser::{ChangeTextStyle, Parser, UnderlineStyle},
+    plugin::{
+        highlight::{x_range, TextLayout},
+        PluginMarker as Plugin, PluginWrapper, ProcessingState,
+    },
     rendering::{
         cursor::LineCursor,
+        fonts::{Fonts, StyleStack, StyleState},
         line_iter::{ElementHandler, LineElementParser, LineEndType},
+        space_config::SpaceConfig,
+    },
+    style::{LineMeasurement, Strikethrough, TextBoxStyle, TextDirection, Underline},
+    utils::{
+        background_color, baseline_offset, cluster_base, clusters, ink_extent, str_width,
+        str_width_with_spacing, strikethrough_color, text_color, underline_color, visible_runs,
     },
-    style::TextBoxStyle,
-    utils::str_width,
 };
+use az::SaturatingAs;
 use embedded_graphics::{
     draw_target::DrawTarget,
     geometry::Point,
     prelude::{PixelColor, Size},
-    primitives::Rectangle,
+    primitives::{PointsIter, Rectangle},
     text::{
         renderer::{CharacterStyle, TextRenderer},
         Baseline, DecorationColor,
     },
+    Pixel,
 };
 
 impl<C> ChangeTextStyle<C>
@@ -37,10 +47,49 @@ where
             ChangeTextStyle::BackgroundColor(color) => text_renderer.set_background_color(color),
             ChangeTextStyle::Underline(color) => text_renderer.set_underline_color(color),
             ChangeTextStyle::Strikethrough(color) => text_renderer.set_strikethrough_color(color),
+            // Fonts and vertical position are handled by the element handlers.
+            ChangeTextStyle::Font(_)
+            | ChangeTextStyle::Superscript(_)
+            | ChangeTextStyle::Subscript(_)
+            | ChangeTextStyle::ReverseVideo(_)
+            | ChangeTextStyle::Bold(_)
+            | ChangeTextStyle::Italic(_)
+            | ChangeTextStyle::UnderlineStyle(_)
+            | ChangeTextStyle::PushStyle
+            | ChangeTextStyle::PopStyle => {}
         }
     }
 }
 
+/// Swaps the text and background colors of a character style.
+fn swap_colors<S>(style: &mut S)
+where
+    S: TextRenderer + CharacterStyle<Color = <S as TextRenderer>::Color>,
+{
+    let text_color = text_color(style);
+    let background_color = background_color(style);
+
+    style.set_text_color(background_color);
+    style.set_background_color(text_color);
+}
+
+/// Returns the pixels of an underline drawn along the bottom row of `area`.
+pub(crate) fn underline_pixels<C: PixelColor>(
+    underline: UnderlineStyle,
+    area: Rectangle,
+    color: C,
+) -> impl Iterator<Item = Pixel<C>> {
+    let left = area.top_left.x;
+    let right = left + area.size.width as i32;
+    let bottom = area.top_left.y + area.size.height as i32 - 1;
+
+    underline.rows().iter().flat_map(move |row| {
+        (left..right)
+            .filter(move |&x| underline.covers(x))
+            .map(move |x| Pixel(Point::new(x, bottom + row), color))
+    })
+}
+
 /// Render a single line of styled text.
 pub(crate) struct StyledLineRenderer<'a, 'b, 'c, S, M>
 where
@@ -50,6 +99,12 @@ where
     pub(crate) cursor: LineCursor,
     pub(crate) state: &'c mut LineRenderState<'a, 'b, S, M>,
     pub(crate) style: &'c TextBoxStyle,
+
+    /// The measurement of the line, made using the full width of the line.
+    pub(crate) measurement: LineMeasurement,
+
+    /// True to end the line with an ellipsis if the rest of the text doesn't fit in it.
+    pub(crate) truncate: bool,
 }
 
 #[derive(Clone)]
@@ -60,19 +115,89 @@ where
 {
     pub parser: Parser<'a, S::Color>,
     pub text_renderer: S,
+    pub fonts: Fonts<'b, S>,
+    pub style_state: StyleState,
+    pub saved_styles: StyleStack<S>,
     pub end_type: LineEndType,
     pub plugin: &'b PluginWrapper<'a, M, S::Color>,
 }
 
+impl<'a, 'b, S, M> LineRenderState<'a, 'b, S, M>
+where
+    S: TextRenderer + Clone,
+    M: Plugin<'a, S::Color>,
+{
+    /// Measures the next line of text without consuming it.
+    pub(crate) fn measure_line(
+        &self,
+        style: &TextBoxStyle,
+        max_line_width: u32,
+    ) -> LineMeasurement {
+        self.measure_line_and_rest(style, max_line_width).0
+    }
+
+    /// Measures the current line, and returns the text after the tokens read by the measurement.
+    ///
+    /// The measurement may read the first token of the next line, so the returned text can start
+    /// after the end of the line.
+    pub(crate) fn measure_line_and_rest(
+        &self,
+        style: &TextBoxStyle,
+        max_line_width: u32,
+    ) -> (LineMeasurement, &'a str) {
+        // Ensure the clone lives for as short as possible. Text boxes drawn using a
+        // `MeasurementCache` reuse the measurements of the cached lines instead, see
+        // `benches/draw.rs`.
+        let mut cloned_parser = self.parser.clone();
+        let measure_plugin = self.plugin.clone();
+        measure_plugin.set_state(ProcessingState::Measure);
+        let measurement = style.measure_line(
+            &measure_plugin,
+            self.fonts,
+            &mut self.style_state.clone(),
+            &mut cloned_parser,
+            max_line_width,
+        );
+
+        (measurement, cloned_parser.as_str())
+    }
+}
+
 struct RenderElementHandler<'a, 'b, F, D, M>
 where
     F: TextRenderer,
     D: DrawTarget<Color = F::Color>,
 {
     text_renderer: &'b mut F,
+    fonts: Fonts<'b, F>,
+    style_state: &'b mut StyleState,
+    saved_styles: &'b mut StyleStack<F>,
     display: &'b mut D,
     pos: Point,
+    letter_spacing: i32,
     plugin: &'b PluginWrapper<'a, M, F::Color>,
+
+    /// The extra space added after the printed characters of justified lines.
+    character_spacing: SpaceConfig,
+
+    /// Twice the horizontal center of the line, used to mirror right-to-left lines.
+    mirror: Option<i32>,
+
+    /// The baseline used to draw text, and its distance from the top of the line.
+    baseline: Baseline,
+    baseline_offset: i32,
+
+    /// The distance of the baseline from the top of the current font.
+    font_baseline_offset: i32,
+
+    /// The area of the line, before it is aligned.
+    line_bounds: Rectangle,
+
+    /// The strikethrough line drawn by `post_print`, if any.
+    strikethrough: Option<Strikethrough>,
+
+    /// The position of the underlines drawn by `post_print`, if it is set by the style.
+    underline: Option<Underline>,
 }
 
 impl<'a, 'b, F, D, M> RenderElementHandler<'a, 'b, F, D, M>
@@ -81,11 +206,206 @@ where
     D: DrawTarget<Color = <F as TextRenderer>::Color>,
     M: Plugin<'a, <F as TextRenderer>::Color>,
 {
-    fn post_print(&mut self, width: u32, st: &str) -> Result<(), D::Error> {
-        let bounds = Rectangle::new(self.pos, Size::new(width, self.text_renderer.line_height()));
+    /// Returns the top left corner of an object at the given position, in display coordinates.
+    fn visual_pos(&self, pos: Point, width: u32) -> Point {
+        match self.mirror {
+            Some(mirror) => Point::new(mirror - pos.x - width as i32, pos.y),
+            None => pos,
+        }
+    }
+
+    /// Returns the vertical offset of superscript or subscript text.
+    fn script_offset(&self) -> i32 {
+        self.style_state
+            .script
+            .offset(self.text_renderer.line_height())
+    }
+
+    /// Returns the position where an object at the given position needs to be drawn.
+    fn draw_pos(&self, pos: Point, width: u32) -> Point {
+        self.visual_pos(pos, width) + Point::new(0, self.baseline_offset + self.script_offset())
+    }
+
+    /// Returns the text renderer to draw with if any decoration is drawn by `post_print`.
+    ///
+    /// The returned renderer doesn't draw these decorations.
+    fn renderer_without_decorations(&self) -> Option<F> {
+        let underline =
+            self.style_state.underline != UnderlineStyle::Solid || self.underline.is_some();
+        if !underline && self.strikethrough.is_none() {
+            return None;
+        }
+
+        let mut renderer = self.text_renderer.clone();
+        if underline {
+            renderer.set_underline_color(DecorationColor::None);
+        }
+        if self.strikethrough.is_some() {
+            renderer.set_strikethrough_color(DecorationColor::None);
+        }
+
+        Some(renderer)
+    }
+
+    /// Draws the underline of `st`, unless it is drawn by the character style.
+    ///
+    /// The underline is drawn along the bottom of `bounds`, or at the position set by the style.
+    fn draw_underline(&mut self, st: &str, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        F: CharacterStyle<Color = <F as TextRenderer>::Color>,
+    {
+        let underline = self.style_state.underline;
+        let custom = self.underline;
+        if (underline == UnderlineStyle::Solid && custom.is_none()) || bounds.size.width == 0 {
+            return Ok(());
+        }
+
+        let color = match underline_color(self.text_renderer) {
+            Some(color) => color,
+            None => return Ok(()),
+        };
+
+        // The underline is drawn along the bottom row of `area`.
+        let height = custom.map_or(bounds.size.height, |custom| custom.offset.saturating_add(1));
+        let area = Rectangle::new(bounds.top_left, Size::new(bounds.size.width, height));
+
+        match custom {
+            Some(custom) if custom.skip_descenders => {
+                self.draw_underline_skipping_ink(st, area, underline, color)
+            }
+            _ => self
+                .display
+                .draw_iter(underline_pixels(underline, area, color)),
+        }
+    }
+
+    /// Draws an underline along the bottom row of `area`, with gaps around the glyphs of `st`
+    /// that cross it.
+    fn draw_underline_skipping_ink(
+        &mut self,
+        st: &str,
+        area: Rectangle,
+        underline: UnderlineStyle,
+        color: <F as TextRenderer>::Color,
+    ) -> Result<(), D::Error>
+    where
+        F: CharacterStyle<Color = <F as TextRenderer>::Color>,
+    {
+        // The rows of the underline, relative to the top of the glyphs.
+        let bottom = area.size.height as i32 - 1;
+        let rows = bottom + underline.rows()[0]..bottom + 1;
+
+        let layout = TextLayout {
+            letter_spacing: self.letter_spacing,
+            text_direction: match self.mirror {
+                Some(_) => TextDirection::RightToLeft,
+                None => TextDirection::LeftToRight,
+            },
+            baseline: self.baseline,
+        };
+
+        for (idx, cluster) in clusters(st) {
+            let range = idx..idx + cluster.len();
+            let (left, right) = x_range(self.text_renderer, &layout, st, area, range);
+
+            // The gap around the glyph, relative to the left of the glyph.
+            let gap = ink_extent(self.text_renderer, cluster, rows.clone())
+                .map(|(first, last)| (first - 1, last + 1));
+
+            let segments = match gap {
+                Some((first, last)) => [
+                    left..(left as i32 + first).clamp(left as i32, right as i32) as u32,
+                    (left as i32 + last + 1).clamp(left as i32, right as i32) as u32..right,
+                ],
+                None => [left..right, right..right],
+            };
+            for segment in segments.into_iter().filter(|segment| !segment.is_empty()) {
+                let segment_area = Rectangle::new(
+                    area.top_left + Point::new(segment.start as i32, 0),
+                    Size::new(segment.end - segment.start, area.size.height),
+                );
+                self.display
+                    .draw_iter(underline_pixels(underline, segment_area, color))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the strikethrough line of the text box style over `bounds`.
+    fn draw_strikethrough(&mut self, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        F: CharacterStyle<Color = <F as TextRenderer>::Color>,
+    {
+        let strikethrough = match self.strikethrough {
+            Some(strikethrough) if bounds.size.width != 0 => strikethrough,
+            _ => return Ok(()),
+        };
+
+        let color = match strikethrough_color(self.text_renderer) {
+            Some(color) => color,
+            None => return Ok(()),
+        };
+
+        let lines = 1 + strikethrough.double as u32;
+        for line in 0..lines {
+            let top = strikethrough.offset + 2 * line * strikethrough.thickness;
+            let area = Rectangle::new(
+                bounds.top_left + Point::new(0, top.saturating_as()),
+                Size::new(bounds.size.width, strikethrough.thickness),
+            );
+            self.display.fill_solid(&area, color)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fills an empty area at the given position using the background color.
+    fn draw_blank(&mut self, pos: Point, width: u32) -> Result<(), D::Error> {
+        if width > 0 {
+            let renderer = self.renderer_without_decorations();
+            renderer
+                .as_ref()
+                .unwrap_or(self.text_renderer)
+                .draw_whitespace(
+                    width,
+                    self.draw_pos(pos, width),
+                    self.baseline,
+                    self.display,
+                )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the area a piece of text with the given width is drawn to.
+    fn text_bounds(&self, width: u32) -> Rectangle {
+        let top = self.baseline_offset - self.font_baseline_offset + self.script_offset();
+        Rectangle::new(
+            self.visual_pos(self.pos, width) + Point::new(0, top),
+            Size::new(width, self.text_renderer.line_height()),
+        )
+    }
+
+    fn pre_print(&mut self, width: u32, st: &str)
+    where
+        F: CharacterStyle<Color = <F as TextRenderer>::Color>,
+    {
+        let bounds = self.text_bounds(width);
+        self.plugin.pre_render(self.text_renderer, st, bounds);
+    }
+
+    fn post_print(&mut self, width: u32, st: &str) -> Result<(), D::Error>
+    where
+        F: CharacterStyle<Color = <F as TextRenderer>::Color>,
+    {
+        let bounds = self.text_bounds(width);
 
         self.pos += Point::new(width as i32, 0);
 
+        self.draw_underline(st, bounds)?;
+        self.draw_strikethrough(bounds)?;
+
         self.plugin
             .post_render(self.display, self.text_renderer, Some(st), bounds)
     }
@@ -93,7 +413,7 @@ where
 
 impl<'a, 'c, F, D, M> ElementHandler for RenderElementHandler<'a, 'c, F, D, M>
 where
-    F: CharacterStyle + TextRenderer,
+    F: CharacterStyle + TextRenderer<Color = <F as CharacterStyle>::Color>,
     D: DrawTarget<Color = <F as TextRenderer>::Color>,
     M: Plugin<'a, <F as TextRenderer>::Color>,
     <F as CharacterStyle>::Color: Default,
@@ -102,24 +422,117 @@ where
     type Color = <F as CharacterStyle>::Color;
 
     fn measure(&self, st: &str) -> u32 {
-        str_width(self.text_renderer, st)
+        str_width_with_spacing(self.text_renderer, st, self.letter_spacing)
     }
 
     fn whitespace(&mut self, st: &str, _space_count: u32, width: u32) -> Result<(), Self::Error> {
-        if width > 0 {
-            self.text_renderer
-                .draw_whitespace(width, self.pos, Baseline::Top, self.display)?;
+        self.pre_print(width, st);
+        self.draw_blank(self.pos, width)?;
+        self.post_print(width, st)
+    }
+
+    fn tab_leader(&mut self, leader: char, width: u32) -> Result<(), Self::Error> {
+        self.pre_print(width, "\t");
+
+        let mut buffer = [0; 4];
+        let leader = &*leader.encode_utf8(&mut buffer);
+        let leader_width = str_width(self.text_renderer, leader);
+
+        // Only draw whole characters, so that the leaders don't extend past the tab stop.
+        let count = width.checked_div(leader_width).unwrap_or(0);
+        let renderer = self.renderer_without_decorations();
+        for i in 0..count {
+            let pos = self.pos + Point::new((i * leader_width) as i32, 0);
+            renderer
+                .as_ref()
+                .unwrap_or(self.text_renderer)
+                .draw_string(
+                    leader,
+                    self.draw_pos(pos, leader_width),
+                    self.baseline,
+                    self.display,
+                )?;
         }
 
-        self.post_print(width, st)
+        let leaders_width = count * leader_width;
+        self.draw_blank(
+            self.pos + Point::new(leaders_width as i32, 0),
+            width - leaders_width,
+        )?;
+
+        self.post_print(width, "\t")
     }
 
     fn printed_characters(&mut self, st: &str, width: Option<u32>) -> Result<(), Self::Error> {
-        let render_width =
-            self.text_renderer
-                .draw_string(st, self.pos, Baseline::Top, self.display)?;
+        self.pre_print(width.unwrap_or_else(|| self.measure(st)), st);
+
+        let renderer = self.renderer_without_decorations();
+        let render_width = if self.letter_spacing == 0
+            && self.mirror.is_none()
+            && !self.character_spacing.has_character_spacing()
+        {
+            let start = self.draw_pos(self.pos, 0);
+            let mut pos = start;
+            for run in visible_runs(st) {
+                pos = renderer
+                    .as_ref()
+                    .unwrap_or(self.text_renderer)
+                    .draw_string(run, pos, self.baseline, self.display)?;
+            }
+
+            (pos - start).x as u32
+        } else {
+            // Draw the characters one by one, inserting the extra space between them.
+            let mut pos = self.pos;
+            let mut gaps = 0;
+            let mut chars = clusters(st).peekable();
+            while let Some((_, c)) = chars.next() {
+                let char_width = str_width(self.text_renderer, c);
+                renderer
+                    .as_ref()
+                    .unwrap_or(self.text_renderer)
+                    .draw_string(
+                        cluster_base(c),
+                        self.draw_pos(pos, char_width),
+                        self.baseline,
+                        self.display,
+                    )?;
+
+                let next = pos + Point::new(char_width as i32, 0);
+                if self.letter_spacing > 0 && chars.peek().is_some() {
+                    let spacing = self.letter_spacing as u32;
+                    renderer
+                        .as_ref()
+                        .unwrap_or(self.text_renderer)
+                        .draw_whitespace(
+                            spacing,
+                            self.draw_pos(next, spacing),
+                            self.baseline,
+                            self.display,
+                        )?;
+                }
+                pos.x = next.x.saturating_add(self.letter_spacing).max(pos.x);
+
+                let gap = self.character_spacing.consume_character_gap();
+                if gap > 0 {
+                    renderer
+                        .as_ref()
+                        .unwrap_or(self.text_renderer)
+                        .draw_whitespace(
+                            gap,
+                            self.draw_pos(pos, gap),
+                            self.baseline,
+                            self.display,
+                        )?;
+                    pos.x += gap as i32;
+                    gaps += gap;
+                }
+            }
+
+            self.measure(st).saturating_add(gaps)
+        };
 
-        let width = width.unwrap_or((render_width - self.pos).x as u32);
+        let width = width.unwrap_or(render_width);
 
         self.post_print(width, st)
     }
@@ -130,11 +543,91 @@ where
         Ok(())
     }
 
+    fn horizontal_rule(&mut self) -> Result<(), Self::Error> {
+        // The rule is drawn through the center of the line, the rest is filled with the
+        // background color.
+        let rule_y = self.line_bounds.center().y;
+        let rule_color = text_color(self.text_renderer);
+        let background_color = background_color(self.text_renderer);
+
+        self.display
+            .draw_iter(self.line_bounds.points().filter_map(|p| {
+                let color = if p.y == rule_y {
+                    rule_color
+                } else {
+                    background_color
+                };
+                color.map(|color| Pixel(p, color))
+            }))
+    }
+
+    fn inline_object(&mut self, id: u32, size: Size) -> Result<(), Self::Error> {
+        // Objects stand on the alphabetic baseline of the current font, and include the baseline
+        // row like the glyphs do.
+        let bottom = self.baseline_offset - self.font_baseline_offset
+            + baseline_offset(self.text_renderer, Baseline::Alphabetic)
+            + 1;
+        let top = bottom - size.height.saturating_as::<i32>();
+        let bounds = Rectangle::new(
+            self.visual_pos(self.pos, size.width) + Point::new(0, top),
+            size,
+        );
+
+        self.pos += Point::new(size.width as i32, 0);
+
+        self.plugin.render_object(self.display, id, bounds)
+    }
+
     fn change_text_style(
         &mut self,
         change: ChangeTextStyle<<F as CharacterStyle>::Color>,
     ) -> Result<(), Self::Error> {
-        change.apply(self.text_renderer);
+        match change {
+            ChangeTextStyle::PushStyle => {
+                self.style_state.update(&self.fonts, change);
+                self.saved_styles.push(self.text_renderer.clone());
+                return Ok(());
+            }
+            ChangeTextStyle::PopStyle => {
+                self.style_state.update(&self.fonts, change);
+                if let Some(style) = self.saved_styles.pop() {
+                    self.font_baseline_offset = baseline_offset(&style, self.baseline);
+                    *self.text_renderer = style;
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let reversed = self.style_state.reverse;
+        if let Some(style) = self.style_state.update(&self.fonts, change) {
+            let previous = core::mem::replace(self.text_renderer, style.clone());
+            self.font_baseline_offset = baseline_offset(style, self.baseline);
+            if let ChangeTextStyle::Font(_) = change {
+                if reversed {
+                    swap_colors(self.text_renderer);
+                }
+                return Ok(());
+            }
+
+            // Bold and italic variants keep the current colors.
+            self.text_renderer.set_text_color(text_color(&previous));
+            self.text_renderer
+                .set_background_color(background_color(&previous));
+        }
+
+        match change {
+            ChangeTextStyle::ReverseVideo(reverse) if reverse != reversed => {
+                swap_colors(self.text_renderer)
+            }
+            ChangeTextStyle::TextColor(color) if reversed => {
+                self.text_renderer.set_background_color(color)
+            }
+            ChangeTextStyle::BackgroundColor(color) if reversed => {
+                self.text_renderer.set_text_color(color)
+            }
+            _ => change.apply(self.text_renderer),
+        }
         Ok(())
     }
 }
@@ -150,42 +643,88 @@ where
     where
         D: DrawTarget<Color = <F as CharacterStyle>::Color>,
     {
+        let mirror = match self.style.text_direction {
+            TextDirection::LeftToRight => None,
+            TextDirection::RightToLeft => {
+                Some(2 * self.cursor.pos().x + self.cursor.line_width() as i32)
+            }
+        };
+
+        let mut lm = self.measurement;
+
+        let ellipsis_width = if self.truncate && lm.line_end_type != LineEndType::EndOfText {
+            // The text does not end in this line. Make room for the ellipsis and measure again.
+            let ellipsis_width = str_width(&self.state.text_renderer, self.style.ellipsis);
+            self.cursor.shrink_width(ellipsis_width);
+            lm = self
+                .state
+                .measure_line(self.style, self.cursor.line_width());
+
+            Some(ellipsis_width)
+        } else {
+            None
+        };
+
         let LineRenderState {
             ref mut parser,
             ref mut text_renderer,
+            fonts,
+            ref mut style_state,
+            ref mut saved_styles,
             plugin,
             ..
         } = self.state;
 
-        let lm = {
-            // Ensure the clone lives for as short as possible.
-            let mut cloned_parser = parser.clone();
-            let measure_plugin = plugin.clone();
-            measure_plugin.set_state(ProcessingState::Measure);
-            self.style.measure_line(
-                &measure_plugin,
-                text_renderer,
-                &mut cloned_parser,
-                self.cursor.line_width(),
-            )
-        };
+        plugin.line_metrics(&lm);
+
+        let line_bounds = Rectangle::new(
+            self.cursor.pos(),
+            Size::new(self.cursor.line_width(), lm.line_height),
+        );
 
-        let (left, space_config) = self.style.alignment.place_line(text_renderer, lm);
+        let (left, space_config) = self.style.alignment.place_line(
+            self.style.space_width(text_renderer),
+            lm,
+            self.style.justify_last_line,
+            self.style.justify_max_space_width,
+            self.style.justify_characters,
+        );
+        let (left, space_config) = plugin.place_line(&lm, left, space_config);
 
         self.cursor.move_cursor(left).ok();
 
+        let font_baseline_offset = baseline_offset(text_renderer, self.style.baseline);
         let mut render_element_handler = RenderElementHandler {
             text_renderer,
+            fonts: *fonts,
+            style_state,
+            saved_styles,
             display,
             pos: self.cursor.pos(),
+            letter_spacing: self.style.letter_spacing,
             plugin: *plugin,
+            character_spacing: space_config,
+            mirror,
+            baseline: self.style.baseline,
+            // The position of the baseline is set by the full line, even if it's truncated.
+            baseline_offset: self.measurement.baseline_offset,
+            font_baseline_offset,
+            line_bounds,
+            strikethrough: self.style.strikethrough,
+            underline: self.style.underline,
         };
         let end_type =
             LineElementParser::new(parser, plugin, self.cursor, space_config, self.style)
                 .process(&mut render_element_handler)?;
 
+        if let Some(width) = ellipsis_width {
+            render_element_handler.printed_characters(self.style.ellipsis, Some(width))?;
+        }
+
+        let end_pos = render_element_handler.visual_pos(render_element_handler.pos, 0);
+        plugin.line_end(end_type, end_pos, &lm);
+
         if end_type == LineEndType::EndOfText {
-            let end_pos = render_element_handler.pos;
             plugin.post_render(
                 display,
                 text_renderer,
@@ -207,6 +746,7 @@ mod test {
         plugin::{NoPlugin, PluginWrapper},
         rendering::{
             cursor::LineCursor,
+            fonts::{Fonts, StyleStack, StyleState},
             line::{LineRenderState, StyledLineRenderer},
             line_iter::LineEndType,
         },
@@ -235,22 +775,28 @@ mod test {
         let parser = Parser::parse(text);
         let cursor = LineCursor::new(
             bounds.size.width,
-            TabSize::Spaces(4).into_pixels(&character_style),
+            TabSize::Spaces(4).into_tab_stops(&character_style),
         );
 
         let plugin = PluginWrapper::new(NoPlugin::new());
 
         let mut state = LineRenderState {
             parser,
-            text_renderer: character_style,
+            text_renderer: character_style.clone(),
+            fonts: Fonts::single(&character_style),
+            style_state: StyleState::new(),
+            saved_styles: StyleStack::new(),
             end_type: LineEndType::EndOfText,
             plugin: &plugin,
         };
 
+        let measurement = state.measure_line(&style, cursor.line_width());
         let renderer = StyledLineRenderer {
             cursor,
             state: &mut state,
             style: &style,
+            measurement,
+            truncate: false,
         };
         let mut display = MockDisplay::new();
         display.set_allow_overdraw(true);
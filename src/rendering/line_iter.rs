@@ -5,13 +5,14 @@
 //! handling tab characters, soft wrapping characters, non-breaking spaces, etc.
 
 use crate::{
-    parser::{ChangeTextStyle, Parser, Token, SPEC_CHAR_NBSP},
+    parser::{ChangeTextStyle, Parser, Token},
     plugin::{PluginMarker as Plugin, PluginWrapper},
     rendering::{cursor::LineCursor, space_config::SpaceConfig},
-    style::TextBoxStyle,
+    style::{TabAlignment, TextBoxStyle, WrapMode},
+    utils::clusters,
 };
 use az::SaturatingAs;
-use embedded_graphics::prelude::PixelColor;
+use embedded_graphics::prelude::{PixelColor, Size};
 
 /// Parser to break down a line into primitive elements used by measurement and rendering.
 #[derive(Debug)]
@@ -27,15 +28,29 @@ where
 
     spaces: SpaceConfig,
     empty: bool,
+
+    /// True if the previous token was a word, so the next word continues it.
+    in_word: bool,
+
+    /// True if the first word of the line is the rest of a URL that was wrapped.
+    continues_url: bool,
     plugin: &'b PluginWrapper<'a, M, C>,
     style: &'b TextBoxStyle,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The reason a line ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LineEndType {
+    /// The line ended with a line break character (`\n`).
     NewLine,
+
+    /// The line ended with a carriage return character (`\r`).
     CarriageReturn,
+
+    /// The line is the last line of the text.
     EndOfText,
+
+    /// The line was wrapped because the rest of the text didn't fit.
     LineBreak,
 }
 
@@ -51,6 +66,11 @@ pub trait ElementHandler {
         Ok(())
     }
 
+    /// The space before a tab stop, filled with the given leader character.
+    fn tab_leader(&mut self, _leader: char, width: u32) -> Result<(), Self::Error> {
+        self.whitespace("\t", 0, width)
+    }
+
     /// A string of printable characters.
     fn printed_characters(&mut self, _st: &str, _width: Option<u32>) -> Result<(), Self::Error> {
         Ok(())
@@ -68,6 +88,16 @@ pub trait ElementHandler {
     ) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    /// A horizontal rule across the line.
+    fn horizontal_rule(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// An inline object with the given size.
+    fn inline_object(&mut self, _id: u32, size: Size) -> Result<(), Self::Error> {
+        self.move_cursor(size.width.saturating_as())
+    }
 }
 
 impl<'a, 'b, M, C> LineElementParser<'a, 'b, M, C>
@@ -80,15 +110,21 @@ where
     pub fn new(
         parser: &'b mut Parser<'a, C>,
         plugin: &'b PluginWrapper<'a, M, C>,
-        cursor: LineCursor,
+        mut cursor: LineCursor,
         spaces: SpaceConfig,
         style: &'b TextBoxStyle,
     ) -> Self {
+        if style.effective_wrap_mode() == WrapMode::None {
+            cursor.unlimit_width();
+        }
+
         Self {
+            continues_url: parser.take_continues_url(),
             parser,
             spaces,
             cursor,
             empty: true,
+            in_word: false,
             plugin,
             style,
         }
@@ -109,6 +145,10 @@ where
                     width += handler.measure(w);
                     width_set = true;
                 }
+                Some(Token::InlineObject { size, .. }) => {
+                    width += size.width;
+                    width_set = true;
+                }
 
                 Some(Token::Break(w)) => return Some(width + handler.measure(w)),
                 Some(Token::ChangeTextStyle(_)) | Some(Token::MoveCursor { .. }) => {}
@@ -123,6 +163,107 @@ where
         }
     }
 
+    /// Returns the width of the words that continue the current one.
+    ///
+    /// Words are continued by words that follow them directly, or only separated by style changes.
+    fn continued_word_width<E: ElementHandler>(&self, handler: &E) -> u32 {
+        let lookahead = self.plugin.clone();
+        let mut lookahead_parser = self.parser.clone();
+
+        let mut width = 0;
+        loop {
+            lookahead.consume_peeked_token();
+            match lookahead.peek_token(&mut lookahead_parser) {
+                Some(Token::Word(w)) => width += handler.measure(w),
+                Some(Token::ChangeTextStyle(_)) => {}
+                _ => return width,
+            }
+        }
+    }
+
+    /// Returns whether wrapping after the current word would leave the last word of the paragraph
+    /// alone in the next line.
+    ///
+    /// `width` is the width of the current word, including the words that continue it.
+    fn leaves_widow<E: ElementHandler>(&self, handler: &E, width: u32) -> bool {
+        let lookahead = self.plugin.clone();
+        let mut lookahead_parser = self.parser.clone();
+
+        let space_width = self.style.add_word_spacing(handler.measure(" "));
+        let mut whitespace = 0_u32;
+        let mut next_word = 0_u32;
+        let mut in_word = true;
+
+        loop {
+            // Stop looking once the words can't fit together in a line.
+            let together = width.saturating_add(whitespace).saturating_add(next_word);
+            if together > self.cursor.line_width() {
+                return false;
+            }
+
+            lookahead.consume_peeked_token();
+            match lookahead.peek_token(&mut lookahead_parser) {
+                // The rest of the current word.
+                Some(Token::Word(_)) if in_word && next_word == 0 => {}
+                Some(Token::Word(w)) if in_word => {
+                    next_word = next_word.saturating_add(handler.measure(w))
+                }
+                Some(Token::Word(w)) if next_word == 0 => {
+                    in_word = true;
+                    next_word = handler.measure(w);
+                }
+                Some(Token::ChangeTextStyle(_)) => {}
+                Some(Token::Whitespace(n, _)) if next_word == 0 => {
+                    in_word = false;
+                    whitespace = whitespace.saturating_add(n.saturating_mul(space_width));
+                }
+                Some(Token::Whitespace(_, _)) => in_word = false,
+                Some(Token::NewLine) | Some(Token::CarriageReturn) | None => break,
+                _ => return false,
+            }
+        }
+
+        // The last word would only be alone if it doesn't fit after the current one, but the two
+        // words together fit in a line.
+        let together = width.saturating_add(whitespace).saturating_add(next_word);
+        next_word != 0
+            && !self.cursor.fits_in_line(together)
+            && together <= self.cursor.line_width()
+    }
+
+    /// Returns the distance between the tab and the point of the following text that is aligned to
+    /// the tab stop.
+    ///
+    /// The aligned text ends at the next tab or at the end of the line.
+    fn tab_aligned_width<E: ElementHandler>(&self, handler: &E) -> u32 {
+        let lookahead = self.plugin.clone();
+        let mut lookahead_parser = self.parser.clone();
+
+        let space_width = self.style.add_word_spacing(handler.measure(" "));
+        let mut width = 0;
+        loop {
+            lookahead.consume_peeked_token();
+            match lookahead.peek_token(&mut lookahead_parser) {
+                Some(Token::Word(w)) => match w.find('.') {
+                    Some(idx) if self.style.tab_alignment == TabAlignment::Decimal => {
+                        return width + handler.measure(&w[..idx]);
+                    }
+                    _ => width += handler.measure(w),
+                },
+                Some(Token::Whitespace(n, _)) => {
+                    width = width.saturating_add(n.saturating_mul(space_width))
+                }
+                Some(Token::ChangeTextStyle(_)) | Some(Token::Break(_)) => {}
+                _ => break,
+            }
+        }
+
+        match self.style.tab_alignment {
+            TabAlignment::Center => width / 2,
+            _ => width,
+        }
+    }
+
     fn move_cursor(&mut self, by: i32) -> Result<i32, i32> {
         self.cursor.move_cursor(by)
     }
@@ -137,11 +278,8 @@ where
         w: &'a str,
     ) -> (&'a str, &'a str) {
         let mut width = 0;
-        for (idx, c) in w.char_indices() {
-            let char_width = handler.measure(unsafe {
-                // SAFETY: we are working on character boundaries
-                w.get_unchecked(idx..idx + c.len_utf8())
-            });
+        for (idx, c) in clusters(w) {
+            let char_width = handler.measure(c);
             if !self.cursor.fits_in_line(width + char_width) {
                 unsafe {
                     if w.is_char_boundary(idx) {
@@ -151,12 +289,54 @@ where
                     }
                 }
             }
-            width += char_width;
+            // Add the letter spacing before the next character.
+            width = width.saturating_add(
+                char_width
+                    .saturating_as::<i32>()
+                    .saturating_add(self.style.letter_spacing)
+                    .max(0) as u32,
+            );
         }
 
         (w, "")
     }
 
+    /// Returns the points where the word can be wrapped at a word separator or inside a URL, as
+    /// the parts before and after the wrapping point.
+    fn separated_substrs(&self, w: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        let (separators, url) = match self.style.effective_wrap_mode() {
+            WrapMode::Word => (
+                self.style.word_separators,
+                self.style.break_urls && self.is_url_fragment(w),
+            ),
+            _ => ("", false),
+        };
+
+        w.char_indices()
+            .skip(1)
+            .map(move |(idx, _)| w.split_at(idx))
+            .filter(move |(before, after)| {
+                before.ends_with(|c| separators.contains(c)) || (url && is_url_break(before, after))
+            })
+    }
+
+    /// Returns whether a word is a URL, or the rest of a URL that was wrapped.
+    fn is_url_fragment(&self, w: &str) -> bool {
+        (self.continues_url && self.empty) || is_url(w)
+    }
+
+    /// Returns the longest part of the word that ends with a word separator and fits in the line,
+    /// and the rest of the word.
+    fn longest_separated_substr<E: ElementHandler>(
+        &self,
+        handler: &E,
+        w: &'a str,
+    ) -> Option<(&'a str, &'a str)> {
+        self.separated_substrs(w)
+            .take_while(|(word, _)| self.cursor.fits_in_line(handler.measure(word)))
+            .last()
+    }
+
     fn next_word_fits<E: ElementHandler>(&self, handler: &E) -> bool {
         let mut cursor = self.cursor.clone();
 
@@ -167,14 +347,47 @@ where
         let mut lookahead_parser = self.parser.clone();
 
         let mut exit = false;
+        let mut in_word = false;
         while !exit {
             lookahead.consume_peeked_token();
             let width = match lookahead.peek_token(&mut lookahead_parser) {
-                Some(Token::Word(w)) | Some(Token::Break(w)) => {
+                Some(Token::Word(w)) if self.style.effective_wrap_mode() == WrapMode::BreakAll => {
+                    // The word can be broken after its first character.
+                    exit = true;
+                    let first = clusters(w).next().map_or("", |(_, c)| c);
+                    handler.measure(first).saturating_as()
+                }
+
+                Some(Token::Word(w)) if self.separated_substrs(w).next().is_some() => {
+                    // The word can be wrapped after its first separator.
+                    exit = true;
+                    let first = self
+                        .separated_substrs(w)
+                        .next()
+                        .map_or("", |(word, _)| word);
+                    handler.measure(first).saturating_as()
+                }
+
+                Some(Token::Word(w)) => {
+                    // The word may be continued after a style change.
+                    in_word = true;
+                    handler.measure(w).saturating_as()
+                }
+
+                Some(Token::ChangeTextStyle(_)) => 0,
+
+                _ if in_word => break,
+
+                Some(Token::Break(w)) => {
                     exit = true;
                     handler.measure(w).saturating_as()
                 }
 
+                Some(Token::InlineObject { size, .. }) => {
+                    exit = true;
+                    size.width.saturating_as()
+                }
+
                 Some(Token::Whitespace(n, _)) => spaces.consume(n).saturating_as(),
                 Some(Token::Tab) => cursor.next_tab_width().saturating_as(),
 
@@ -182,8 +395,6 @@ where
                     chars * handler.measure(" ").saturating_as::<i32>()
                 }
 
-                Some(Token::ChangeTextStyle(_)) => 0,
-
                 _ => return false,
             };
 
@@ -196,11 +407,55 @@ where
     }
 
     fn render_trailing_spaces(&self) -> bool {
-        self.style.trailing_spaces
+        self.style.keeps_trailing_spaces()
     }
 
     fn skip_leading_spaces(&self) -> bool {
-        self.empty && !self.style.leading_spaces
+        self.empty && !self.style.keeps_leading_spaces()
+    }
+
+    /// Draws a word, or the part of it that is not hidden by the plugin.
+    ///
+    /// Hidden text still takes up space in the line.
+    fn render_word<E: ElementHandler>(
+        &mut self,
+        handler: &mut E,
+        word: &'a str,
+    ) -> Result<(), E::Error> {
+        let rendered = match self.plugin.render_token(Token::Word(word)) {
+            Some(Token::Word(rendered)) => rendered,
+            _ => "",
+        };
+
+        if !rendered.is_empty() {
+            self.process_word(handler, rendered)?;
+        }
+
+        if rendered.len() < word.len() {
+            let hidden_width = handler.measure(word) - handler.measure(rendered);
+            handler.move_cursor(hidden_width.saturating_as())?;
+        }
+
+        Ok(())
+    }
+
+    /// Draws whitespace, unless it is hidden by the plugin.
+    ///
+    /// Hidden whitespace still takes up space in the line.
+    fn render_whitespace<E: ElementHandler>(
+        &mut self,
+        handler: &mut E,
+        string: &'a str,
+        space_count: u32,
+        width: u32,
+    ) -> Result<(), E::Error> {
+        match self
+            .plugin
+            .render_token(Token::Whitespace(space_count, string))
+        {
+            Some(Token::Whitespace(_, _)) => handler.whitespace(string, space_count, width),
+            _ => handler.move_cursor(width.saturating_as()),
+        }
     }
 
     fn draw_whitespace<E: ElementHandler>(
@@ -217,11 +472,8 @@ where
 
         match self.move_cursor_forward(space_width) {
             Ok(moved) => {
-                handler.whitespace(
-                    string,
-                    space_count,
-                    moved * self.should_draw_whitespace(handler) as u32,
-                )?;
+                let width = moved * self.should_draw_whitespace(handler) as u32;
+                self.render_whitespace(handler, string, space_count, width)?;
                 Ok(false)
             }
 
@@ -241,15 +493,28 @@ where
                     let consumed_width = consumed * single;
 
                     let _ = self.move_cursor_forward(consumed_width);
-                    handler.whitespace(
+                    self.render_whitespace(
+                        handler,
                         consumed_str,
                         consumed,
                         consumed_width * self.render_trailing_spaces() as u32,
                     )?;
                 }
 
-                self.plugin
-                    .consume_partial((consumed + 1).min(space_count) as usize);
+                // The spaces at the wrapping point are removed, unless the whitespace is
+                // preformatted. Lines that can't fit any space need to remove one to make progress.
+                let removed = if self.style.preformatted {
+                    (consumed == 0 && self.empty) as u32
+                } else if self.style.trim_wrapped_spaces {
+                    space_count
+                } else {
+                    1
+                };
+                if consumed + removed >= space_count {
+                    self.consume_token();
+                } else {
+                    self.plugin.consume_partial((consumed + removed) as usize);
+                }
                 Ok(true)
             }
         }
@@ -260,11 +525,16 @@ where
             return Ok(());
         }
 
-        let space_width = self.cursor.next_tab_width();
+        let mut space_width = self.cursor.next_tab_width();
+        if self.style.tab_alignment != TabAlignment::Left {
+            space_width = space_width.saturating_sub(self.tab_aligned_width(handler));
+        }
+
         match self.move_cursor_forward(space_width) {
-            Ok(moved) if self.should_draw_whitespace(handler) => {
-                handler.whitespace("\t", 0, moved)?
-            }
+            Ok(moved) if self.should_draw_whitespace(handler) => match self.style.tab_leader {
+                Some(leader) => handler.tab_leader(leader, moved)?,
+                None => handler.whitespace("\t", 0, moved)?,
+            },
 
             Ok(moved) | Err(moved) => handler.move_cursor(moved as i32)?,
         }
@@ -285,6 +555,10 @@ where
         handler: &mut E,
     ) -> Result<LineEndType, E::Error> {
         while let Some(token) = self.peek_next_token() {
+            if !matches!(token, Token::Word(_) | Token::ChangeTextStyle(_)) {
+                self.in_word = false;
+            }
+
             match token {
                 Token::Whitespace(n, seq) => {
                     let space_width = self.spaces.consume(n);
@@ -322,20 +596,52 @@ where
 
                 Token::Word(w) => {
                     let width = handler.measure(w);
+
+                    // Words that are split by style changes are wrapped as a whole, unless they can
+                    // be wrapped after a word separator.
+                    if !self.in_word
+                        && !self.empty
+                        && self.style.effective_wrap_mode() == WrapMode::Word
+                        && !self
+                            .cursor
+                            .fits_in_line(width + self.continued_word_width(handler))
+                        && self.longest_separated_substr(handler, w).is_none()
+                    {
+                        return Ok(LineEndType::LineBreak);
+                    }
+
+                    // Move the word to the next line to keep the last word of the paragraph
+                    // company.
+                    if self.style.avoid_widows
+                        && !self.in_word
+                        && !self.empty
+                        && self.style.effective_wrap_mode() == WrapMode::Word
+                        && self.leaves_widow(handler, width + self.continued_word_width(handler))
+                    {
+                        return Ok(LineEndType::LineBreak);
+                    }
+
                     let (word, remainder) = if self.move_cursor_forward(width).is_ok() {
                         // We can move the cursor here since `process_word()`
                         // doesn't depend on it.
                         (w, "")
-                    } else if self.empty {
-                        // This word does not fit into an empty line. Find longest part
+                    } else if let Some(split) = self.longest_separated_substr(handler, w) {
+                        // Wrap the word after a word separator.
+                        split
+                    } else if self.empty
+                        || self.in_word
+                        || self.style.effective_wrap_mode() == WrapMode::BreakAll
+                    {
+                        // This word does not fit into the line. Find longest part
                         // that fits and push the rest to the next line.
                         match self.longest_fitting_substr(handler, w) {
-                            ("", _) => {
+                            ("", _) if self.empty => {
                                 // Weird case where width doesn't permit drawing anything.
                                 // End here to prevent infinite looping.
                                 self.consume_token();
                                 return Ok(LineEndType::LineBreak);
                             }
+                            ("", _) => return Ok(LineEndType::LineBreak),
                             other => other,
                         }
                     } else {
@@ -343,15 +649,18 @@ where
                         return Ok(LineEndType::LineBreak);
                     };
 
+                    let continues_url = self.continues_url && self.empty;
                     self.empty = false;
+                    self.in_word = true;
 
-                    if let Some(Token::Word(word)) = self.plugin.render_token(Token::Word(word)) {
-                        self.process_word(handler, word)?;
-                    }
+                    self.render_word(handler, word)?;
 
                     if !remainder.is_empty() {
+                        // The rest of a URL is wrapped like the URL itself.
+                        self.parser.set_continues_url(continues_url || is_url(w));
+
                         // Consume what was printed.
-                        self.plugin.consume_partial(word.len());
+                        self.plugin.consume_partial(word.chars().count());
                         return Ok(LineEndType::LineBreak);
                     }
                 }
@@ -395,9 +704,39 @@ where
 
                 Token::ChangeTextStyle(change) => handler.change_text_style(change)?,
 
+                Token::HorizontalRule(_) => {
+                    // The parser only emits rules at the start of a line.
+                    self.empty = false;
+                    if let Some(Token::HorizontalRule(_)) = self.plugin.render_token(token) {
+                        handler.horizontal_rule()?;
+                    }
+                }
+
+                Token::InlineObject { id, size } => {
+                    if self.move_cursor_forward(size.width).is_err() {
+                        if !self.empty {
+                            return Ok(LineEndType::LineBreak);
+                        }
+
+                        // The object is wider than the line, so it's cut off.
+                        let _ = self.move_cursor_forward(self.cursor.space());
+                    }
+
+                    self.empty = false;
+                    match self.plugin.render_token(token) {
+                        Some(Token::InlineObject { .. }) => handler.inline_object(id, size)?,
+                        _ => handler.move_cursor(size.width.saturating_as())?,
+                    }
+                }
+
                 Token::CarriageReturn => {
                     handler.whitespace("\r", 0, 0)?;
                     self.consume_token();
+
+                    // CRLF line endings are a single line break.
+                    if let Some(Token::NewLine) = self.peek_next_token() {
+                        continue;
+                    }
                     return Ok(LineEndType::CarriageReturn);
                 }
 
@@ -420,7 +759,8 @@ where
     ) -> Result<(), E::Error> {
         loop {
             let mut iter = w.char_indices();
-            match iter.find(|(_, c)| *c == SPEC_CHAR_NBSP) {
+            // Words only contain whitespace that must not break the line, e.g. non-breaking spaces.
+            match iter.find(|(_, c)| c.is_whitespace()) {
                 Some((space_pos, _)) => {
                     // If we have anything before the space...
                     if space_pos != 0 {
@@ -431,7 +771,11 @@ where
                         handler.printed_characters(word, None)?;
                     }
 
-                    handler.whitespace("\u{a0}", 1, self.spaces.consume(1))?;
+                    let space = unsafe {
+                        // Safety: space_pos is the character boundary before the space
+                        w.get_unchecked(space_pos..w.len() - iter.as_str().len())
+                    };
+                    handler.whitespace(space, 1, self.spaces.consume(1))?;
 
                     // If we have anything after the space...
                     w = iter.as_str();
@@ -450,6 +794,24 @@ where
     }
 }
 
+/// Returns whether a word looks like a URL.
+fn is_url(w: &str) -> bool {
+    let has_host = matches!(w.split_once('/'), Some((host, _)) if host.contains('.'));
+
+    w.contains("://") || w.starts_with("www.") || has_host
+}
+
+/// Returns whether a URL can be wrapped between `before` and `after`.
+///
+/// URLs are wrapped after `//` and before `/`, `?`, `&` and `.`, but never inside a `//`.
+fn is_url_break(before: &str, after: &str) -> bool {
+    if after.starts_with('/') {
+        !after.starts_with("//") && !before.ends_with('/')
+    } else {
+        before.ends_with("//") || after.starts_with(['?', '&', '.'])
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use std::convert::Infallible;
@@ -556,7 +918,7 @@ pub(crate) mod test {
             Rectangle::new(Point::zero(), size_for(&FONT_6X9, max_chars, 1)),
             style.line_height(),
             LineHeight::Percent(100),
-            TabSize::Spaces(4).into_pixels(&style),
+            TabSize::Spaces(4).into_tab_stops(&style),
         )
         .line();
 
@@ -582,7 +944,7 @@ pub(crate) mod test {
             Rectangle::new(Point::zero(), size_for(&FONT_6X9, 1, 1) - Size::new(1, 0)),
             style.line_height(),
             LineHeight::Percent(100),
-            TabSize::Spaces(4).into_pixels(&style),
+            TabSize::Spaces(4).into_tab_stops(&style),
         )
         .line();
 
@@ -676,33 +1038,42 @@ pub(crate) mod test {
             ],
             &mw,
         );
+        assert_line_elements(&mut parser, 5, &[RenderElement::string("f", 6)], &mw);
+    }
+
+    #[test]
+    fn soft_hyphen_issue_42() {
+        let mut parser =
+            Parser::parse("super\u{AD}cali\u{AD}fragi\u{AD}listic\u{AD}espeali\u{AD}docious");
+        let mw = PluginWrapper::new(NoPlugin::<Rgb888>::new());
+
+        assert_line_elements(&mut parser, 5, &[RenderElement::string("super", 30)], &mw);
         assert_line_elements(
             &mut parser,
             5,
             &[
-                RenderElement::Space(0, false), // FIXME: why 🤷‍♂️ Should have eaten in prev line
-                RenderElement::string("f", 6),
+                RenderElement::string("-", 6),
+                RenderElement::string("cali", 24),
             ],
             &mw,
         );
     }
 
     #[test]
-    fn soft_hyphen_issue_42() {
-        let mut parser =
-            Parser::parse("super\u{AD}cali\u{AD}fragi\u{AD}listic\u{AD}espeali\u{AD}docious");
+    fn soft_hyphen_long_word() {
+        let mut parser = Parser::parse("Silben\u{AD}trennung");
         let mw = PluginWrapper::new(NoPlugin::<Rgb888>::new());
 
-        assert_line_elements(&mut parser, 5, &[RenderElement::string("super", 30)], &mw);
         assert_line_elements(
             &mut parser,
-            5,
+            7,
             &[
+                RenderElement::string("Silben", 36),
                 RenderElement::string("-", 6),
-                RenderElement::string("cali", 24),
             ],
             &mw,
         );
+        assert_line_elements(&mut parser, 7, &[RenderElement::string("trennun", 42)], &mw);
     }
 
     #[test]
@@ -766,12 +1137,7 @@ pub(crate) mod test {
             ],
             &mw,
         );
-        assert_line_elements(
-            &mut parser,
-            10,
-            &[RenderElement::Space(1, true), RenderElement::string("s", 6)],
-            &mw,
-        );
+        assert_line_elements(&mut parser, 10, &[RenderElement::string("s", 6)], &mw);
     }
 
     #[test]
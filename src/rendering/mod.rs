@@ -1,26 +1,45 @@
 //! Pixel iterators used for text rendering.
 
 pub(crate) mod cursor;
+pub(crate) mod drop_cap;
+pub(crate) mod fade;
+pub(crate) mod fonts;
 pub(crate) mod line;
 pub(crate) mod line_iter;
+pub(crate) mod pages;
+pub(crate) mod position;
 pub(crate) mod space_config;
+pub(crate) mod strip;
+pub(crate) mod transformed;
 
 use crate::{
+    cache::{
+        CacheEntries, DrawnLine, Fnv, Frame, LineAction, MeasurementCache, Redraw, RedrawState,
+    },
     parser::Parser,
-    plugin::{PluginMarker as Plugin, ProcessingState},
+    plugin::{NoPlugin, PluginMarker as Plugin, PluginWrapper, ProcessingState},
     rendering::{
         cursor::Cursor,
+        drop_cap::DropCap,
+        fade::Fade,
+        fonts::{Fonts, StyleStack, StyleState},
         line::{LineRenderState, StyledLineRenderer},
+        strip::LineStrip,
+        transformed::{LayoutMapping, Transformed},
     },
-    style::TextBoxStyle,
+    style::{HeightMode, Rotation, TextBoxStyle, TextDirection},
+    utils::background_color,
     TextBox,
 };
 use az::SaturatingAs;
 use embedded_graphics::{
     draw_target::{DrawTarget, DrawTargetExt},
-    prelude::{Dimensions, Point, Size},
-    primitives::Rectangle,
-    text::renderer::{CharacterStyle, TextRenderer},
+    prelude::{Point, Size},
+    primitives::{Primitive, PrimitiveStyleBuilder, Rectangle, StrokeAlignment},
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Baseline,
+    },
     Drawable,
 };
 use line_iter::LineEndType;
@@ -39,10 +58,32 @@ pub struct TextBoxProperties<'a, S> {
     /// The height of the text.
     pub text_height: i32,
 
-    /// The bounds of the text box.
+    /// The bounds of the text area, which is the text box without its border and padding.
     pub bounding_box: Rectangle,
 }
 
+/// Optional behavior of a single draw.
+struct DrawOptions<'o, C> {
+    /// The measurements to reuse.
+    cache: Option<CacheEntries<'o>>,
+
+    /// The buffer that collects the pixels of each line.
+    buffer: Option<&'o mut [C]>,
+
+    /// The lines drawn by the previous draw.
+    redraw: Option<Redraw<'o>>,
+}
+
+impl<C> DrawOptions<'_, C> {
+    const fn new() -> Self {
+        Self {
+            cache: None,
+            buffer: None,
+            redraw: None,
+        }
+    }
+}
+
 impl<'a, F, M> Drawable for TextBox<'a, F, M>
 where
     F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
@@ -57,24 +98,231 @@ where
         &self,
         display: &mut D,
     ) -> Result<&'a str, D::Error> {
+        self.draw_with_plugin(display, &self.plugin)
+    }
+}
+
+impl<'a, F, M> TextBox<'a, F, M>
+where
+    F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
+    M: Plugin<'a, <F as TextRenderer>::Color>,
+    <F as CharacterStyle>::Color: Default,
+{
+    /// Draws the text box and returns the number of bytes of the text that were rendered.
+    ///
+    /// If the text doesn't fit into the text box, the rest of the text starts at the returned
+    /// byte offset. This can be used to continue the text in a different text box, e.g. to lay out
+    /// text in multiple columns.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mock_display::MockDisplay,
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # use embedded_text::TextBox;
+    /// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// # display.set_allow_overdraw(true);
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// let text = "Text that continues in the second column.";
+    ///
+    /// let first_column = Rectangle::new(Point::zero(), Size::new(30, 18));
+    /// let rendered = TextBox::new(text, first_column, character_style)
+    ///     .draw_continuation(&mut display)?;
+    ///
+    /// assert_eq!(&text[rendered..], "continues in the second column.");
+    ///
+    /// let second_column = Rectangle::new(Point::new(32, 0), Size::new(30, 18));
+    /// TextBox::new(&text[rendered..], second_column, character_style).draw(&mut display)?;
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    #[inline]
+    pub fn draw_continuation<D>(&self, display: &mut D) -> Result<usize, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+    {
+        let remaining = self.draw_with_plugin(display, &self.plugin)?;
+
+        Ok(self.text.len() - remaining.len())
+    }
+
+    /// Draws the text box, collecting the pixels of each line in `buffer` before drawing them.
+    ///
+    /// Every displayed line is first drawn into `buffer`, then drawn on the display using a single
+    /// [`fill_contiguous`] call. On displays that are connected through a slow bus, like SPI,
+    /// this is much faster than drawing the characters one by one.
+    ///
+    /// The pixels that aren't covered by text are drawn using the [`background_color`] of the
+    /// text box, so lines are only buffered if the text box has a background color. Lines that
+    /// need more pixels than `buffer` holds, i.e. more than the width of the text area times the
+    /// height of the line, are drawn directly, as are the lines of text boxes without a background
+    /// color.
+    ///
+    /// Because each line covers the area of the previous one, lines that overlap, e.g. because of
+    /// a small [`LineHeight`], are cut off at the top of the next line.
+    ///
+    /// [`fill_contiguous`]: DrawTarget::fill_contiguous()
+    /// [`background_color`]: TextBox::background_color
+    /// [`LineHeight`]: embedded_graphics::text::LineHeight
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mock_display::MockDisplay,
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::Rgb565,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # use embedded_text::TextBox;
+    /// # let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// # display.set_allow_overdraw(true);
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, Rgb565::WHITE);
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 27));
+    ///
+    /// // Room for a line of 60x9 pixels.
+    /// let mut buffer = [Rgb565::BLACK; 60 * 9];
+    ///
+    /// TextBox::new("Lines are drawn at once.", bounding_box, character_style)
+    ///     .set_background_color(Some(Rgb565::BLACK))
+    ///     .draw_buffered(&mut display, &mut buffer)?;
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    #[inline]
+    pub fn draw_buffered<D>(
+        &self,
+        display: &mut D,
+        buffer: &mut [<F as CharacterStyle>::Color],
+    ) -> Result<&'a str, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+    {
+        self.draw_with_options(
+            display,
+            &self.plugin,
+            DrawOptions {
+                buffer: Some(buffer),
+                ..DrawOptions::new()
+            },
+        )
+    }
+
+    /// Draws the text box using the given plugin instead of the text box's own.
+    pub(crate) fn draw_with_plugin<D, P>(
+        &self,
+        display: &mut D,
+        plugin: &PluginWrapper<'a, P, <F as CharacterStyle>::Color>,
+    ) -> Result<&'a str, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+        P: Plugin<'a, <F as CharacterStyle>::Color>,
+    {
+        self.draw_with_options(display, plugin, DrawOptions::new())
+    }
+
+    /// Returns the mapping from the box the text is laid out in to the bounding box.
+    pub(crate) fn layout_mapping(&self) -> LayoutMapping {
+        LayoutMapping::new(self.style.rotation, self.style.scale_factor(), self.bounds)
+    }
+
+    /// Draws the text box using the given plugin and options.
+    fn draw_with_options<D, P>(
+        &self,
+        display: &mut D,
+        plugin: &PluginWrapper<'a, P, <F as CharacterStyle>::Color>,
+        options: DrawOptions<'_, <F as CharacterStyle>::Color>,
+    ) -> Result<&'a str, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+        P: Plugin<'a, <F as CharacterStyle>::Color>,
+    {
+        if self.style.rotation == Rotation::Rotate0 && self.style.scale_factor() == 1 {
+            return self.draw_in(display, plugin, options, self.bounds);
+        }
+
+        self.draw_in(
+            &mut Transformed::new(display, self.layout_mapping()),
+            plugin,
+            options,
+            Rectangle::new(
+                self.bounds.top_left,
+                self.style.layout_size(self.bounds.size),
+            ),
+        )
+    }
+
+    /// Lays out and draws the text box in the given bounds.
+    fn draw_in<D, P>(
+        &self,
+        display: &mut D,
+        plugin: &PluginWrapper<'a, P, <F as CharacterStyle>::Color>,
+        mut options: DrawOptions<'_, <F as CharacterStyle>::Color>,
+        bounds: Rectangle,
+    ) -> Result<&'a str, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+        P: Plugin<'a, <F as CharacterStyle>::Color>,
+    {
+        // Unchanged text boxes are redrawn line by line.
+        let full = match &options.redraw {
+            Some(redraw) => redraw.full,
+            None => true,
+        };
+
+        if let (true, Some(color)) = (full, self.background_color) {
+            display.fill_solid(&bounds, color)?;
+        }
+
+        if let (true, Some(color)) = (full, self.border_color) {
+            bounds
+                .into_styled(
+                    PrimitiveStyleBuilder::new()
+                        .stroke_color(color)
+                        .stroke_width(self.style.border_width)
+                        .stroke_alignment(StrokeAlignment::Inside)
+                        .build(),
+                )
+                .draw(display)?;
+        }
+
+        let text_area = self.style.insets().shrink(bounds);
+
+        let fonts = Fonts::new(&self.character_style, self.fonts, self.font_variants)
+            .with_drop_cap(self.drop_cap);
         let mut cursor = Cursor::new(
-            self.bounds,
+            text_area,
             self.character_style.line_height(),
             self.style.line_height,
-            self.style.tab_size.into_pixels(&self.character_style),
+            self.style.tab_size.into_tab_stops(&self.character_style),
         );
 
-        let text_height = self
-            .style
-            .measure_text_height_impl(
-                self.plugin.clone(),
-                &self.character_style,
+        let measure_text_height = || {
+            self.style.measure_text_height_impl(
+                plugin.clone(),
+                fonts,
                 self.text,
                 cursor.line_width(),
             )
-            .saturating_as::<i32>();
+        };
+        let text_height = match options.cache {
+            Some(cache) => cache.text_height(measure_text_height),
+            None => measure_text_height(),
+        }
+        .saturating_as::<i32>();
+
+        let box_height = text_area.size.height.saturating_as::<i32>();
 
-        let box_height = self.bounding_box().size.height.saturating_as::<i32>();
+        // Truncated text always starts at the top of the text box.
+        let text_height = if self.style.height_mode.truncates() {
+            text_height.min(box_height)
+        } else {
+            text_height
+        };
 
         self.style.vertical_alignment.apply_vertical_alignment(
             &mut cursor,
@@ -88,24 +336,62 @@ where
             box_style: &self.style,
             char_style: &self.character_style,
             text_height,
-            bounding_box: self.bounding_box(),
+            bounding_box: text_area,
         };
 
-        self.plugin.on_start_render(&mut cursor, props);
+        plugin.on_start_render(&mut cursor, props);
+        plugin.pre_render_box(display, bounds)?;
 
         let mut state = LineRenderState {
             text_renderer: self.character_style.clone(),
-            parser: Parser::parse(self.text),
+            fonts,
+            style_state: StyleState::new(),
+            saved_styles: StyleStack::new(),
+            parser: Parser::parse(self.text).with_horizontal_rules(self.style.horizontal_rules),
             end_type: LineEndType::EndOfText,
-            plugin: &self.plugin,
+            plugin,
         };
 
         state.plugin.set_state(ProcessingState::Render);
 
+        let drop_cap = DropCap::take(&self.style, fonts, plugin, &mut state.parser);
+        let drop_cap_origin = cursor.line_start();
+
+        let max_lines = self.style.max_lines.unwrap_or(usize::MAX);
+        let mut line_count = 0;
+        let mut measured_lines = 0;
+        let mut displayed_lines = 0;
+        let mut paragraph_start = true;
+
         let mut anything_drawn = false;
         loop {
             state.plugin.new_line();
 
+            let mut line_cursor = cursor.line();
+            line_cursor.indent(
+                self.style
+                    .line_indent(paragraph_start)
+                    .saturating_add(state.plugin.line_indent(&self.character_style))
+                    .saturating_add(drop_cap.map_or(0, |d| d.line_indent(measured_lines))),
+            );
+
+            // The height of the line depends on the fonts used in it.
+            let line_width = line_cursor.line_width();
+            let line_offset = self.text.len() - state.parser.as_str().len();
+            let measure = || {
+                let (measurement, rest) = state.measure_line_and_rest(&self.style, line_width);
+                (measurement, self.text.len() - rest.len())
+            };
+            let (measurement, line_end) = match options.cache {
+                Some(cache) => cache.line(measured_lines, line_offset, line_width, measure),
+                None => measure(),
+            };
+            measured_lines += 1;
+            cursor.set_line_height(
+                measurement.line_height,
+                self.style.line_spacing(measurement.line_height),
+            );
+
             let display_range = self
                 .style
                 .height_mode
@@ -118,44 +404,177 @@ where
 
             // FIXME: cropping isn't necessary for whole lines, but make sure not to blow up the
             // binary size as well. We could also use a different way to consume invisible text.
-            let mut display = display.clipped(&Rectangle::new(
+            let display_area = Rectangle::new(
                 line_start + Point::new(0, display_range_start),
                 display_size,
-            ));
-            if display_range_count == 0 {
-                // Display range can be empty if we are above, or below the visible text section
-                if anything_drawn {
+            );
+
+            let displayed = line_count != max_lines && display_range_count != 0;
+            let truncate = self
+                .style
+                .height_mode
+                .should_truncate(&cursor, line_count + 1 == max_lines);
+
+            let action = match options.redraw.as_mut() {
+                Some(redraw) if displayed => {
+                    let hash = Fnv::hash_of(&(
+                        self.text.get(line_offset..line_end),
+                        measurement,
+                        line_start,
+                        line_width,
+                        paragraph_start,
+                        truncate,
+                    ));
+                    redraw.line(
+                        displayed_lines,
+                        DrawnLine {
+                            hash,
+                            area: display_area,
+                        },
+                    )
+                }
+                _ => LineAction::Draw,
+            };
+            match action {
+                LineAction::Restart => return Ok(""),
+                LineAction::Redraw => {
+                    if let Some(color) = self.background_color {
+                        display.fill_solid(&display_area, color)?;
+                    }
+                }
+                LineAction::Skip | LineAction::Draw => {}
+            }
+
+            // The drop cap spans multiple lines, so it's drawn again if any of them is redrawn.
+            if let Some(drop_cap) = drop_cap {
+                let first_line = measured_lines == 1 && action != LineAction::Skip;
+                let redrawn = measured_lines <= drop_cap.lines && action == LineAction::Redraw;
+                if first_line || redrawn {
+                    drop_cap.style.draw_string(
+                        drop_cap.letter,
+                        drop_cap_origin,
+                        Baseline::Top,
+                        &mut display.clipped(&text_area),
+                    )?;
+                }
+            }
+
+            // Unchanged lines are only consumed.
+            let mut line_display = display.clipped(&if action == LineAction::Skip {
+                Rectangle::zero()
+            } else {
+                display_area
+            });
+            if !displayed {
+                // Display range can be empty if we are above, or below the visible text section.
+                // If nothing was drawn yet, the line is below the visible section if the text
+                // box is too small to display it.
+                let below = anything_drawn || cursor.y >= cursor.top_left().y;
+                if below || line_count == max_lines {
                     // We are below, so we won't be drawing anything else
-                    let remaining_bytes = state.parser.as_str().len();
-                    let consumed_bytes = self.text.len() - remaining_bytes;
+                    let remaining_text = state.plugin.remaining_text(&state.parser);
 
                     state.plugin.post_render(
-                        &mut display,
+                        &mut line_display,
                         &self.character_style,
                         None,
                         Rectangle::new(line_start, Size::new(0, cursor.line_height())),
                     )?;
+                    state.plugin.post_render_box(display, bounds)?;
                     state.plugin.on_rendering_finished();
-                    return Ok(self.text.get(consumed_bytes..).unwrap());
+                    if let Some(redraw) = options.redraw.as_mut() {
+                        redraw.finish(displayed_lines);
+                    }
+                    return Ok(remaining_text);
                 }
-            } else {
-                anything_drawn = true;
             }
 
-            StyledLineRenderer {
-                cursor: cursor.line(),
+            let line_bounds = Rectangle::new(
+                line_start,
+                Size::new(cursor.line_width(), cursor.line_height()),
+            );
+            // The end of the last visible line fades out, instead of being replaced by an
+            // ellipsis.
+            let fade = match self.style.height_mode {
+                HeightMode::TruncateWithFade(width)
+                    if truncate && measurement.line_end_type != LineEndType::EndOfText =>
+                {
+                    let size =
+                        Size::new(width.min(line_bounds.size.width), line_bounds.size.height);
+                    Some(match self.style.text_direction {
+                        TextDirection::LeftToRight => Rectangle::new(
+                            line_start
+                                + Point::new((line_bounds.size.width - size.width) as i32, 0),
+                            size,
+                        ),
+                        TextDirection::RightToLeft => Rectangle::new(line_start, size),
+                    })
+                }
+                _ => None,
+            };
+            let fade_reversed = self.style.text_direction == TextDirection::RightToLeft;
+            let fade_background = background_color(&self.character_style);
+
+            let plugin = state.plugin;
+            let renderer = StyledLineRenderer {
+                cursor: line_cursor,
                 state: &mut state,
                 style: &self.style,
+                measurement,
+                truncate: truncate && fade.is_none(),
+            };
+
+            let strip = match (options.buffer.as_deref_mut(), self.background_color) {
+                (Some(buffer), Some(background)) if displayed => {
+                    LineStrip::new(buffer, display_area, background)
+                }
+                _ => None,
+            };
+
+            if displayed {
+                anything_drawn = true;
+                displayed_lines += 1;
+            }
+            match strip {
+                Some(mut strip) => {
+                    plugin.post_line_start(&mut strip, line_bounds)?;
+                    match fade {
+                        Some(area) => renderer.draw(&mut Fade::new(
+                            &mut strip,
+                            area,
+                            fade_reversed,
+                            fade_background,
+                        ))?,
+                        None => renderer.draw(&mut strip)?,
+                    }
+                    strip.flush(&mut line_display)?;
+                }
+                None => {
+                    if displayed {
+                        plugin.post_line_start(&mut line_display, line_bounds)?;
+                    }
+                    match fade {
+                        Some(area) => renderer.draw(&mut Fade::new(
+                            &mut line_display,
+                            area,
+                            fade_reversed,
+                            fade_background,
+                        ))?,
+                        None => renderer.draw(&mut line_display)?,
+                    }
+                }
             }
-            .draw(&mut display)?;
 
             match state.end_type {
                 LineEndType::EndOfText => {
+                    state.plugin.post_render_box(display, bounds)?;
                     state.plugin.on_rendering_finished();
                     break;
                 }
                 LineEndType::CarriageReturn => {}
                 _ => {
+                    line_count += 1;
+                    paragraph_start = state.end_type == LineEndType::NewLine;
                     cursor.new_line();
 
                     if state.end_type == LineEndType::NewLine {
@@ -165,26 +584,140 @@ where
             }
         }
 
+        if let Some(redraw) = options.redraw.as_mut() {
+            redraw.finish(displayed_lines);
+        }
+
         Ok("")
     }
 }
 
+impl<'a, F> TextBox<'a, F, NoPlugin<<F as CharacterStyle>::Color>>
+where
+    F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
+    <F as CharacterStyle>::Color: Default,
+{
+    /// Draws the text box, reusing the measurements stored in `cache` by previous draws.
+    ///
+    /// The text box is drawn the same way as by [`draw`], but the text is only measured when
+    /// the cache doesn't hold the measurements of this text box. This is useful for text boxes
+    /// that are redrawn often without changes. See [`MeasurementCache`] for the changes that
+    /// discard the cached measurements.
+    ///
+    /// Text boxes with plugins can't be drawn using a cache, because plugins can change the
+    /// text and its measurements between draws.
+    ///
+    /// [`draw`]: Drawable::draw()
+    #[inline]
+    pub fn draw_cached<D, const N: usize>(
+        &self,
+        display: &mut D,
+        cache: &MeasurementCache<N>,
+    ) -> Result<&'a str, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+    {
+        let entries = cache.entries(self.text, self.bounds.size, &self.style);
+
+        self.draw_with_options(
+            display,
+            &self.plugin,
+            DrawOptions {
+                cache: Some(entries),
+                ..DrawOptions::new()
+            },
+        )
+    }
+
+    /// Draws the lines of the text box that have changed since the previous draw.
+    ///
+    /// `previous` holds the lines drawn by the previous call. Lines that haven't changed are not
+    /// drawn again, and changed lines are erased using the [`background_color`] of the text box
+    /// before they are drawn. If the text box has no background color, the changed lines can't be
+    /// erased, so the whole text box is drawn every time. See [`RedrawState`] for the changes that
+    /// cause the whole text box to be redrawn.
+    ///
+    /// Text boxes with plugins can't be drawn incrementally, because plugins can change how the
+    /// text is drawn between draws.
+    ///
+    /// [`background_color`]: TextBox::background_color
+    #[inline]
+    pub fn draw_incremental<D, const N: usize>(
+        &self,
+        display: &mut D,
+        previous: &mut RedrawState<F, N>,
+    ) -> Result<&'a str, D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+        F: PartialEq,
+    {
+        let background_color = match self.background_color {
+            Some(color) => color,
+            None => {
+                previous.clear();
+                return self.draw(display);
+            }
+        };
+
+        let frame = Frame {
+            character_style: self.character_style.clone(),
+            style: self.style,
+            bounds: self.bounds,
+            vertical_offset: self.vertical_offset,
+            background_color,
+            border_color: self.border_color,
+        };
+
+        // Lines continued after a carriage return share their area with the previous line.
+        let full = self.text.contains('\r');
+        let remaining = self.draw_with_options(
+            display,
+            &self.plugin,
+            DrawOptions {
+                redraw: Some(previous.begin(frame.clone(), full)),
+                ..DrawOptions::new()
+            },
+        )?;
+        if !previous.needs_restart() {
+            return Ok(remaining);
+        }
+
+        self.draw_with_options(
+            display,
+            &self.plugin,
+            DrawOptions {
+                redraw: Some(previous.begin(frame, true)),
+                ..DrawOptions::new()
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use embedded_graphics::{
         mock_display::MockDisplay,
         mono_font::{
-            ascii::{FONT_6X10, FONT_6X9},
-            MonoTextStyleBuilder,
+            ascii::{FONT_6X10, FONT_6X13, FONT_6X9},
+            MonoTextStyle, MonoTextStyleBuilder,
         },
         pixelcolor::BinaryColor,
         prelude::*,
         primitives::Rectangle,
+        text::{Baseline, DecorationColor, LineHeight},
     };
 
+    use std::{cell::RefCell, rc::Rc};
+
     use crate::{
         alignment::HorizontalAlignment,
-        style::{HeightMode, TextBoxStyle, TextBoxStyleBuilder, VerticalOverdraw},
+        parser::{ChangeTextStyle, Token, UnderlineStyle},
+        plugin::private::Plugin,
+        style::{
+            HeightMode, LineEndType, LineMeasurement, Padding, Rotation, Strikethrough,
+            TabAlignment, TabSize, TextBoxStyle, TextBoxStyleBuilder, TextDirection, Underline,
+            VerticalOverdraw, WrapMode,
+        },
         utils::test::size_for,
         TextBox,
     };
@@ -226,6 +759,241 @@ pub mod test {
         display.assert_pattern(pattern);
     }
 
+    #[test]
+    fn crlf_is_a_single_line_break() {
+        assert_rendered(
+            HorizontalAlignment::Left,
+            "ab\r\ncd",
+            size_for(&FONT_6X9, 2, 3),
+            &[
+                "............",
+                ".......#....",
+                ".......#....",
+                "..###..###..",
+                ".#..#..#..#.",
+                ".#..#..#..#.",
+                "..###..###..",
+                "............",
+                "............",
+                "............",
+                "..........#.",
+                "..........#.",
+                "..###...###.",
+                ".#.....#..#.",
+                ".#.....#..#.",
+                "..###...###.",
+                "............",
+                "............",
+            ],
+        );
+    }
+
+    #[test]
+    fn words_are_wrapped_after_separators() {
+        let style = TextBoxStyleBuilder::new().word_separators("/").build();
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 10, 3));
+
+        for (text, wrapped) in [
+            (
+                "usr/local/bin/very/long/path",
+                "usr/local/\nbin/very/\nlong/path",
+            ),
+            ("see usr/local/bin", "see usr/\nlocal/bin"),
+        ] {
+            let mut expected = MockDisplay::new();
+            TextBox::new(wrapped, bounding_box, character_style)
+                .draw(&mut expected)
+                .unwrap();
+
+            let mut display = MockDisplay::new();
+            TextBox::with_textbox_style(text, bounding_box, character_style, style)
+                .draw(&mut display)
+                .unwrap();
+
+            display.assert_eq(&expected);
+        }
+    }
+
+    #[test]
+    fn urls_are_wrapped_at_separators() {
+        let style = TextBoxStyleBuilder::new().break_urls(true).build();
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 12, 4));
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(
+            "https://\nexample.com\n/path?a=1\n&b=2",
+            bounding_box,
+            character_style,
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::with_textbox_style(
+            "https://example.com/path?a=1&b=2",
+            bounding_box,
+            character_style,
+            style,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn prose_is_not_wrapped_like_urls() {
+        let style = TextBoxStyleBuilder::new().break_urls(true).build();
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 8, 3));
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("wait\n...and\n/s", bounding_box, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::with_textbox_style("wait ...and /s", bounding_box, character_style, style)
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn horizontal_rule() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Center)
+                .horizontal_rules(true)
+                .build(),
+            "a\n---\nb",
+            size_for(&FONT_6X9, 3, 3),
+            &[
+                "      ......      ",
+                "      ......      ",
+                "      ......      ",
+                "      ..###.      ",
+                "      .#..#.      ",
+                "      .#..#.      ",
+                "      ..###.      ",
+                "      ......      ",
+                "      ......      ",
+                "..................",
+                "..................",
+                "..................",
+                "..................",
+                "##################",
+                "..................",
+                "..................",
+                "..................",
+                "..................",
+                "      ......      ",
+                "      .#....      ",
+                "      .#....      ",
+                "      .###..      ",
+                "      .#..#.      ",
+                "      .#..#.      ",
+                "      .###..      ",
+                "      ......      ",
+                "      ......      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn key_value_table() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new().key_value_table(12).build(),
+            "a\tbb cc\nd\te",
+            size_for(&FONT_6X9, 4, 3),
+            &[
+                "........................",
+                ".............#.....#....",
+                ".............#.....#....",
+                "..###........###...###..",
+                ".#..#........#..#..#..#.",
+                ".#..#........#..#..#..#.",
+                "..###........###...###..",
+                "........................",
+                "........................",
+                "            ............",
+                "            ............",
+                "            ............",
+                "            ..###...###.",
+                "            .#.....#....",
+                "            .#.....#....",
+                "            ..###...###.",
+                "            ............",
+                "            ............",
+                "..................      ",
+                "....#.............      ",
+                "....#.............      ",
+                "..###.........##..      ",
+                ".#..#........#.##.      ",
+                ".#..#........##...      ",
+                "..###.........###.      ",
+                "..................      ",
+                "..................      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn font_metrics() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let style = TextBoxStyleBuilder::new()
+            .line_height(LineHeight::Percent(150))
+            .scale(2)
+            .build();
+
+        let metrics = TextBox::with_textbox_style("", Rectangle::zero(), character_style, style)
+            .font_metrics();
+
+        assert_eq!(metrics.ascent, 2 * 6);
+        assert_eq!(metrics.descent, 2 * 3);
+        assert_eq!(metrics.line_spacing, 2 * 13);
+    }
+
+    #[test]
+    fn scroll_metrics() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let style = TextBoxStyleBuilder::new()
+            .padding(Padding::new(1, 2, 3, 4))
+            .scale(2)
+            .build();
+
+        let mut text_box = TextBox::with_textbox_style(
+            "ab cd ef gh",
+            Rectangle::new(Point::zero(), Size::new(2 * 18, 2 * 24)),
+            character_style,
+            style,
+        );
+        text_box.set_vertical_offset(5);
+
+        let metrics = text_box.scroll_metrics();
+
+        // The text area is 12x20 pixels, so every word is on its own line.
+        assert_eq!(metrics.content_height, 4 * 9);
+        assert_eq!(metrics.viewport_height, 20);
+        assert_eq!(metrics.offset, -5);
+        assert_eq!(metrics.max_offset(), 16);
+    }
+
     #[test]
     fn nbsp_doesnt_break() {
         assert_rendered(
@@ -333,6 +1101,50 @@ pub mod test {
         ]);
     }
 
+    #[test]
+    fn vertical_offset_scrolls_lines() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_textbox_style(
+            "ab\ncd\nef\ngh",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 2)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::Exact(VerticalOverdraw::Hidden))
+                .build(),
+        )
+        .set_vertical_offset(-13)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            ".#.....#..#.",
+            ".#.....#..#.",
+            "..###...###.",
+            "............",
+            "............",
+            "............",
+            ".........#..",
+            "........#.#.",
+            "..##....#...",
+            ".#.##..###..",
+            ".##.....#...",
+            "..###...#...",
+            "............",
+            "............",
+            "............",
+            ".......#....",
+            ".......#....",
+            "..##...###..",
+        ]);
+    }
+
     #[test]
     fn rendering_not_stopped_prematurely() {
         let mut display = MockDisplay::new();
@@ -370,26 +1182,1186 @@ pub mod test {
     }
 
     #[test]
-    fn space_wrapping_issue() {
-        let mut display = MockDisplay::new();
+    fn letter_spacing() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new().letter_spacing(2).build(),
+            "ab cd ef",
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "..................................",
+                ".........#......................#.",
+                ".........#......................#.",
+                "..###....###..........###.....###.",
+                ".#..#....#..#........#.......#..#.",
+                ".#..#....#..#........#.......#..#.",
+                "..###....###..........###.....###.",
+                "..................................",
+                "..................................",
+                "..............                    ",
+                "...........#..                    ",
+                "..........#.#.                    ",
+                "..##......#...                    ",
+                ".#.##....###..                    ",
+                ".##.......#...                    ",
+                "..###.....#...                    ",
+                "..............                    ",
+                "..............                    ",
+            ],
+        );
+    }
 
-        let character_style = MonoTextStyleBuilder::new()
-            .font(&FONT_6X10)
-            .text_color(BinaryColor::On)
-            .background_color(BinaryColor::Off)
-            .build();
+    #[test]
+    fn huge_letter_spacing_does_not_overflow() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
 
-        TextBox::with_textbox_style(
-            "Hello,      s",
-            Rectangle::new(Point::zero(), size_for(&FONT_6X10, 10, 2)),
-            character_style,
+        for letter_spacing in [i32::MAX, i32::MIN] {
+            let mut display = MockDisplay::new();
+            display.set_allow_out_of_bounds_drawing(true);
+            display.set_allow_overdraw(true);
+
+            TextBox::with_textbox_style(
+                "ab cd",
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 2)),
+                character_style,
+                TextBoxStyleBuilder::new()
+                    .letter_spacing(letter_spacing)
+                    .build(),
+            )
+            .draw(&mut display)
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn word_spacing_is_stretched_by_justification() {
+        // The widened spaces make the last word wrap, then the first line is stretched.
+        assert_styled_rendered(
             TextBoxStyleBuilder::new()
-                .height_mode(HeightMode::Exact(VerticalOverdraw::Hidden))
-                .trailing_spaces(true)
+                .alignment(HorizontalAlignment::Justified)
+                .word_spacing(6)
                 .build(),
-        )
-        .draw(&mut display)
-        .unwrap();
+            "a b c",
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "....................................",
+                "...............................#....",
+                "...............................#....",
+                "..###..........................###..",
+                ".#..#..........................#..#.",
+                ".#..#..........................#..#.",
+                "..###..........................###..",
+                "....................................",
+                "....................................",
+                "......                              ",
+                "......                              ",
+                "......                              ",
+                "..###.                              ",
+                ".#....                              ",
+                ".#....                              ",
+                "..###.                              ",
+                "......                              ",
+                "......                              ",
+            ],
+        );
+    }
+
+    #[test]
+    fn right_to_left() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .text_direction(TextDirection::RightToLeft)
+                .build(),
+            "ab cd ef",
+            size_for(&FONT_6X9, 7, 2),
+            &[
+                "            ..............................",
+                "            ....#..............#..........",
+                "            ....#..............#..........",
+                "            ..###...###........###....###.",
+                "            .#..#..#...........#..#..#..#.",
+                "            .#..#..#...........#..#..#..#.",
+                "            ..###...###........###....###.",
+                "            ..............................",
+                "            ..............................",
+                "                              ............",
+                "                              ...#........",
+                "                              ..#.#.......",
+                "                              ..#.....##..",
+                "                              .###...#.##.",
+                "                              ..#....##...",
+                "                              ..#.....###.",
+                "                              ............",
+                "                              ............",
+            ],
+        );
+    }
+
+    #[test]
+    fn right_to_left_aligned_right() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .text_direction(TextDirection::RightToLeft)
+                .alignment(HorizontalAlignment::Right)
+                .build(),
+            "ab cd",
+            size_for(&FONT_6X9, 7, 1),
+            &[
+                "..............................",
+                "....#..............#..........",
+                "....#..............#..........",
+                "..###...###........###....###.",
+                ".#..#..#...........#..#..#..#.",
+                ".#..#..#...........#..#..#..#.",
+                "..###...###........###....###.",
+                "..............................",
+                "..............................",
+            ],
+        );
+    }
+
+    #[test]
+    fn no_wrap_overflowing_lines_are_clipped() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .wrap_mode(WrapMode::None)
+                .alignment(HorizontalAlignment::Center)
+                .build(),
+            "ab cd\nef",
+            size_for(&FONT_6X9, 3, 2),
+            &[
+                "..................",
+                ".......#..........",
+                ".......#..........",
+                "..###..###........",
+                ".#..#..#..#.......",
+                ".#..#..#..#.......",
+                "..###..###........",
+                "..................",
+                "..................",
+                "   ............   ",
+                "   .........#..   ",
+                "   ........#.#.   ",
+                "   ..##....#...   ",
+                "   .#.##..###..   ",
+                "   .##.....#...   ",
+                "   ..###...#...   ",
+                "   ............   ",
+                "   ............   ",
+            ],
+        );
+    }
+
+    #[test]
+    fn break_all() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .wrap_mode(WrapMode::BreakAll)
+                .build(),
+            "ab cdefg",
+            size_for(&FONT_6X9, 4, 2),
+            &[
+                "........................",
+                ".......#................",
+                ".......#................",
+                "..###..###..........###.",
+                ".#..#..#..#........#....",
+                ".#..#..#..#........#....",
+                "..###..###..........###.",
+                "........................",
+                "........................",
+                "........................",
+                "....#..........#........",
+                "....#.........#.#.......",
+                "..###...##....#.....##..",
+                ".#..#..#.##..###...#..#.",
+                ".#..#..##.....#....#..#.",
+                "..###...###...#.....###.",
+                "......................#.",
+                "....................##..",
+            ],
+        );
+    }
+
+    #[test]
+    fn paragraph_spacing_is_only_added_after_newlines() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "ab cd\nef",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 0)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .paragraph_spacing(3)
+                .build(),
+        );
+
+        assert_eq!(text_box.bounding_box().size.height, 3 * 9 + 3);
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "           ",
+            "       #   ",
+            "       #   ",
+            "  ###  ### ",
+            " #  #  #  #",
+            " #  #  #  #",
+            "  ###  ### ",
+            "           ",
+            "           ",
+            "           ",
+            "          #",
+            "          #",
+            "  ###   ###",
+            " #     #  #",
+            " #     #  #",
+            "  ###   ###",
+            "           ",
+            "           ",
+            "           ",
+            "           ",
+            "           ",
+            "           ",
+            "         # ",
+            "        # #",
+            "  ##    #  ",
+            " # ##  ### ",
+            " ##     #  ",
+            "  ###   #  ",
+        ]);
+    }
+
+    #[test]
+    fn background_color_fills_the_box() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::with_textbox_style(
+            "ab\ncd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 2) + Size::new(0, 3)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .line_height(LineHeight::Pixels(11))
+                .build(),
+        )
+        .set_background_color(Some(BinaryColor::Off))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "..................",
+            ".......#..........",
+            ".......#..........",
+            "..###..###........",
+            ".#..#..#..#.......",
+            ".#..#..#..#.......",
+            "..###..###........",
+            "..................",
+            "..................",
+            "..................",
+            "..................",
+            "..................",
+            "..........#.......",
+            "..........#.......",
+            "..###...###.......",
+            ".#.....#..#.......",
+            ".#.....#..#.......",
+            "..###...###.......",
+            "..................",
+            "..................",
+            "..................",
+        ]);
+    }
+
+    #[test]
+    fn min_line_height() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounding_box = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 3));
+
+        let mut expected = MockDisplay::new();
+        TextBox::with_textbox_style(
+            "ab\ncd",
+            bounding_box,
+            character_style,
+            TextBoxStyleBuilder::new()
+                .line_height(LineHeight::Pixels(11))
+                .build(),
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::with_textbox_style(
+            "ab\ncd",
+            bounding_box,
+            character_style,
+            TextBoxStyleBuilder::new().min_line_height(11).build(),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn line_height_smaller_than_font() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::with_textbox_style(
+            "ab\ncd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .line_height(LineHeight::Pixels(7))
+                .height_mode(HeightMode::FitToText)
+                .build(),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "           ",
+            "       #   ",
+            "       #   ",
+            "  ###  ### ",
+            " #  #  #  #",
+            " #  #  #  #",
+            "  ###  ### ",
+            "           ",
+            "          #",
+            "          #",
+            "  ###   ###",
+            " #     #  #",
+            " #     #  #",
+            "  ###   ###",
+        ]);
+    }
+
+    #[test]
+    fn rotated_90() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .rotation(Rotation::Rotate90)
+                .build(),
+            "ab",
+            Size::new(9, 12),
+            &[
+                ".........",
+                "...##....",
+                "..#..#...",
+                "..#..#...",
+                "..####...",
+                ".........",
+                ".........",
+                "..######.",
+                "..#..#...",
+                "..#..#...",
+                "...##....",
+                ".........",
+            ],
+        );
+    }
+
+    #[test]
+    fn rotated_270() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .rotation(Rotation::Rotate270)
+                .build(),
+            "ab",
+            Size::new(9, 12),
+            &[
+                ".........",
+                "....##...",
+                "...#..#..",
+                "...#..#..",
+                ".######..",
+                ".........",
+                ".........",
+                "...####..",
+                "...#..#..",
+                "...#..#..",
+                "....##...",
+                ".........",
+            ],
+        );
+    }
+
+    #[test]
+    fn rotated_180() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .rotation(Rotation::Rotate180)
+                .build(),
+            "ab",
+            Size::new(12, 9),
+            &[
+                "............",
+                "............",
+                "..###..###..",
+                ".#..#..#..#.",
+                ".#..#..#..#.",
+                "..###..###..",
+                "....#.......",
+                "....#.......",
+                "............",
+            ],
+        );
+    }
+
+    #[test]
+    fn scaled_2x() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new().scale(2).build(),
+            "ab",
+            Size::new(24, 18),
+            &[
+                "........................",
+                "........................",
+                "..............##........",
+                "..............##........",
+                "..............##........",
+                "..............##........",
+                "....######....######....",
+                "....######....######....",
+                "..##....##....##....##..",
+                "..##....##....##....##..",
+                "..##....##....##....##..",
+                "..##....##....##....##..",
+                "....######....######....",
+                "....######....######....",
+                "........................",
+                "........................",
+                "........................",
+                "........................",
+            ],
+        );
+    }
+
+    #[test]
+    fn scaled_text_box_fits_height_to_text() {
+        let text_box = TextBox::with_textbox_style(
+            "ab\ncd",
+            Rectangle::new(Point::zero(), Size::new(25, 100)),
+            MonoTextStyleBuilder::new()
+                .font(&FONT_6X9)
+                .text_color(BinaryColor::On)
+                .build(),
+            TextBoxStyleBuilder::new()
+                .scale(2)
+                .height_mode(HeightMode::FitToText)
+                .build(),
+        );
+
+        assert_eq!(text_box.bounding_box().size, Size::new(25, 2 * 2 * 9));
+    }
+
+    #[test]
+    fn rotated_text_box_fits_width_to_text() {
+        let text_box = TextBox::with_textbox_style(
+            "ab\ncd",
+            Rectangle::new(Point::zero(), Size::new(100, 12)),
+            MonoTextStyleBuilder::new()
+                .font(&FONT_6X9)
+                .text_color(BinaryColor::On)
+                .build(),
+            TextBoxStyleBuilder::new()
+                .rotation(Rotation::Rotate90)
+                .height_mode(HeightMode::FitToText)
+                .build(),
+        );
+
+        assert_eq!(text_box.bounding_box().size, Size::new(18, 12));
+    }
+
+    #[test]
+    fn padding() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::with_textbox_style(
+            "ab cd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 2) + Size::new(4, 3)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .padding(Padding::new(1, 2, 2, 2))
+                .build(),
+        )
+        .set_background_color(Some(BinaryColor::Off))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "......................",
+            "......................",
+            ".........#............",
+            ".........#............",
+            "....###..###..........",
+            "...#..#..#..#.........",
+            "...#..#..#..#.........",
+            "....###..###..........",
+            "......................",
+            "......................",
+            "......................",
+            "............#.........",
+            "............#.........",
+            "....###...###.........",
+            "...#.....#..#.........",
+            "...#.....#..#.........",
+            "....###...###.........",
+            "......................",
+            "......................",
+            "......................",
+            "......................",
+        ]);
+    }
+
+    #[test]
+    fn border() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::with_textbox_style(
+            "ab cd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 2) + Size::new(6, 6)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .border_width(2)
+                .padding(Padding::all(1))
+                .build(),
+        )
+        .set_background_color(Some(BinaryColor::Off))
+        .set_border_color(Some(BinaryColor::On))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "########################",
+            "########################",
+            "##....................##",
+            "##....................##",
+            "##........#...........##",
+            "##........#...........##",
+            "##...###..###.........##",
+            "##..#..#..#..#........##",
+            "##..#..#..#..#........##",
+            "##...###..###.........##",
+            "##....................##",
+            "##....................##",
+            "##....................##",
+            "##...........#........##",
+            "##...........#........##",
+            "##...###...###........##",
+            "##..#.....#..#........##",
+            "##..#.....#..#........##",
+            "##...###...###........##",
+            "##....................##",
+            "##....................##",
+            "##....................##",
+            "########################",
+            "########################",
+        ]);
+    }
+
+    #[test]
+    fn padding_larger_than_the_box() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::with_textbox_style(
+            "ab cd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 2)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .padding(Padding::new(20, 10, 20, 10))
+                .build(),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[]);
+    }
+
+    #[test]
+    fn first_line_indent() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new().first_line_indent(12).build(),
+            "ab cd ef\ngh ij",
+            size_for(&FONT_6X9, 6, 4),
+            &[
+                "            ............      ",
+                "            .......#....      ",
+                "            .......#....      ",
+                "            ..###..###..      ",
+                "            .#..#..#..#.      ",
+                "            .#..#..#..#.      ",
+                "            ..###..###..      ",
+                "            ............      ",
+                "            ............      ",
+                "..............................",
+                "..........#................#..",
+                "..........#...............#.#.",
+                "..###...###.........##....#...",
+                ".#.....#..#........#.##..###..",
+                ".#.....#..#........##.....#...",
+                "..###...###.........###...#...",
+                "..............................",
+                "..............................",
+                "            ............      ",
+                "            .......#....      ",
+                "            .......#....      ",
+                "            ..##...###..      ",
+                "            .#..#..#..#.      ",
+                "            .#..#..#..#.      ",
+                "            ..###..#..#.      ",
+                "            ....#.......      ",
+                "            ..##........      ",
+                "............                  ",
+                "..#......#..                  ",
+                "............                  ",
+                ".##.....##..                  ",
+                "..#......#..                  ",
+                "..#......#..                  ",
+                ".###.....#..                  ",
+                ".......#.#..                  ",
+                "........#...                  ",
+            ],
+        );
+    }
+
+    #[test]
+    fn subsequent_line_indent_justified() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .subsequent_line_indent(12)
+                .alignment(HorizontalAlignment::Justified)
+                .build(),
+            "- a b c dd e ff",
+            size_for(&FONT_6X9, 7, 3),
+            &[
+                "..........................................",
+                ".........................#................",
+                ".........................#................",
+                "..............###........###..........###.",
+                "#####........#..#........#..#........#....",
+                ".............#..#........#..#........#....",
+                "..............###........###..........###.",
+                "..........................................",
+                "..........................................",
+                "            ..............................",
+                "            ....#.....#...................",
+                "            ....#.....#...................",
+                "            ..###...###...............##..",
+                "            .#..#..#..#..............#.##.",
+                "            .#..#..#..#..............##...",
+                "            ..###...###...............###.",
+                "            ..............................",
+                "            ..............................",
+                "            ............                  ",
+                "            ...#.....#..                  ",
+                "            ..#.#...#.#.                  ",
+                "            ..#.....#...                  ",
+                "            .###...###..                  ",
+                "            ..#.....#...                  ",
+                "            ..#.....#...                  ",
+                "            ............                  ",
+                "            ............                  ",
+            ],
+        );
+    }
+
+    #[test]
+    fn tab_stops() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .tab_size(TabSize::Stops(&[24, 36]))
+                .build(),
+            "a\tb\tc\td\nlonger\te",
+            size_for(&FONT_6X9, 10, 2),
+            &[
+                "......................................................",
+                ".........................#..........................#.",
+                ".........................#..........................#.",
+                "..###....................###..........###.........###.",
+                ".#..#....................#..#........#...........#..#.",
+                ".#..#....................#..#........#...........#..#.",
+                "..###....................###..........###.........###.",
+                "......................................................",
+                "......................................................",
+                "......................................................",
+                ".##...................................................",
+                "..#...................................................",
+                "..#.....##...###....##....##...#.#................##..",
+                "..#....#..#..#..#..#..#..#.##..##.#..............#.##.",
+                "..#....#..#..#..#..#..#..##....#.................##...",
+                ".###....##...#..#...###...###..#..................###.",
+                "......................#...............................",
+                "....................##................................",
+            ],
+        );
+    }
+
+    #[test]
+    fn tab_leaders() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .tab_size(TabSize::Stops(&[21]))
+                .tab_leader('.')
+                .build(),
+            "a\tb",
+            size_for(&FONT_6X9, 5, 1),
+            &[
+                "...........................",
+                "......................#....",
+                "......................#....",
+                "..###.................###..",
+                ".#..#.................#..#.",
+                ".#..#...##....##......#..#.",
+                "..###...##....##......###..",
+                "...........................",
+                "...........................",
+            ],
+        );
+    }
+
+    #[test]
+    fn right_aligned_tabs() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .tab_size(TabSize::Pixels(24))
+                .tab_alignment(TabAlignment::Right)
+                .build(),
+            "\t12\n\t3",
+            size_for(&FONT_6X9, 4, 2),
+            &[
+                "........................",
+                "..............#.....##..",
+                ".............##....#..#.",
+                "..............#.......#.",
+                "..............#......#..",
+                "..............#.....#...",
+                ".............###...####.",
+                "........................",
+                "........................",
+                "........................",
+                "...................####.",
+                ".....................#..",
+                "....................##..",
+                "......................#.",
+                "......................#.",
+                "...................###..",
+                "........................",
+                "........................",
+            ],
+        );
+    }
+
+    #[test]
+    fn decimal_aligned_tabs() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .tab_size(TabSize::Pixels(18))
+                .tab_alignment(TabAlignment::Decimal)
+                .build(),
+            "\t1.5\n\t12.25",
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "..............................      ",
+                "..............#..........####.      ",
+                ".............##..........#....      ",
+                "..............#..........###..      ",
+                "..............#.............#.      ",
+                "..............#.....##......#.      ",
+                ".............###....##...###..      ",
+                "..............................      ",
+                "..............................      ",
+                "....................................",
+                "........#.....##..........##...####.",
+                ".......##....#..#........#..#..#....",
+                "........#.......#...........#..###..",
+                "........#......#...........#......#.",
+                "........#.....#.....##....#.......#.",
+                ".......###...####...##...####..###..",
+                "....................................",
+                "....................................",
+            ],
+        );
+    }
+
+    #[test]
+    fn wrapped_spaces_are_removed() {
+        assert_styled_rendered(
+            TextBoxStyle::default(),
+            "foo  bar",
+            size_for(&FONT_6X9, 3, 2),
+            &[
+                "..................",
+                "...#..............",
+                "..#.#.............",
+                "..#.....##....##..",
+                ".###...#..#..#..#.",
+                "..#....#..#..#..#.",
+                "..#.....##....##..",
+                "..................",
+                "..................",
+                "..................",
+                ".#................",
+                ".#................",
+                ".###....###..#.#..",
+                ".#..#..#..#..##.#.",
+                ".#..#..#..#..#....",
+                ".###....###..#....",
+                "..................",
+                "..................",
+            ],
+        );
+    }
+
+    #[test]
+    fn preformatted_spaces_are_kept() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new().preformatted().build(),
+            "a    b",
+            size_for(&FONT_6X9, 6, 1),
+            &[
+                "....................................",
+                "...............................#....",
+                "...............................#....",
+                "..###..........................###..",
+                ".#..#..........................#..#.",
+                ".#..#..........................#..#.",
+                "..###..........................###..",
+                "....................................",
+                "....................................",
+            ],
+        );
+    }
+
+    #[test]
+    fn preformatted_does_not_depend_on_builder_order() {
+        let expected = [
+            "........................",
+            "........................",
+            "........................",
+            "..............###...##..",
+            ".............#..#..#..#.",
+            ".............#..#..#..#.",
+            "..............###...##..",
+            "........................",
+            "........................",
+        ];
+
+        let mut style = TextBoxStyleBuilder::new()
+            .wrap_mode(WrapMode::Word)
+            .leading_spaces(false)
+            .trailing_spaces(false)
+            .build();
+        style.preformatted = true;
+
+        for style in [
+            TextBoxStyleBuilder::new()
+                .wrap_mode(WrapMode::Word)
+                .leading_spaces(false)
+                .preformatted()
+                .build(),
+            TextBoxStyleBuilder::new()
+                .preformatted()
+                .wrap_mode(WrapMode::Word)
+                .leading_spaces(false)
+                .build(),
+            style,
+        ] {
+            assert_styled_rendered(style, "  ao ao", size_for(&FONT_6X9, 4, 2), &expected);
+        }
+    }
+
+    #[test]
+    fn preformatted_spaces_continue_after_wrapping() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .preformatted()
+                .wrap_mode(WrapMode::BreakAll)
+                .build(),
+            "ab  cd",
+            size_for(&FONT_6X9, 3, 2),
+            &[
+                "..................",
+                ".......#..........",
+                ".......#..........",
+                "..###..###........",
+                ".#..#..#..#.......",
+                ".#..#..#..#.......",
+                "..###..###........",
+                "..................",
+                "..................",
+                "..................",
+                "................#.",
+                "................#.",
+                "........###...###.",
+                ".......#.....#..#.",
+                ".......#.....#..#.",
+                "........###...###.",
+                "..................",
+                "..................",
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "grapheme")]
+    fn grapheme_clusters_are_drawn_as_their_base() {
+        let size = size_for(&FONT_6X9, 3, 1);
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("aeb", Rectangle::new(Point::zero(), size), character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            "ae\u{301}b",
+            Rectangle::new(Point::zero(), size),
+            character_style,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn baseline_doesnt_move_single_font_text() {
+        let text = "Some sample text";
+        let size = size_for(&FONT_6X9, 7, 3);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(
+            text,
+            Rectangle::new(Point::zero(), size),
+            MonoTextStyleBuilder::new()
+                .font(&FONT_6X9)
+                .text_color(BinaryColor::On)
+                .background_color(BinaryColor::Off)
+                .build(),
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        for baseline in [Baseline::Middle, Baseline::Bottom, Baseline::Alphabetic] {
+            let mut display = MockDisplay::new();
+            TextBox::with_textbox_style(
+                text,
+                Rectangle::new(Point::zero(), size),
+                MonoTextStyleBuilder::new()
+                    .font(&FONT_6X9)
+                    .text_color(BinaryColor::On)
+                    .background_color(BinaryColor::Off)
+                    .build(),
+                TextBoxStyleBuilder::new().baseline(baseline).build(),
+            )
+            .draw(&mut display)
+            .unwrap();
+
+            display.assert_eq(&expected);
+        }
+    }
+
+    #[test]
+    fn max_lines() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "ab cd ef gh ij kl mn op qr st",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 0)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .max_lines(3)
+                .build(),
+        );
+
+        assert_eq!(text_box.bounding_box().size, size_for(&FONT_6X9, 2, 3));
+
+        let remainder = text_box.draw(&mut display).unwrap();
+
+        assert_eq!(remainder, "gh ij kl mn op qr st");
+        display.assert_pattern(&[
+            "............",
+            ".......#....",
+            ".......#....",
+            "..###..###..",
+            ".#..#..#..#.",
+            ".#..#..#..#.",
+            "..###..###..",
+            "............",
+            "............",
+            "............",
+            "..........#.",
+            "..........#.",
+            "..###...###.",
+            ".#.....#..#.",
+            ".#.....#..#.",
+            "..###...###.",
+            "............",
+            "............",
+            "............",
+            ".........#..",
+            "........#.#.",
+            "..##....#...",
+            ".#.##..###..",
+            ".##.....#...",
+            "..###...#...",
+            "............",
+            "............",
+        ]);
+    }
+
+    #[test]
+    fn draw_continuation() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (text; expected rendered text)
+            ("ab cd", "ab cd"),
+            ("ab cd ef gh", "ab cd "),
+            ("abcdefgh", "abcd"),
+            ("ab\ncd\nef", "ab\ncd\n"),
+        ];
+
+        for (text, rendered) in data {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+
+            let bytes = TextBox::new(
+                text,
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 2)),
+                character_style,
+            )
+            .draw_continuation(&mut display)
+            .unwrap();
+
+            assert_eq!(
+                &text[..bytes],
+                rendered,
+                "Remainder of {:?} is incorrect",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn draw_continuation_in_box_smaller_than_a_line() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut display = MockDisplay::new();
+        let text_box = TextBox::new(
+            "ab cd",
+            Rectangle::new(Point::zero(), Size::new(30, 5)),
+            character_style,
+        );
+
+        assert_eq!(text_box.draw(&mut display), Ok("ab cd"));
+        assert_eq!(text_box.draw_continuation(&mut display), Ok(0));
+    }
+
+    #[test]
+    fn draw_buffered() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut text_box = TextBox::new(
+            "Buffered\nlines",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 3)),
+            character_style,
+        );
+        text_box.set_background_color(Some(BinaryColor::Off));
+
+        let mut expected = MockDisplay::new();
+        expected.set_allow_overdraw(true);
+        text_box.draw(&mut expected).unwrap();
+
+        // The second buffer is too small, so the lines are drawn directly.
+        for len in [6 * 6 * 9, 6 * 6 * 9 - 1] {
+            let mut buffer = vec![BinaryColor::On; len];
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+            text_box.draw_buffered(&mut display, &mut buffer).unwrap();
+
+            display.assert_eq(&expected);
+        }
+    }
+
+    #[test]
+    fn space_wrapping_issue() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X10)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_textbox_style(
+            "Hello,      s",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X10, 10, 2)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::Exact(VerticalOverdraw::Hidden))
+                .trailing_spaces(true)
+                .trim_wrapped_spaces(false)
+                .build(),
+        )
+        .draw(&mut display)
+        .unwrap();
 
         display.assert_pattern(&[
             "............................................................",
@@ -414,4 +2386,528 @@ pub mod test {
             "............                                                ",
         ]);
     }
+
+    /// Plugin that inserts tokens at the start of the text.
+    #[derive(Clone)]
+    struct TokensAtStart<'a, C>(Vec<Token<'a, C>>);
+
+    impl<'a, C: PixelColor> Plugin<'a, C> for TokensAtStart<'a, C> {
+        fn next_token(
+            &mut self,
+            mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+        ) -> Option<Token<'a, C>> {
+            if self.0.is_empty() {
+                next_token()
+            } else {
+                Some(self.0.remove(0))
+            }
+        }
+    }
+
+    #[test]
+    fn underline_styles() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            (
+                UnderlineStyle::Solid,
+                &[
+                    "                        ",
+                    "       #                ",
+                    "       #                ",
+                    "  ###  ###          ### ",
+                    " #  #  #  #        #    ",
+                    " #  #  #  #        #    ",
+                    "  ###  ###          ### ",
+                    "                        ",
+                    "########################",
+                ] as &[&str],
+            ),
+            (
+                UnderlineStyle::Double,
+                &[
+                    "                        ",
+                    "       #                ",
+                    "       #                ",
+                    "  ###  ###          ### ",
+                    " #  #  #  #        #    ",
+                    " #  #  #  #        #    ",
+                    "########################",
+                    "                        ",
+                    "########################",
+                ],
+            ),
+            (
+                UnderlineStyle::Dotted,
+                &[
+                    "                       ",
+                    "       #               ",
+                    "       #               ",
+                    "  ###  ###          ###",
+                    " #  #  #  #        #   ",
+                    " #  #  #  #        #   ",
+                    "  ###  ###          ###",
+                    "                       ",
+                    "# # # # # # # # # # # #",
+                ],
+            ),
+            (
+                UnderlineStyle::Dashed,
+                &[
+                    "                       ",
+                    "       #               ",
+                    "       #               ",
+                    "  ###  ###          ###",
+                    " #  #  #  #        #   ",
+                    " #  #  #  #        #   ",
+                    "  ###  ###          ###",
+                    "                       ",
+                    "####  ####  ####  #### ",
+                ],
+            ),
+        ];
+
+        for (underline, pattern) in data {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+
+            TextBox::new(
+                "ab c",
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+                character_style,
+            )
+            .add_plugin(TokensAtStart(vec![
+                Token::ChangeTextStyle(ChangeTextStyle::Underline(DecorationColor::TextColor)),
+                Token::ChangeTextStyle(ChangeTextStyle::UnderlineStyle(underline)),
+            ]))
+            .draw(&mut display)
+            .unwrap();
+
+            display.assert_pattern(pattern);
+        }
+    }
+
+    #[test]
+    fn custom_strikethrough() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .strikethrough()
+            .build();
+
+        let draw = |style: TextBoxStyle| {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+
+            TextBox::with_textbox_style(
+                "ab c",
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+                character_style,
+                style,
+            )
+            .draw(&mut display)
+            .unwrap();
+
+            display
+        };
+
+        // The strikethrough of the character style is used by default.
+        draw(TextBoxStyle::default()).assert_pattern(&[
+            "                        ",
+            "       #                ",
+            "       #                ",
+            "  ###  ###          ### ",
+            "########################",
+            " #  #  #  #        #    ",
+            "  ###  ###          ### ",
+        ]);
+
+        draw(
+            TextBoxStyleBuilder::new()
+                .strikethrough(Strikethrough::new(3, 2))
+                .build(),
+        )
+        .assert_pattern(&[
+            "                        ",
+            "       #                ",
+            "       #                ",
+            "########################",
+            "########################",
+            " #  #  #  #        #    ",
+            "  ###  ###          ### ",
+        ]);
+
+        draw(
+            TextBoxStyleBuilder::new()
+                .strikethrough(Strikethrough::double(2, 1))
+                .build(),
+        )
+        .assert_pattern(&[
+            "                        ",
+            "       #                ",
+            "########################",
+            "  ###  ###          ### ",
+            "########################",
+            " #  #  #  #        #    ",
+            "  ###  ###          ### ",
+        ]);
+    }
+
+    #[test]
+    fn custom_underline() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let draw = |underline: Underline| {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+
+            TextBox::with_textbox_style(
+                "ga y",
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+                character_style,
+                TextBoxStyleBuilder::new().underline(underline).build(),
+            )
+            .add_plugin(TokensAtStart(vec![Token::ChangeTextStyle(
+                ChangeTextStyle::Underline(DecorationColor::TextColor),
+            )]))
+            .draw(&mut display)
+            .unwrap();
+
+            display
+        };
+
+        draw(Underline::new(7)).assert_pattern(&[
+            "                        ",
+            "                        ",
+            "                        ",
+            "  ##    ###        #  # ",
+            " #  #  #  #        #  # ",
+            " #  #  #  #        #  # ",
+            "  ###   ###         ### ",
+            "########################",
+            "  ##                ##  ",
+        ]);
+
+        // The underline has gaps around the descenders.
+        draw(Underline::new(7).with_skip_descenders(true)).assert_pattern(&[
+            "                       ",
+            "                       ",
+            "                       ",
+            "  ##    ###        #  #",
+            " #  #  #  #        #  #",
+            " #  #  #  #        #  #",
+            "  ###   ###         ###",
+            "### # ############ #  #",
+            "  ##                ## ",
+        ]);
+    }
+
+    #[test]
+    fn nested_styles_are_restored() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "e",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+        )
+        .add_plugin(TokensAtStart(vec![
+            Token::Word("a"),
+            Token::ChangeTextStyle(ChangeTextStyle::PushStyle),
+            Token::ChangeTextStyle(ChangeTextStyle::Underline(DecorationColor::TextColor)),
+            Token::Word("b"),
+            Token::ChangeTextStyle(ChangeTextStyle::PushStyle),
+            Token::ChangeTextStyle(ChangeTextStyle::TextColor(Some(BinaryColor::Off))),
+            Token::Word("c"),
+            Token::ChangeTextStyle(ChangeTextStyle::PopStyle),
+            Token::Word("d"),
+            Token::ChangeTextStyle(ChangeTextStyle::PopStyle),
+        ]))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                             ",
+            "       #              #      ",
+            "       #              #      ",
+            "  ###  ###    ...   ###   ## ",
+            " #  #  #  #  .     #  #  # ##",
+            " #  #  #  #  .     #  #  ##  ",
+            "  ###  ###    ...   ###   ###",
+            "                             ",
+            "      ######......######     ",
+        ]);
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordLineMetrics(Rc<RefCell<Vec<(u32, LineEndType)>>>);
+
+    impl<'a, C: PixelColor> Plugin<'a, C> for RecordLineMetrics {
+        fn line_metrics(&mut self, metrics: &LineMeasurement) {
+            self.0
+                .borrow_mut()
+                .push((metrics.width(), metrics.line_end_type()));
+        }
+    }
+
+    /// Plugin that replaces `@` with an inline object and draws it as a filled box.
+    #[derive(Clone)]
+    struct Icons;
+
+    impl<'a> Plugin<'a, BinaryColor> for Icons {
+        fn next_token(
+            &mut self,
+            mut next_token: impl FnMut() -> Option<Token<'a, BinaryColor>>,
+        ) -> Option<Token<'a, BinaryColor>> {
+            match next_token() {
+                Some(Token::Word("@")) => Some(Token::InlineObject {
+                    id: 7,
+                    size: Size::new(4, 8),
+                }),
+                token => token,
+            }
+        }
+
+        fn render_object<D>(
+            &mut self,
+            draw_target: &mut D,
+            id: u32,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = BinaryColor>,
+        {
+            assert_eq!(id, 7);
+            draw_target.fill_solid(&bounds, BinaryColor::Off)
+        }
+    }
+
+    #[test]
+    fn inline_objects() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        // The object stands on the baseline and makes the line taller.
+        TextBox::new(
+            "a @ b",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2)),
+            character_style,
+        )
+        .add_plugin(Icons)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "            ....           ",
+            "            ....           ",
+            "            ....       #   ",
+            "            ....       #   ",
+            "  ###       ....       ### ",
+            " #  #       ....       #  #",
+            " #  #       ....       #  #",
+            "  ###       ....       ### ",
+        ]);
+
+        // Objects that don't fit are moved to the next line.
+        let text_box = TextBox::new("ab @", Rectangle::zero(), character_style).add_plugin(Icons);
+        assert_eq!(text_box.content_size(18), Size::new(12, 9 + 10));
+    }
+
+    #[derive(Clone)]
+    struct BoxDecoration;
+
+    impl<'a> Plugin<'a, BinaryColor> for BoxDecoration {
+        fn pre_render_box<D>(
+            &mut self,
+            draw_target: &mut D,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = BinaryColor>,
+        {
+            draw_target.fill_solid(&bounds, BinaryColor::Off)
+        }
+
+        fn post_render_box<D>(
+            &mut self,
+            draw_target: &mut D,
+            bounds: Rectangle,
+        ) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = BinaryColor>,
+        {
+            // A scrollbar on the right side of the box.
+            let top_right = bounds.top_left + Point::new(bounds.size.width as i32 - 1, 0);
+            draw_target.fill_solid(
+                &Rectangle::new(top_right, Size::new(1, bounds.size.height)),
+                BinaryColor::On,
+            )
+        }
+    }
+
+    #[test]
+    fn plugin_draws_around_the_box() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "ab cd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            character_style,
+        )
+        .add_plugin(BoxDecoration)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            ".................#",
+            ".......#.........#",
+            ".......#.........#",
+            "..###..###.......#",
+            ".#..#..#..#......#",
+            ".#..#..#..#......#",
+            "..###..###.......#",
+            ".................#",
+            ".................#",
+        ]);
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordLineEnds(Rc<RefCell<Vec<(LineEndType, Point)>>>);
+
+    impl<'a, C: PixelColor> Plugin<'a, C> for RecordLineEnds {
+        fn line_end(&mut self, end_type: LineEndType, end_pos: Point, _metrics: &LineMeasurement) {
+            self.0.borrow_mut().push((end_type, end_pos));
+        }
+    }
+
+    #[test]
+    fn plugin_receives_line_ends() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let lines = RecordLineEnds::default();
+
+        TextBox::with_alignment(
+            "ab cd ef\ngh",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 3)),
+            character_style,
+            HorizontalAlignment::Right,
+        )
+        .add_plugin(lines.clone())
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            *lines.0.borrow(),
+            [
+                (LineEndType::LineBreak, Point::new(30, 0)),
+                (LineEndType::NewLine, Point::new(30, 9)),
+                (LineEndType::EndOfText, Point::new(30, 18)),
+            ]
+        );
+    }
+
+    #[test]
+    fn plugin_receives_line_metrics() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let lines = RecordLineMetrics::default();
+
+        TextBox::new(
+            "ab cd ef\ngh",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 3)),
+            character_style,
+        )
+        .add_plugin(lines.clone())
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            *lines.0.borrow(),
+            [
+                (30, LineEndType::LineBreak),
+                (12, LineEndType::NewLine),
+                (12, LineEndType::EndOfText),
+            ]
+        );
+    }
+
+    #[test]
+    fn drop_cap() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let drop_cap_style = MonoTextStyle::new(&FONT_6X13, BinaryColor::On);
+
+        let mut text_box = TextBox::new(
+            "Abcd ef gh",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 3)),
+            character_style,
+        );
+        text_box.set_drop_cap(Some(&drop_cap_style));
+
+        // The first two lines are indented by the width of the letter and a space.
+        assert_eq!(text_box.content_size(30), Size::new(30, 27));
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "                             ",
+            "             #              #",
+            "  #          #              #",
+            " # #         ###    ###   ###",
+            "#   #        #  #  #     #  #",
+            "#   #        #  #  #     #  #",
+            "#   #        ###    ###   ###",
+            "#####                        ",
+            "#   #                        ",
+            "#   #                        ",
+            "#   #                #       ",
+            "                    # #      ",
+            "              ##    #        ",
+            "             # ##  ###       ",
+            "             ##     #        ",
+            "              ###   #        ",
+            "                             ",
+            "                             ",
+            "                             ",
+            "       #                     ",
+            "       #                     ",
+            "  ##   ###                   ",
+            " #  #  #  #                  ",
+            " #  #  #  #                  ",
+            "  ###  #  #                  ",
+            "    #                        ",
+            "  ##                         ",
+        ]);
+    }
 }
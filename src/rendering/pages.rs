@@ -0,0 +1,172 @@
+//! Split text into pages.
+
+use core::ops::Range;
+
+use embedded_graphics::text::renderer::{CharacterStyle, TextRenderer};
+
+use crate::{plugin::PluginMarker as Plugin, TextBox};
+
+/// Iterator over the pages of a text.
+///
+/// Each page is returned as the byte range of the text that fits into the text box. Created by
+/// [`TextBox::pages`].
+#[derive(Clone)]
+#[must_use]
+pub struct Pages<'t, 'a, S, M>
+where
+    S: TextRenderer,
+{
+    text_box: &'t TextBox<'a, S, M>,
+    offset: usize,
+    finished: bool,
+}
+
+impl<'t, 'a, S, M> Iterator for Pages<'t, 'a, S, M>
+where
+    S: TextRenderer<Color = <S as CharacterStyle>::Color> + CharacterStyle,
+    M: Plugin<'a, <S as TextRenderer>::Color>,
+    <S as CharacterStyle>::Color: Default,
+{
+    type Item = Range<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let start = self.offset;
+        let mut page = self.text_box.clone();
+        page.text = &self.text_box.text[start..];
+        page.vertical_offset = 0;
+
+        let end = start + page.page_end();
+
+        // Stop if the rest of the text fits, or if the page can't display anything.
+        self.finished = end == self.text_box.text.len() || end == start;
+        self.offset = end;
+
+        Some(start..end)
+    }
+}
+
+impl<'a, S, M> TextBox<'a, S, M>
+where
+    S: TextRenderer<Color = <S as CharacterStyle>::Color> + CharacterStyle,
+    M: Plugin<'a, <S as TextRenderer>::Color>,
+    <S as CharacterStyle>::Color: Default,
+{
+    /// Returns an iterator over the pages of the text.
+    ///
+    /// The text is split into pages that fit into the text box, and each page is returned as a
+    /// byte range of the text. Page `n` can be displayed by drawing a copy of the text box that
+    /// only contains the text of the `n`th range. Each page is laid out like the start of a new
+    /// text, with the plugins and the vertical offset of the text box reset.
+    ///
+    /// An empty text has a single, empty page. If the text box can't display any text, e.g.
+    /// because it's shorter than a line or its
+    /// [`max_lines`](crate::style::TextBoxStyle::max_lines) is 0, the iteration stops with an
+    /// empty page.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// use embedded_text::TextBox;
+    ///
+    /// let text = "It was a dark and stormy night.";
+    /// let text_box = TextBox::new(
+    ///     text,
+    ///     Rectangle::new(Point::zero(), Size::new(60, 18)),
+    ///     character_style,
+    /// );
+    ///
+    /// // Each page displays two lines of text.
+    /// let mut pages = text_box.pages();
+    /// assert_eq!(&text[pages.next().unwrap()], "It was a dark and ");
+    /// assert_eq!(&text[pages.next().unwrap()], "stormy night.");
+    /// assert_eq!(pages.next(), None);
+    /// ```
+    #[inline]
+    pub fn pages(&self) -> Pages<'_, 'a, S, M> {
+        Pages {
+            text_box: self,
+            offset: 0,
+            finished: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+    };
+
+    use crate::{style::TextBoxStyleBuilder, utils::test::size_for, TextBox};
+
+    #[test]
+    fn pages() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let data: [(&str, &[&str]); 4] = [
+            ("", &[""]),
+            ("ab cd", &["ab cd"]),
+            ("ab cd ef gh ij", &["ab cd ef gh ", "ij"]),
+            ("ab\n\ncd\nef", &["ab\n\n", "cd\nef"]),
+        ];
+
+        for (text, expected) in data {
+            let text_box = TextBox::new(
+                text,
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2)),
+                character_style,
+            );
+
+            let pages = text_box.pages().map(|range| &text[range]);
+            assert!(pages.eq(expected.iter().copied()), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn pages_of_text_box_without_lines() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let text_box = TextBox::with_textbox_style(
+            "ab cd",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 2)),
+            character_style,
+            TextBoxStyleBuilder::new().max_lines(0).build(),
+        );
+
+        let mut pages = text_box.pages();
+        assert_eq!(pages.next(), Some(0..0));
+        assert_eq!(pages.next(), None);
+    }
+
+    #[test]
+    fn pages_of_text_box_shorter_than_a_line() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let text_box = TextBox::new(
+            "ab cd",
+            Rectangle::new(Point::zero(), Size::new(30, 5)),
+            character_style,
+        );
+
+        assert_eq!(text_box.page_end(), 0);
+
+        let mut pages = text_box.pages();
+        assert_eq!(pages.next(), Some(0..0));
+        assert_eq!(pages.next(), None);
+    }
+}
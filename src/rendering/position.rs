@@ -0,0 +1,1162 @@
+//! Map between pixel positions and byte offsets in the text.
+//!
+//! Positions are computed by rendering the text box into a draw target that discards every
+//! pixel, while observing the rendered pieces of text. This ensures the results match the actual
+//! rendering, including alignment, tabs and plugins.
+
+use core::{convert::Infallible, marker::PhantomData};
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Point, Size},
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+    transform::Transform,
+    Pixel,
+};
+
+use crate::{
+    alignment::SpaceConfig,
+    parser::Token,
+    plugin::{private, PluginMarker as Plugin, PluginWrapper},
+    rendering::{cursor::Cursor, fonts::Fonts, TextBoxProperties},
+    style::{LineEndType, LineMeasurement, TextDirection},
+    utils::{clusters, offset_in, str_width},
+    TextBox,
+};
+
+/// Draw target that discards everything drawn into it.
+struct NullDrawTarget<C> {
+    size: Size,
+    _marker: PhantomData<C>,
+}
+
+impl<C> OriginDimensions for NullDrawTarget<C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C: PixelColor> DrawTarget for NullDrawTarget<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, _pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        Ok(())
+    }
+}
+
+/// Receives the pieces of text rendered by the text box, along with their byte offsets.
+pub(crate) trait ElementObserver {
+    /// Called for each piece of text.
+    ///
+    /// `offset` is the byte offset of the source of `st` in the text, `source_len` is the length
+    /// of the source. Some rendered strings are not part of the text, like the hyphen displayed in
+    /// place of a soft hyphen. For these, `source_len` is 0.
+    fn element<T: TextRenderer>(
+        &mut self,
+        character_style: &T,
+        st: &str,
+        offset: usize,
+        source_len: usize,
+        bounds: Rectangle,
+    );
+
+    /// Called when the end of the text is reached.
+    fn end_of_text(&mut self, _offset: usize, _bounds: Rectangle) {}
+}
+
+/// Plugin that forwards every call to the text box's plugin and reports the rendered text to an
+/// [`ElementObserver`].
+#[derive(Clone)]
+struct Observed<'a, M, O> {
+    plugin: M,
+    observer: O,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a, C, M, O> private::Plugin<'a, C> for Observed<'a, M, O>
+where
+    C: PixelColor,
+    M: Plugin<'a, C>,
+    O: ElementObserver + Clone,
+{
+    fn new_line(&mut self) {
+        self.plugin.new_line();
+    }
+
+    fn line_indent<S: TextRenderer>(&mut self, character_style: &S) -> u32 {
+        self.plugin.line_indent(character_style)
+    }
+
+    fn next_token(
+        &mut self,
+        next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        self.plugin.next_token(next_token)
+    }
+
+    fn line_metrics(&mut self, metrics: &LineMeasurement) {
+        self.plugin.line_metrics(metrics);
+    }
+
+    fn place_line(
+        &mut self,
+        metrics: &LineMeasurement,
+        offset: i32,
+        space_config: SpaceConfig,
+    ) -> (i32, SpaceConfig) {
+        self.plugin.place_line(metrics, offset, space_config)
+    }
+
+    fn line_end(&mut self, end_type: LineEndType, end_pos: Point, metrics: &LineMeasurement) {
+        self.plugin.line_end(end_type, end_pos, metrics);
+    }
+
+    fn post_line_start<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.plugin.post_line_start(draw_target, bounds)
+    }
+
+    fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
+        self.plugin.render_token(token)
+    }
+
+    fn pre_render<T>(&mut self, character_style: &mut T, text: &str, bounds: Rectangle)
+    where
+        T: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+    {
+        self.plugin.pre_render(character_style, text, bounds);
+    }
+
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        match text {
+            Some(st) => {
+                let (offset, source_len) = match offset_in(self.text, st) {
+                    Some(offset) => (offset, st.len()),
+
+                    // Control characters are reported using their own, static strings.
+                    None if matches!(st, "\n" | "\r" | "\t") => (self.offset, st.len()),
+
+                    None => (self.offset, 0),
+                };
+
+                self.observer
+                    .element(character_style, st, offset, source_len, bounds);
+                self.offset = offset + source_len;
+            }
+            None => self.observer.end_of_text(self.offset, bounds),
+        }
+
+        self.plugin
+            .post_render(draw_target, character_style, text, bounds)
+    }
+
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        self.plugin.on_start_render(cursor, props);
+    }
+
+    fn pre_render_box<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.plugin.pre_render_box(draw_target, bounds)
+    }
+
+    fn render_object<D>(
+        &mut self,
+        draw_target: &mut D,
+        id: u32,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.plugin.render_object(draw_target, id, bounds)
+    }
+
+    fn post_render_box<D>(&mut self, draw_target: &mut D, bounds: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.plugin.post_render_box(draw_target, bounds)
+    }
+
+    fn on_rendering_finished(&mut self) {
+        self.plugin.on_rendering_finished();
+    }
+}
+
+/// Finds the byte offset of the character under a point.
+#[derive(Clone)]
+struct HitTest {
+    point: Point,
+    text_direction: TextDirection,
+    result: Option<usize>,
+}
+
+impl ElementObserver for HitTest {
+    fn element<T: TextRenderer>(
+        &mut self,
+        character_style: &T,
+        st: &str,
+        offset: usize,
+        source_len: usize,
+        bounds: Rectangle,
+    ) {
+        if self.result.is_some() || !bounds.contains(self.point) {
+            return;
+        }
+
+        if source_len != st.len() {
+            self.result = Some(offset);
+            return;
+        }
+
+        let mut x = (self.point.x - bounds.top_left.x) as u32;
+        if self.text_direction == TextDirection::RightToLeft {
+            // Right-to-left text is mirrored, its first character is on the right.
+            x = bounds.size.width - 1 - x;
+        }
+
+        let byte_offset = if str_width(character_style, st) == bounds.size.width {
+            // Find the character under the point.
+            let mut right = 0;
+            clusters(st)
+                .find(|(_, c)| {
+                    right += str_width(character_style, c);
+                    x < right
+                })
+                .map(|(idx, _)| idx)
+        } else {
+            // Rendered width differs from the measured width (e.g. justified spaces, tabs).
+            // Assume the characters are evenly distributed.
+            let chars = clusters(st).count() as u32;
+            clusters(st)
+                .nth((x * chars / bounds.size.width) as usize)
+                .map(|(idx, _)| idx)
+        };
+
+        self.result = byte_offset.map(|idx| offset + idx);
+    }
+}
+
+/// Finds the position of the caret placed before a given byte offset.
+#[derive(Clone, Debug)]
+pub(crate) struct CaretPosition {
+    offset: usize,
+    text_direction: TextDirection,
+    found: bool,
+    result: Option<Rectangle>,
+}
+
+impl CaretPosition {
+    /// Creates an observer that finds the caret placed before the byte at `offset`, in text laid
+    /// out in the given direction.
+    pub(crate) const fn new(offset: usize, text_direction: TextDirection) -> Self {
+        Self {
+            offset,
+            text_direction,
+            found: false,
+            result: None,
+        }
+    }
+
+    /// Returns the caret as a zero width rectangle, if it was found.
+    pub(crate) const fn result(&self) -> Option<Rectangle> {
+        self.result
+    }
+}
+
+impl ElementObserver for CaretPosition {
+    fn element<T: TextRenderer>(
+        &mut self,
+        character_style: &T,
+        st: &str,
+        offset: usize,
+        source_len: usize,
+        bounds: Rectangle,
+    ) {
+        if self.found {
+            return;
+        }
+
+        let end = offset + source_len;
+        let x = if (offset..end).contains(&self.offset) {
+            self.found = true;
+
+            if source_len == st.len() && !st.is_char_boundary(self.offset - offset) {
+                // The offset is inside a character.
+                self.result = None;
+                return;
+            }
+
+            if bounds.size.width == 0 && self.result.is_some() {
+                // Keep the caret after the previous element, e.g. at a wrapping space.
+                return;
+            }
+
+            let prefix = st.get(..self.offset - offset).unwrap_or_default();
+            if source_len != st.len() || bounds.size.width == 0 {
+                0
+            } else if str_width(character_style, st) == bounds.size.width {
+                str_width(character_style, prefix)
+            } else {
+                // Rendered width differs from the measured width (e.g. justified spaces, tabs).
+                // Assume the characters are evenly distributed.
+                let chars = clusters(st).count() as u32;
+                clusters(prefix).count() as u32 * bounds.size.width / chars
+            }
+        } else if end == self.offset && !st.is_empty() {
+            // The caret is just past this element, but the next element may also contain it.
+            bounds.size.width
+        } else {
+            return;
+        };
+
+        let x = match self.text_direction {
+            TextDirection::LeftToRight => x,
+            // Right-to-left text is mirrored, the caret is measured from the right.
+            TextDirection::RightToLeft => bounds.size.width.saturating_sub(x),
+        };
+
+        self.result = Some(Rectangle::new(
+            bounds.top_left + Size::new(x, 0),
+            Size::new(0, bounds.size.height),
+        ));
+    }
+
+    fn end_of_text(&mut self, offset: usize, bounds: Rectangle) {
+        if !self.found && offset == self.offset {
+            self.result = Some(bounds);
+        }
+    }
+}
+
+/// Finds the smallest rectangle that contains every rendered piece of text.
+#[derive(Clone)]
+struct TextBounds {
+    result: Option<Rectangle>,
+}
+
+impl ElementObserver for TextBounds {
+    fn element<T: TextRenderer>(
+        &mut self,
+        _character_style: &T,
+        _st: &str,
+        _offset: usize,
+        _source_len: usize,
+        bounds: Rectangle,
+    ) {
+        if bounds.is_zero_sized() {
+            return;
+        }
+
+        self.result = Some(match self.result {
+            Some(result) => {
+                let top_left = Point::new(
+                    result.top_left.x.min(bounds.top_left.x),
+                    result.top_left.y.min(bounds.top_left.y),
+                );
+                // Neither rectangle is zero sized, so their bottom right corners exist.
+                let bottom_right = result
+                    .bottom_right()
+                    .unwrap()
+                    .component_max(bounds.bottom_right().unwrap());
+
+                Rectangle::with_corners(top_left, bottom_right)
+            }
+            None => bounds,
+        });
+    }
+}
+
+impl<'a, F, M> TextBox<'a, F, M>
+where
+    F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
+    M: Plugin<'a, <F as TextRenderer>::Color>,
+    <F as CharacterStyle>::Color: Default,
+{
+    fn observe<O>(&self, observer: O) -> O
+    where
+        O: ElementObserver + Clone,
+    {
+        let plugin = PluginWrapper::new(Observed {
+            plugin: self.plugin.clone().into_inner(),
+            observer,
+            text: self.text,
+            offset: 0,
+        });
+
+        let mut display = NullDrawTarget {
+            size: self.bounding_box().size,
+            _marker: PhantomData,
+        };
+        let _ = self.draw_with_plugin(&mut display, &plugin);
+
+        plugin.into_inner().observer
+    }
+
+    /// Returns the byte offset of the character at the given point.
+    ///
+    /// The point is relative to the top left corner of the text box. Returns `None` if there is
+    /// no character at the given point. Points of scaled or rotated text boxes are mapped back to
+    /// the text before they are tested.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// use embedded_text::TextBox;
+    ///
+    /// let text_box = TextBox::new(
+    ///     "Hello, World!",
+    ///     Rectangle::new(Point::new(10, 10), Size::new(60, 20)),
+    ///     character_style,
+    /// );
+    ///
+    /// // The characters are 6 pixels wide, "W" is the 3rd character of the second line.
+    /// assert_eq!(text_box.hit_test(Point::new(14, 12)), Some(9));
+    /// assert_eq!(text_box.hit_test(Point::new(50, 12)), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn hit_test(&self, point: Point) -> Option<usize> {
+        self.observe(HitTest {
+            point: self
+                .layout_mapping()
+                .unmap_point(self.bounds.top_left + point),
+            text_direction: self.style.text_direction,
+            result: None,
+        })
+        .result
+    }
+
+    /// Returns the position of a caret placed before the character at the given byte offset.
+    ///
+    /// The caret is returned as a zero width rectangle. The top left corner of the rectangle is
+    /// relative to the top left corner of the text box, and the height of the rectangle is the
+    /// height of the line. At the end of a line or the end of the text the caret is placed just
+    /// past the last character.
+    ///
+    /// The caret of a scaled or rotated text box is scaled and rotated like the text. With
+    /// [`Rotation::Rotate90`] and [`Rotation::Rotate270`] it is a zero height rectangle.
+    ///
+    /// [`Rotation::Rotate90`]: crate::style::Rotation::Rotate90
+    /// [`Rotation::Rotate270`]: crate::style::Rotation::Rotate270
+    ///
+    /// Returns `None` if the character at the given offset is not displayed, or if the offset is
+    /// inside a multi-byte character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// use embedded_text::TextBox;
+    ///
+    /// let text_box = TextBox::new(
+    ///     "Hello, World!",
+    ///     Rectangle::new(Point::new(10, 10), Size::new(60, 20)),
+    ///     character_style,
+    /// );
+    ///
+    /// // "W" is the first character of the second line.
+    /// assert_eq!(
+    ///     text_box.caret_position(7),
+    ///     Some(Rectangle::new(Point::new(0, 9), Size::new(0, 9)))
+    /// );
+    /// // The end of the text.
+    /// assert_eq!(
+    ///     text_box.caret_position(13),
+    ///     Some(Rectangle::new(Point::new(36, 9), Size::new(0, 9)))
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn caret_position(&self, offset: usize) -> Option<Rectangle> {
+        self.observe(CaretPosition::new(offset, self.style.text_direction))
+            .result()
+            .map(|caret| {
+                self.layout_mapping()
+                    .map_area(&caret)
+                    .translate(-self.bounds.top_left)
+            })
+    }
+
+    /// Returns the area that is covered by the rendered text.
+    ///
+    /// The returned rectangle contains every rendered character and space, and it's usually
+    /// smaller than the text box if the lines don't fill the whole width, for example because the
+    /// text is centered. The top left corner of the rectangle is relative to the top left corner
+    /// of the text box, the rectangle is scaled and rotated like the text. Returns `None` if
+    /// nothing is rendered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// use embedded_text::{alignment::HorizontalAlignment, TextBox};
+    ///
+    /// let text_box = TextBox::with_alignment(
+    ///     "Hello,\nWorld!",
+    ///     Rectangle::new(Point::new(10, 10), Size::new(60, 60)),
+    ///     character_style,
+    ///     HorizontalAlignment::Center,
+    /// );
+    ///
+    /// assert_eq!(
+    ///     text_box.text_bounds(),
+    ///     Some(Rectangle::new(Point::new(12, 0), Size::new(36, 18)))
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn text_bounds(&self) -> Option<Rectangle> {
+        self.observe(TextBounds { result: None })
+            .result
+            .map(|bounds| {
+                self.layout_mapping()
+                    .map_area(&bounds)
+                    .translate(-self.bounds.top_left)
+            })
+    }
+
+    /// Returns the byte offset of the first part of the text that doesn't fit into the text box.
+    ///
+    /// This is the number of bytes that [`draw_continuation`] would render, without drawing
+    /// anything. The text starting at the returned offset can be displayed in another text box,
+    /// e.g. on the next page. If the whole text fits, the length of the text is returned.
+    ///
+    /// [`draw_continuation`]: TextBox::draw_continuation()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// use embedded_text::TextBox;
+    ///
+    /// let text = "Hello, World!";
+    /// let text_box = TextBox::new(
+    ///     text,
+    ///     Rectangle::new(Point::zero(), Size::new(60, 9)),
+    ///     character_style,
+    /// );
+    ///
+    /// assert_eq!(&text[text_box.page_end()..], "World!");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn page_end(&self) -> usize {
+        let mut display = NullDrawTarget {
+            size: self.bounding_box().size,
+            _marker: PhantomData,
+        };
+        let remaining = self
+            .draw_with_plugin(&mut display, &self.plugin.clone())
+            .unwrap_or_default();
+
+        self.text.len() - remaining.len()
+    }
+
+    /// Returns whether the text doesn't fit into the text box.
+    ///
+    /// The text overflows if some of it isn't rendered, because it's cut off by the height of the
+    /// text box or by its [`max_lines`](crate::style::TextBoxStyle::max_lines) setting, or if the
+    /// text is larger than the text area, so that some lines are only partially visible or can
+    /// only be seen by changing the [`vertical_offset`](TextBox::vertical_offset). Truncated text
+    /// overflows, even though it ends with an ellipsis.
+    ///
+    /// This can be used to display a "more" indicator, to enable scrolling or to pick a smaller
+    /// font.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// use embedded_text::TextBox;
+    ///
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 9));
+    ///
+    /// let text_box = TextBox::new("Hello", bounding_box, character_style);
+    /// assert!(!text_box.overflows());
+    ///
+    /// let text_box = TextBox::new("Hello, World!", bounding_box, character_style);
+    /// assert!(text_box.overflows());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn overflows(&self) -> bool {
+        if self.page_end() < self.text.len() {
+            return true;
+        }
+
+        let text_area = self.style.insets().shrink(Rectangle::new(
+            Point::zero(),
+            self.style.layout_size(self.bounds.size),
+        ));
+        let size = self
+            .style
+            .measure_text_impl(
+                self.plugin.clone(),
+                Fonts::new(&self.character_style, self.fonts, self.font_variants)
+                    .with_drop_cap(self.drop_cap),
+                self.text,
+                text_area.size.width,
+            )
+            .size;
+
+        size.height > text_area.size.height || size.width > text_area.size.width
+    }
+
+    /// Sets the character style to the first of the candidates that fits the text into the text
+    /// box.
+    ///
+    /// The candidates are tried in order, so they should be sorted from the largest to the
+    /// smallest font. A candidate fits if the text box doesn't [overflow](TextBox::overflows)
+    /// when it's used. The height mode is applied to the text box for each candidate, like when
+    /// the text box is created.
+    ///
+    /// Returns the index of the selected candidate. If none of the candidates fit, the last one is
+    /// used and `None` is returned. The text box is not changed if there are no candidates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{
+    /// #         ascii::{FONT_10X20, FONT_4X6, FONT_6X9},
+    /// #         MonoTextStyle,
+    /// #     },
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// use embedded_text::TextBox;
+    ///
+    /// let candidates = [
+    ///     MonoTextStyle::new(&FONT_10X20, BinaryColor::On),
+    ///     MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+    ///     MonoTextStyle::new(&FONT_4X6, BinaryColor::On),
+    /// ];
+    ///
+    /// // A button that fits 6 characters of the 6x9 font.
+    /// let button = Rectangle::new(Point::zero(), Size::new(36, 12));
+    /// let mut text_box = TextBox::new("Cancel", button, candidates[0]);
+    ///
+    /// assert_eq!(text_box.fit_character_style(&candidates), Some(1));
+    /// ```
+    #[inline]
+    pub fn fit_character_style(&mut self, candidates: &[F]) -> Option<usize> {
+        let bounds = self.bounds;
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            self.bounds = bounds;
+            self.character_style = candidate.clone();
+            self.style.height_mode.apply(self);
+
+            if !self.overflows() {
+                return Some(index);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mono_font::{
+            ascii::{FONT_10X20, FONT_4X6, FONT_6X9},
+            MonoTextStyle,
+        },
+        pixelcolor::BinaryColor,
+        primitives::Rectangle,
+    };
+
+    use embedded_graphics::text::renderer::{CharacterStyle, TextRenderer};
+
+    use crate::{
+        alignment::HorizontalAlignment,
+        plugin::PluginMarker as Plugin,
+        style::{
+            HeightMode, Rotation, TextBoxStyleBuilder, TextDirection, VerticalOverdraw, WrapMode,
+        },
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[test]
+    fn hit_test() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::new(
+            "some text\n\ttab\u{a0}x",
+            Rectangle::new(Point::new(5, 5), size_for(&FONT_6X9, 10, 2)),
+            character_style,
+        );
+
+        let data = [
+            (Point::new(0, 0), Some(0)),
+            (Point::new(5, 8), Some(0)),
+            (Point::new(6, 0), Some(1)),
+            (Point::new(25, 0), Some(4)),
+            (Point::new(30, 0), Some(5)),
+            (Point::new(59, 0), None),
+            // tab
+            (Point::new(0, 9), Some(10)),
+            (Point::new(23, 9), Some(10)),
+            (Point::new(24, 9), Some(11)),
+            // non-breaking space
+            (Point::new(42, 9), Some(14)),
+            (Point::new(48, 9), Some(16)),
+            (Point::new(54, 9), None),
+            (Point::new(0, 18), None),
+            (Point::new(-1, 0), None),
+        ];
+
+        for (point, expected) in data {
+            assert_eq!(text_box.hit_test(point), expected, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn hit_test_aligned() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_textbox_style(
+            "a b\nlonger text",
+            Rectangle::new(Point::zero(), Size::new(66, 18)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Right)
+                .build(),
+        );
+
+        let data = [
+            (Point::new(0, 0), None),
+            (Point::new(47, 0), None),
+            (Point::new(48, 0), Some(0)),
+            (Point::new(54, 0), Some(1)),
+            (Point::new(60, 0), Some(2)),
+            (Point::new(0, 9), Some(4)),
+            (Point::new(65, 9), Some(14)),
+        ];
+
+        for (point, expected) in data {
+            assert_eq!(text_box.hit_test(point), expected, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn hit_test_right_to_left() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_textbox_style(
+            "abcdef",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 10, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .text_direction(TextDirection::RightToLeft)
+                .build(),
+        );
+
+        let data = [
+            (Point::new(58, 4), Some(0)),
+            (Point::new(54, 4), Some(0)),
+            (Point::new(53, 4), Some(1)),
+            (Point::new(24, 4), Some(5)),
+            (Point::new(23, 4), None),
+        ];
+
+        for (point, expected) in data {
+            assert_eq!(text_box.hit_test(point), expected, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn text_bounds() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let data = [
+            // (text; alignment; expected bounds)
+            ("", HorizontalAlignment::Left, None),
+            ("ab", HorizontalAlignment::Left, Some((0, 0, 12, 9))),
+            (
+                "ab\nabcd",
+                HorizontalAlignment::Right,
+                Some((36, 0, 24, 18)),
+            ),
+            (
+                "ab\nabcd",
+                HorizontalAlignment::Center,
+                Some((18, 0, 24, 18)),
+            ),
+            (
+                "ab\n\nabcd",
+                HorizontalAlignment::Left,
+                Some((0, 0, 24, 27)),
+            ),
+        ];
+
+        for (text, alignment, expected) in data {
+            let text_box = TextBox::with_alignment(
+                text,
+                Rectangle::new(Point::new(5, 5), size_for(&FONT_6X9, 10, 3)),
+                character_style,
+                alignment,
+            );
+
+            let expected =
+                expected.map(|(x, y, w, h)| Rectangle::new(Point::new(x, y), Size::new(w, h)));
+            assert_eq!(text_box.text_bounds(), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn hit_test_justified_spaces() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_alignment(
+            "a  b cc",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 2)),
+            character_style,
+            HorizontalAlignment::Justified,
+        );
+
+        let data = [
+            (Point::new(6, 0), Some(1)),
+            (Point::new(17, 0), Some(1)),
+            (Point::new(18, 0), Some(2)),
+            (Point::new(30, 0), Some(3)),
+            (Point::new(0, 9), Some(5)),
+        ];
+
+        for (point, expected) in data {
+            assert_eq!(text_box.hit_test(point), expected, "{:?}", point);
+        }
+    }
+
+    #[track_caller]
+    fn assert_carets<'a, F, M>(text_box: &TextBox<'a, F, M>, data: &[(usize, Option<Point>)])
+    where
+        F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
+        M: Plugin<'a, <F as TextRenderer>::Color>,
+        <F as CharacterStyle>::Color: Default,
+    {
+        for &(offset, expected) in data {
+            assert_eq!(
+                text_box.caret_position(offset),
+                expected.map(|point| Rectangle::new(point, Size::new(0, 9))),
+                "offset: {}",
+                offset
+            );
+        }
+    }
+
+    #[test]
+    fn caret_position() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::new(
+            "some text\nmo\ttab",
+            Rectangle::new(Point::new(5, 5), size_for(&FONT_6X9, 10, 2)),
+            character_style,
+        );
+
+        assert_carets(
+            &text_box,
+            &[
+                (0, Some(Point::new(0, 0))),
+                (4, Some(Point::new(24, 0))),
+                (5, Some(Point::new(30, 0))),
+                (9, Some(Point::new(54, 0))),
+                (10, Some(Point::new(0, 9))),
+                (12, Some(Point::new(12, 9))),
+                (13, Some(Point::new(24, 9))),
+                (16, Some(Point::new(42, 9))),
+                (17, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn caret_position_wrapped() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::new(
+            "abc def",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 2)),
+            character_style,
+        );
+
+        assert_carets(
+            &text_box,
+            &[
+                (2, Some(Point::new(12, 0))),
+                (3, Some(Point::new(18, 0))),
+                (4, Some(Point::new(0, 9))),
+                (7, Some(Point::new(18, 9))),
+            ],
+        );
+    }
+
+    #[test]
+    fn caret_position_aligned() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_alignment(
+            "ab",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+            HorizontalAlignment::Right,
+        );
+
+        assert_carets(
+            &text_box,
+            &[(0, Some(Point::new(18, 0))), (2, Some(Point::new(30, 0)))],
+        );
+    }
+
+    #[test]
+    fn caret_position_right_to_left() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_textbox_style(
+            "abcdef",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 10, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .text_direction(TextDirection::RightToLeft)
+                .build(),
+        );
+
+        assert_carets(
+            &text_box,
+            &[
+                (0, Some(Point::new(60, 0))),
+                (1, Some(Point::new(54, 0))),
+                (5, Some(Point::new(30, 0))),
+                (6, Some(Point::new(24, 0))),
+            ],
+        );
+    }
+
+    #[test]
+    fn positions_in_scaled_text_box() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_textbox_style(
+            "abc\nd",
+            Rectangle::new(Point::new(5, 5), Size::new(60, 40)),
+            character_style,
+            TextBoxStyleBuilder::new().scale(2).build(),
+        );
+
+        assert_eq!(text_box.hit_test(Point::new(11, 4)), Some(0));
+        assert_eq!(text_box.hit_test(Point::new(14, 4)), Some(1));
+        assert_eq!(text_box.hit_test(Point::new(0, 18)), Some(4));
+        assert_eq!(text_box.hit_test(Point::new(36, 4)), None);
+
+        assert_eq!(
+            text_box.caret_position(1),
+            Some(Rectangle::new(Point::new(12, 0), Size::new(0, 18)))
+        );
+        assert_eq!(
+            text_box.caret_position(5),
+            Some(Rectangle::new(Point::new(12, 18), Size::new(0, 18)))
+        );
+        assert_eq!(
+            text_box.text_bounds(),
+            Some(Rectangle::new(Point::zero(), Size::new(36, 36)))
+        );
+    }
+
+    #[test]
+    fn positions_in_rotated_text_box() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::with_textbox_style(
+            "abcd\ne",
+            Rectangle::new(Point::new(5, 5), Size::new(18, 60)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .rotation(Rotation::Rotate90)
+                .build(),
+        );
+
+        // The first line is on the right, running from top to bottom.
+        assert_eq!(text_box.hit_test(Point::new(17, 0)), Some(0));
+        assert_eq!(text_box.hit_test(Point::new(10, 7)), Some(1));
+        assert_eq!(text_box.hit_test(Point::new(0, 0)), Some(5));
+        assert_eq!(text_box.hit_test(Point::new(0, 7)), None);
+
+        assert_eq!(
+            text_box.caret_position(1),
+            Some(Rectangle::new(Point::new(9, 6), Size::new(9, 0)))
+        );
+        assert_eq!(
+            text_box.text_bounds(),
+            Some(Rectangle::new(Point::zero(), Size::new(18, 24)))
+        );
+    }
+
+    #[test]
+    fn caret_position_empty_text() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::new(
+            "",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+        );
+
+        assert_carets(&text_box, &[(0, Some(Point::new(0, 0))), (1, None)]);
+    }
+
+    #[test]
+    fn caret_position_inside_character() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let text_box = TextBox::new(
+            "aäb",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+        );
+
+        assert_carets(
+            &text_box,
+            &[
+                (1, Some(Point::new(6, 0))),
+                (2, None),
+                (3, Some(Point::new(12, 0))),
+            ],
+        );
+    }
+
+    #[test]
+    fn overflows() {
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let data = [
+            // (text; style; overflows)
+            ("", TextBoxStyleBuilder::new().build(), false),
+            ("ab cd", TextBoxStyleBuilder::new().build(), false),
+            ("ab cd ef", TextBoxStyleBuilder::new().build(), true),
+            (
+                "ab cd",
+                TextBoxStyleBuilder::new().max_lines(1).build(),
+                true,
+            ),
+            (
+                "ab cd e",
+                TextBoxStyleBuilder::new()
+                    .height_mode(HeightMode::Exact(VerticalOverdraw::Visible))
+                    .build(),
+                true,
+            ),
+            (
+                "ab cd ef",
+                TextBoxStyleBuilder::new()
+                    .height_mode(HeightMode::TruncateWithEllipsis)
+                    .build(),
+                true,
+            ),
+            (
+                "abcdefg",
+                TextBoxStyleBuilder::new().wrap_mode(WrapMode::None).build(),
+                true,
+            ),
+        ];
+
+        for (text, style, expected) in data {
+            let text_box = TextBox::with_textbox_style(
+                text,
+                Rectangle::new(Point::new(5, 5), Size::new(15, 18)),
+                character_style,
+                style,
+            );
+
+            assert_eq!(text_box.overflows(), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn fit_character_style() {
+        let candidates = [
+            MonoTextStyle::new(&FONT_10X20, BinaryColor::On),
+            MonoTextStyle::new(&FONT_6X9, BinaryColor::On),
+            MonoTextStyle::new(&FONT_4X6, BinaryColor::On),
+        ];
+
+        let data = [
+            // (text; selected candidate)
+            ("ab", Some(0)),
+            ("abcd", Some(1)),
+            ("abcdefghij", Some(2)),
+            ("abcdefghijklmnopqrst", None),
+        ];
+
+        for (text, expected) in data {
+            let mut text_box = TextBox::new(
+                text,
+                Rectangle::new(Point::zero(), Size::new(24, 20)),
+                candidates[0],
+            );
+
+            assert_eq!(
+                text_box.fit_character_style(&candidates),
+                expected,
+                "{:?}",
+                text
+            );
+
+            let selected = expected.unwrap_or(candidates.len() - 1);
+            assert_eq!(text_box.character_style, candidates[selected], "{:?}", text);
+        }
+
+        let mut text_box = TextBox::new("ab", Rectangle::zero(), candidates[1]);
+        assert_eq!(text_box.fit_character_style(&[]), None);
+        assert_eq!(text_box.character_style, candidates[1]);
+    }
+}
@@ -1,6 +1,16 @@
 //! Space rendering config
 
-#[derive(Copy, Clone, Debug)]
+/// The widths of the spaces in a line.
+///
+/// Every space uses the base width. Optionally, the first spaces of the line are one pixel wider
+/// to distribute the pixels that can't be divided evenly between the spaces, and the spaces after
+/// a number of spaces use a different width, e.g. the trailing spaces of a justified line.
+///
+/// The widths are consumed in order while the line is rendered, see [`SpaceConfig::consume`].
+/// The configuration can also add extra space between the printed characters of the line, see
+/// [`SpaceConfig::with_character_spacing`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[must_use]
 pub struct SpaceConfig {
     /// The width of the whitespace characters.
     width: u32,
@@ -8,24 +18,86 @@ pub struct SpaceConfig {
     /// Stores how many characters are rendered using the `width` width. This field changes
     /// during rendering.
     count: Option<u32>,
+
+    /// The number of spaces that use `width`, and the width of the spaces after them. This field
+    /// changes during rendering.
+    limit: Option<(u32, u32)>,
+
+    /// The number of gaps after printed characters that are widened, the extra width of every
+    /// gap, and the number of gaps that are one pixel wider. This field changes during rendering.
+    character_gaps: (u32, u32, u32),
 }
 
-/// Retrieves size of space characters.
 impl SpaceConfig {
-    /// Creates a new SpaceConfig object.
+    /// Creates a new space configuration.
+    ///
+    /// `width` is the width of every space. If `count` is set, the first `count` spaces are one
+    /// pixel wider.
+    #[inline]
     pub const fn new(width: u32, count: Option<u32>) -> Self {
-        Self { width, count }
+        Self {
+            width,
+            count,
+            limit: None,
+            character_gaps: (0, 0, 0),
+        }
+    }
+
+    /// Distributes `width` extra pixels between the next `gaps` gaps after printed characters.
+    #[inline]
+    pub const fn with_character_spacing(mut self, gaps: u32, width: u32) -> Self {
+        if gaps != 0 {
+            self.character_gaps = (gaps, width / gaps, width % gaps);
+        }
+        self
+    }
+
+    /// Returns the extra width of the gap after the next printed character, and consumes it.
+    #[inline]
+    pub fn consume_character_gap(&mut self) -> u32 {
+        let (gaps, width, extra) = &mut self.character_gaps;
+        if *gaps == 0 {
+            return 0;
+        }
+
+        *gaps -= 1;
+        if *extra > 0 {
+            *extra -= 1;
+            *width + 1
+        } else {
+            *width
+        }
     }
 
-    /// Look at the size of next n spaces, without advancing.
+    /// Returns whether extra space is added after printed characters.
+    pub(crate) const fn has_character_spacing(&self) -> bool {
+        self.character_gaps.0 != 0
+    }
+
+    /// Only uses the configured width for the next `count` spaces, and `width` after them.
+    #[inline]
+    pub const fn limit(mut self, count: u32, width: u32) -> Self {
+        self.limit = Some((count, width));
+        self
+    }
+
+    /// Returns the total width of the next `n` spaces, without consuming them.
+    #[inline]
+    #[must_use]
     pub fn peek_next_width(&self, n: u32) -> u32 {
+        let (n, rest) = match self.limit {
+            None => (n, 0),
+            Some((limit, width)) => (n.min(limit), n.saturating_sub(limit) * width),
+        };
+
         match self.count {
-            None => n * self.width,
-            Some(count) => n * self.width + count.min(n),
+            None => n * self.width + rest,
+            Some(count) => n * self.width + count.min(n) + rest,
         }
     }
 
-    /// Advance the internal state
+    /// Returns the total width of the next `n` spaces, and consumes them.
+    #[inline]
     pub fn consume(&mut self, n: u32) -> u32 {
         let w = self.peek_next_width(n);
 
@@ -33,6 +105,10 @@ impl SpaceConfig {
             *count = count.saturating_sub(n);
         }
 
+        if let Some((limit, _)) = self.limit.as_mut() {
+            *limit = limit.saturating_sub(n);
+        }
+
         w
     }
 }
@@ -0,0 +1,70 @@
+//! Draw target that collects the pixels of a line before they are drawn at once.
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    prelude::PixelColor,
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Draw target that buffers the pixels of an area, then draws them using a single
+/// `fill_contiguous` call.
+///
+/// The error type is the one of the draw target the pixels are finally drawn to.
+pub(crate) struct LineStrip<'b, C, E> {
+    area: Rectangle,
+    pixels: &'b mut [C],
+    _error: PhantomData<E>,
+}
+
+impl<'b, C: PixelColor, E> LineStrip<'b, C, E> {
+    /// Creates a strip for `area` filled with `background`, or `None` if the buffer is too small.
+    pub(crate) fn new(buffer: &'b mut [C], area: Rectangle, background: C) -> Option<Self> {
+        let len = (area.size.width as usize).checked_mul(area.size.height as usize)?;
+        let pixels = buffer.get_mut(..len)?;
+        pixels.fill(background);
+
+        Some(Self {
+            area,
+            pixels,
+            _error: PhantomData,
+        })
+    }
+
+    /// Draws the buffered pixels.
+    pub(crate) fn flush<D>(self, display: &mut D) -> Result<(), E>
+    where
+        D: DrawTarget<Color = C, Error = E>,
+    {
+        display.fill_contiguous(&self.area, self.pixels.iter().copied())
+    }
+}
+
+impl<C, E> Dimensions for LineStrip<'_, C, E> {
+    fn bounding_box(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<C: PixelColor, E> DrawTarget for LineStrip<'_, C, E> {
+    type Color = C;
+    type Error = E;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let width = self.area.size.width as usize;
+        for Pixel(p, color) in pixels {
+            if self.area.contains(p) {
+                let Point { x, y } = p - self.area.top_left;
+                self.pixels[y as usize * width + x as usize] = color;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! Draw target that scales and rotates the drawn pixels.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::style::Rotation;
+
+/// Maps the layout box of a scaled or rotated text box to its bounding box.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct LayoutMapping {
+    rotation: Rotation,
+    scale: u32,
+    bounds: Rectangle,
+}
+
+impl LayoutMapping {
+    /// Creates a mapping that scales, then rotates the layout box into `bounds`.
+    pub(crate) const fn new(rotation: Rotation, scale: u32, bounds: Rectangle) -> Self {
+        Self {
+            rotation,
+            scale,
+            bounds,
+        }
+    }
+
+    /// Returns the area of the bounding box that an area of the layout box is drawn to.
+    ///
+    /// Zero sized areas, like carets, are mapped to zero sized areas.
+    pub(crate) fn map_area(&self, area: &Rectangle) -> Rectangle {
+        // The edges of the scaled area, relative to the bounding box.
+        let Point { x: left, y: top } = (area.top_left - self.bounds.top_left) * self.scale as i32;
+        let size = area.size * self.scale;
+        let (right, bottom) = (left + size.width as i32, top + size.height as i32);
+
+        let (width, height) = (
+            self.bounds.size.width as i32,
+            self.bounds.size.height as i32,
+        );
+
+        let (top_left, bottom_right) = match self.rotation {
+            Rotation::Rotate0 => ((left, top), (right, bottom)),
+            Rotation::Rotate90 => ((width - bottom, left), (width - top, right)),
+            Rotation::Rotate180 => (
+                (width - right, height - bottom),
+                (width - left, height - top),
+            ),
+            Rotation::Rotate270 => ((top, height - right), (bottom, height - left)),
+        };
+
+        Rectangle::new(
+            self.bounds.top_left + Point::from(top_left),
+            Size::new(
+                (bottom_right.0 - top_left.0) as u32,
+                (bottom_right.1 - top_left.1) as u32,
+            ),
+        )
+    }
+
+    /// Returns the point of the layout box that is drawn to a point of the bounding box.
+    pub(crate) fn unmap_point(&self, point: Point) -> Point {
+        let Point { x, y } = point - self.bounds.top_left;
+        let Size { width, height } = self.bounds.size;
+        let (right, bottom) = (width as i32 - 1, height as i32 - 1);
+
+        let (x, y) = match self.rotation {
+            Rotation::Rotate0 => (x, y),
+            Rotation::Rotate90 => (y, right - x),
+            Rotation::Rotate180 => (right - x, bottom - y),
+            Rotation::Rotate270 => (bottom - y, x),
+        };
+
+        let scale = self.scale as i32;
+        self.bounds.top_left + Point::new(x.div_euclid(scale), y.div_euclid(scale))
+    }
+}
+
+/// Draw target that maps the layout box of a scaled or rotated text box to its bounding box.
+pub(crate) struct Transformed<'d, D> {
+    target: &'d mut D,
+    mapping: LayoutMapping,
+}
+
+impl<'d, D> Transformed<'d, D> {
+    /// Creates a draw target that draws into `target` using the given mapping.
+    pub(crate) fn new(target: &'d mut D, mapping: LayoutMapping) -> Self {
+        Self { target, mapping }
+    }
+}
+
+impl<D> Dimensions for Transformed<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        let LayoutMapping {
+            rotation,
+            scale,
+            bounds,
+        } = self.mapping;
+
+        Rectangle::new(bounds.top_left, rotation.layout_size(bounds.size) / scale)
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for Transformed<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mapping = self.mapping;
+        if mapping.scale == 1 {
+            return self
+                .target
+                .draw_iter(pixels.into_iter().map(|Pixel(p, color)| {
+                    Pixel(mapping.rotation.transform(&mapping.bounds, p), color)
+                }));
+        }
+
+        // Every pixel is drawn as a square.
+        for Pixel(p, color) in pixels {
+            let area = mapping.map_area(&Rectangle::new(p, Size::new(1, 1)));
+            self.target.fill_solid(&area, color)?;
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.fill_solid(&self.mapping.map_area(area), color)
+    }
+}
@@ -1,9 +1,12 @@
 //! Text box style builder.
-use embedded_graphics::text::LineHeight;
+use embedded_graphics::text::{Baseline, LineHeight};
 
 use crate::{
     alignment::{HorizontalAlignment, VerticalAlignment},
-    style::{HeightMode, TabSize, TextBoxStyle, VerticalOverdraw},
+    style::{
+        HeightMode, Padding, Rotation, Strikethrough, TabAlignment, TabSize, TextBoxStyle,
+        TextDirection, Underline, VerticalOverdraw, WrapMode,
+    },
 };
 
 /// [`TextBoxStyle`] builder object.
@@ -31,11 +34,38 @@ impl TextBoxStyleBuilder {
                 vertical_alignment: VerticalAlignment::Top,
                 height_mode: HeightMode::Exact(VerticalOverdraw::FullRowsOnly),
                 line_height: LineHeight::Percent(100),
+                min_line_height: 0,
                 paragraph_spacing: 0,
                 tab_size: TabSize::Spaces(4),
                 // we will update these at build time
                 leading_spaces: false,
                 trailing_spaces: false,
+                max_lines: None,
+                letter_spacing: 0,
+                word_spacing: 0,
+                text_direction: TextDirection::LeftToRight,
+                rotation: Rotation::Rotate0,
+                scale: 1,
+                baseline: Baseline::Top,
+                first_line_indent: 0,
+                subsequent_line_indent: 0,
+                wrap_mode: WrapMode::Word,
+                justify_last_line: false,
+                justify_max_space_width: None,
+                justify_characters: false,
+                trim_wrapped_spaces: true,
+                padding: Padding::zero(),
+                border_width: 0,
+                ellipsis: "\u{2026}",
+                avoid_widows: false,
+                tab_leader: None,
+                preformatted: false,
+                word_separators: "",
+                break_urls: false,
+                horizontal_rules: false,
+                tab_alignment: TabAlignment::Left,
+                strikethrough: None,
+                underline: None,
             },
             leading_spaces: None,
             trailing_spaces: None,
@@ -47,6 +77,10 @@ impl TextBoxStyleBuilder {
     /// The line height is defined as the vertical distance between the baseline of two adjacent lines
     /// of text.
     ///
+    /// A line height smaller than the height of the font makes the lines overlap, e.g.
+    /// `LineHeight::Pixels(7)` with a 9 pixel tall font draws the lines 2 pixels closer together.
+    /// Lines are never drawn above the previous line.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -64,6 +98,28 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Sets the minimum line height, in pixels.
+    ///
+    /// Lines are at least this far apart, even if the [line height](Self::line_height) is smaller,
+    /// e.g. to keep the lines of a small font at the same distance as the lines of a larger one.
+    /// The glyphs are still drawn at the baseline of the font.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .min_line_height(12)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn min_line_height(mut self, min_line_height: u32) -> Self {
+        self.style.min_line_height = min_line_height;
+
+        self
+    }
+
     /// Sets the paragraph spacing.
     ///
     /// # Example
@@ -118,6 +174,156 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Renders the whitespace of the text as it is, e.g. to display source code or ASCII art.
+    ///
+    /// See [`TextBoxStyle::preformatted`] for details. The result doesn't depend on the order of
+    /// the builder calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{TextBoxStyleBuilder, WrapMode};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .preformatted()
+    ///     .wrap_mode(WrapMode::BreakAll)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn preformatted(mut self) -> Self {
+        self.style.preformatted = true;
+
+        self
+    }
+
+    /// Lays out `key<tab>value` lines as a table with two columns.
+    ///
+    /// The keys are left aligned in a column that is `key_width` pixels wide, and the values fill
+    /// the rest of the line. Values that don't fit in a line are wrapped within the value column.
+    /// Keys that are wider than the key column move their value to the next tab stop, which is
+    /// another `key_width` pixels to the right.
+    ///
+    /// This setting sets the tab size and the indentation of wrapped lines.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// use embedded_text::{style::TextBoxStyleBuilder, TextBox};
+    ///
+    /// let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(96, 36));
+    ///
+    /// let style = TextBoxStyleBuilder::new().key_value_table(36).build();
+    ///
+    /// let text_box = TextBox::with_textbox_style(
+    ///     "SSID\tHome network\nSignal\tGood",
+    ///     bounding_box,
+    ///     character_style,
+    ///     style,
+    /// );
+    /// ```
+    #[inline]
+    pub const fn key_value_table(mut self, key_width: u16) -> Self {
+        self.style.tab_size = TabSize::Pixels(key_width);
+        self.style.first_line_indent = 0;
+        self.style.subsequent_line_indent = key_width as u32;
+
+        self
+    }
+
+    /// Sets the character that fills the space before tab stops.
+    ///
+    /// The leader character is repeated to fill the space a tab advances the cursor by, like the
+    /// dots in a table of contents. Only whole characters are drawn, the leaders never extend past
+    /// the tab stop.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .tab_leader('.')
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn tab_leader(mut self, leader: char) -> Self {
+        self.style.tab_leader = Some(leader);
+
+        self
+    }
+
+    /// Sets the alignment of the text that follows a tab, relative to the tab stop.
+    ///
+    /// # Example
+    ///
+    /// Right align a column of numbers:
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{TabAlignment, TabSize, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .tab_size(TabSize::Pixels(36))
+    ///     .tab_alignment(TabAlignment::Right)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn tab_alignment(mut self, alignment: TabAlignment) -> Self {
+        self.style.tab_alignment = alignment;
+
+        self
+    }
+
+    /// Draws strikethrough lines with the given position and thickness.
+    ///
+    /// By default, the strikethrough line of the character style is used. A custom line can be
+    /// made thicker to match a larger font, or doubled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{Strikethrough, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .strikethrough(Strikethrough::new(8, 2))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn strikethrough(mut self, strikethrough: Strikethrough) -> Self {
+        self.style.strikethrough = Some(strikethrough);
+
+        self
+    }
+
+    /// Draws underlines at the given position.
+    ///
+    /// By default, the underline of the character style is used, and the underlines that aren't
+    /// solid are drawn along the bottom of the line. A custom underline can be moved closer to the
+    /// text, and can leave gaps around descenders, which makes small underlined text easier to
+    /// read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{TextBoxStyleBuilder, Underline};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .underline(Underline::new(7).with_skip_descenders(true))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn underline(mut self, underline: Underline) -> Self {
+        self.style.underline = Some(underline);
+
+        self
+    }
+
     /// Render leading spaces.
     #[inline]
     pub const fn leading_spaces(mut self, render: bool) -> Self {
@@ -134,6 +340,451 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Sets the maximum number of rendered lines.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .max_lines(3)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn max_lines(mut self, max_lines: usize) -> Self {
+        self.style.max_lines = Some(max_lines);
+
+        self
+    }
+
+    /// Sets the extra space between characters, in pixels.
+    ///
+    /// Negative values move the characters closer together, but never make them go backwards.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .letter_spacing(1)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn letter_spacing(mut self, letter_spacing: i32) -> Self {
+        self.style.letter_spacing = letter_spacing;
+
+        self
+    }
+
+    /// Sets the extra width of spaces between words, in pixels.
+    ///
+    /// Negative values make spaces narrower, but never narrower than zero pixels. Justified text
+    /// stretches the adjusted spaces.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .word_spacing(-1)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn word_spacing(mut self, word_spacing: i32) -> Self {
+        self.style.word_spacing = word_spacing;
+
+        self
+    }
+
+    /// Sets the text direction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{TextBoxStyleBuilder, TextDirection};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .text_direction(TextDirection::RightToLeft)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn text_direction(mut self, text_direction: TextDirection) -> Self {
+        self.style.text_direction = text_direction;
+
+        self
+    }
+
+    /// Sets the rotation of the text box.
+    ///
+    /// The text is drawn rotated clockwise inside the bounding box of the text box. With
+    /// [`Rotation::Rotate90`] and [`Rotation::Rotate270`] the lines run along the height of the
+    /// bounding box, and height modes that resize the text box change its width instead.
+    ///
+    /// Plugins receive the positions of the text before the rotation is applied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{Rotation, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .rotation(Rotation::Rotate90)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn rotation(mut self, rotation: Rotation) -> Self {
+        self.style.rotation = rotation;
+
+        self
+    }
+
+    /// Sets the scale of the text box.
+    ///
+    /// Every pixel of the text box, including the text, the spacing, the padding and the border,
+    /// is drawn as a square of `scale` by `scale` display pixels. The text is laid out in a box
+    /// that is `scale` times smaller than the bounding box, so that larger text can be drawn
+    /// without a larger font.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .scale(2)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn scale(mut self, scale: u32) -> Self {
+        self.style.scale = scale;
+
+        self
+    }
+
+    /// Sets the baseline that is used to align the text within a line.
+    ///
+    /// Characters are drawn so that the given baseline of every character lines up. This matters
+    /// when the line contains characters of different sizes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// use embedded_graphics::text::Baseline;
+    ///
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .baseline(Baseline::Alphabetic)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn baseline(mut self, baseline: Baseline) -> Self {
+        self.style.baseline = baseline;
+
+        self
+    }
+
+    /// Sets the indentation of the first line of each paragraph, in pixels.
+    ///
+    /// Lines that start after a line break character (`\n`) are indented. Lines that are
+    /// created by wrapping the text are not.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .first_line_indent(12)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn first_line_indent(mut self, indent: u32) -> Self {
+        self.style.first_line_indent = indent;
+
+        self
+    }
+
+    /// Sets the indentation of the wrapped lines of each paragraph, in pixels.
+    ///
+    /// Every line of a paragraph except the first one is indented. This can be used to create
+    /// hanging indents, e.g. for bullet lists.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .subsequent_line_indent(12)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn subsequent_line_indent(mut self, indent: u32) -> Self {
+        self.style.subsequent_line_indent = indent;
+
+        self
+    }
+
+    /// Sets the line wrapping mode.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{TextBoxStyleBuilder, WrapMode};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .wrap_mode(WrapMode::None)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn wrap_mode(mut self, wrap_mode: WrapMode) -> Self {
+        self.style.wrap_mode = wrap_mode;
+
+        self
+    }
+
+    /// Sets whether the last line of each paragraph is stretched when the text is justified.
+    ///
+    /// By default, the last line of a paragraph and the last line of the text are left aligned.
+    /// This setting has no effect on other alignments.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::{alignment::HorizontalAlignment, style::TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .alignment(HorizontalAlignment::Justified)
+    ///     .justify_last_line(true)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn justify_last_line(mut self, justify_last_line: bool) -> Self {
+        self.style.justify_last_line = justify_last_line;
+
+        self
+    }
+
+    /// Sets the maximum width of a space in justified lines, in pixels.
+    ///
+    /// If a line can only be justified using wider spaces, it is left aligned instead. This
+    /// prevents large gaps between the words of short lines. The minimum width of a space is the
+    /// width of the space character of the font.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::{alignment::HorizontalAlignment, style::TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .alignment(HorizontalAlignment::Justified)
+    ///     .justify_max_space_width(12)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn justify_max_space_width(mut self, max_width: u32) -> Self {
+        self.style.justify_max_space_width = Some(max_width);
+
+        self
+    }
+
+    /// Sets whether justified lines without usable spaces are stretched between their characters.
+    ///
+    /// Text without spaces between words, like Chinese or Japanese text, can't be justified by
+    /// stretching the spaces. If this setting is enabled, the extra space of these lines is
+    /// distributed between their characters. This also applies to lines that would need spaces
+    /// wider than [`justify_max_space_width`](Self::justify_max_space_width).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::{alignment::HorizontalAlignment, style::TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .alignment(HorizontalAlignment::Justified)
+    ///     .justify_characters(true)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn justify_characters(mut self, justify_characters: bool) -> Self {
+        self.style.justify_characters = justify_characters;
+
+        self
+    }
+
+    /// Sets whether every space at which a line is wrapped is removed.
+    ///
+    /// By default, the whole run of spaces at a wrapping point is removed, so that the next line
+    /// starts with the following word. If disabled, only a single space is removed and the rest
+    /// of the spaces are moved to the start of the next line, where they are displayed if
+    /// [`leading_spaces`](Self::leading_spaces) are rendered. The spaces of preformatted text are
+    /// always kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .trim_wrapped_spaces(false)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn trim_wrapped_spaces(mut self, trim: bool) -> Self {
+        self.style.trim_wrapped_spaces = trim;
+
+        self
+    }
+
+    /// Sets the space between the edges of the text box and the text.
+    ///
+    /// The padding reduces the width available for the text. If the padding is larger than the
+    /// text box, the text area is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{Padding, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .padding(Padding::new(2, 4, 2, 4))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn padding(mut self, padding: Padding) -> Self {
+        self.style.padding = padding;
+
+        self
+    }
+
+    /// Sets the width of the border, in pixels.
+    ///
+    /// The border reduces the area available for the text by `width` pixels on each side. It is
+    /// only drawn if a border color is set using [`TextBox::set_border_color`].
+    ///
+    /// [`TextBox::set_border_color`]: crate::TextBox::set_border_color
+    #[inline]
+    pub const fn border_width(mut self, width: u32) -> Self {
+        self.style.border_width = width;
+
+        self
+    }
+
+    /// Sets the string that replaces the end of the last line when the text is truncated.
+    ///
+    /// The ellipsis is only used with [`HeightMode::TruncateWithEllipsis`]. The default is `…`,
+    /// which can be replaced for fonts that don't contain that character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{HeightMode, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .height_mode(HeightMode::TruncateWithEllipsis)
+    ///     .ellipsis("...")
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn ellipsis(mut self, ellipsis: &'static str) -> Self {
+        self.style.ellipsis = ellipsis;
+
+        self
+    }
+
+    /// Sets the characters after which words can be wrapped, in addition to whitespace.
+    ///
+    /// This allows long words, e.g. paths or identifiers, to be wrapped at their separators
+    /// instead of being broken at an arbitrary character. No hyphen is inserted at the wrapping
+    /// point. Word separators are only used with [`WrapMode::Word`]. The default is an empty
+    /// string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .word_separators("/-_")
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn word_separators(mut self, word_separators: &'static str) -> Self {
+        self.style.word_separators = word_separators;
+
+        self
+    }
+
+    /// Wraps URLs at their separators.
+    ///
+    /// Words that look like URLs, e.g. because they contain `://` or start with `www.`, are wrapped
+    /// after `//` and before `/`, `?`, `&` and `.`, if they don't fit in a line. URLs are only
+    /// wrapped with [`WrapMode::Word`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .break_urls(true)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn break_urls(mut self, break_urls: bool) -> Self {
+        self.style.break_urls = break_urls;
+
+        self
+    }
+
+    /// Draws lines that only contain three or more `-` characters as horizontal rules.
+    ///
+    /// The rule is drawn across the whole width of the line, through its vertical center, using
+    /// the current text color.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .horizontal_rules(true)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn horizontal_rules(mut self, horizontal_rules: bool) -> Self {
+        self.style.horizontal_rules = horizontal_rules;
+
+        self
+    }
+
+    /// Avoids leaving the last word of a paragraph alone in a line.
+    ///
+    /// If the last line of a wrapped paragraph would only contain a single word, the previous line
+    /// is wrapped one word earlier, so that the last line contains two words. The previous line is
+    /// not changed if the two words don't fit in a line together.
+    ///
+    /// This option only affects [`WrapMode::Word`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .avoid_widows(true)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn avoid_widows(mut self, avoid: bool) -> Self {
+        self.style.avoid_widows = avoid;
+
+        self
+    }
+
     /// Builds the [`TextBoxStyle`].
     #[inline]
     pub const fn build(mut self) -> TextBoxStyle {
@@ -0,0 +1,64 @@
+//! Bold and italic font variants.
+
+/// Bold and italic variants of the character style of a [`TextBox`].
+///
+/// The variants are selected by the `ChangeTextStyle::Bold` and `ChangeTextStyle::Italic` text
+/// style changes, e.g. using the `\x1b[1m` and `\x1b[3m` escape codes of the ANSI plugin. If a
+/// variant is missing, the closest available one is used: bold italic text falls back to the bold,
+/// then to the italic variant.
+///
+/// Variants only replace the character style of the text box. If an alternative font is selected
+/// using [`TextBox::set_fonts`], bold and italic have no effect.
+///
+/// [`TextBox`]: crate::TextBox
+/// [`TextBox::set_fonts`]: crate::TextBox::set_fonts
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct FontVariants<'a, S> {
+    /// The bold character style.
+    pub bold: Option<&'a S>,
+
+    /// The italic character style.
+    pub italic: Option<&'a S>,
+
+    /// The bold italic character style.
+    pub bold_italic: Option<&'a S>,
+}
+
+impl<S> Clone for FontVariants<'_, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for FontVariants<'_, S> {}
+
+impl<S> Default for FontVariants<'_, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S> FontVariants<'a, S> {
+    /// Creates an empty set of variants.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            bold: None,
+            italic: None,
+            bold_italic: None,
+        }
+    }
+
+    /// Returns the variant for the given combination of styles.
+    #[inline]
+    pub(crate) fn get(&self, bold: bool, italic: bool) -> Option<&'a S> {
+        match (bold, italic) {
+            (false, false) => None,
+            (true, false) => self.bold,
+            (false, true) => self.italic,
+            (true, true) => self.bold_italic.or(self.bold).or(self.italic),
+        }
+    }
+}
@@ -7,10 +7,11 @@ use crate::{
     plugin::PluginMarker as Plugin, rendering::cursor::Cursor, style::VerticalOverdraw, TextBox,
 };
 use core::ops::Range;
-use embedded_graphics::{geometry::Dimensions, text::renderer::TextRenderer};
+use embedded_graphics::text::renderer::TextRenderer;
 
 /// Specifies how the [`TextBox`]'s height should be adjusted.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeightMode {
     /// Keep the original [`TextBox`] height.
     ///
@@ -174,6 +175,115 @@ pub enum HeightMode {
     /// assert_eq!(text_box.bounding_box().size, Size::new(60, 18));
     /// ```
     ShrinkToText(VerticalOverdraw),
+
+    /// Keep the original [`TextBox`] height and only render full rows of text. If the text does
+    /// not fit the bounding box, the end of the last visible line is replaced by an ellipsis (`…`
+    /// by default, see [`TextBoxStyleBuilder::ellipsis`]).
+    ///
+    /// Note: the text always starts at the top of the [`TextBox`] if it does not fit, regardless
+    /// of the vertical alignment.
+    ///
+    /// # Example: `TruncateWithEllipsis` does not change the size of the [`TextBox`].
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// #
+    /// use embedded_graphics::primitives::Rectangle;
+    /// use embedded_text::{TextBox, style::HeightMode};
+    ///
+    /// // This TextBox is only tall enough to display one of the two lines
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 10));
+    /// let text_box = TextBox::with_height_mode(
+    ///     "Two lines\nof text",
+    ///     bounding_box,
+    ///     character_style,
+    ///     HeightMode::TruncateWithEllipsis,
+    /// );
+    ///
+    /// assert_eq!(text_box.bounding_box().size, Size::new(60, 10));
+    /// ```
+    ///
+    /// [`TextBoxStyleBuilder::ellipsis`]: crate::style::TextBoxStyleBuilder::ellipsis()
+    TruncateWithEllipsis,
+
+    /// Keep the original [`TextBox`] height and only render full rows of text. If the text does
+    /// not fit the bounding box, the end of the last visible line fades out over the given number
+    /// of pixels.
+    ///
+    /// The fade is dithered, so that it works with every color type: the glyph pixels are replaced
+    /// by the background color, or not drawn if the text has no background color, more and more
+    /// towards the end of the line.
+    ///
+    /// Note: the text always starts at the top of the [`TextBox`] if it does not fit, regardless
+    /// of the vertical alignment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// #
+    /// use embedded_graphics::primitives::Rectangle;
+    /// use embedded_text::{TextBox, style::HeightMode};
+    ///
+    /// // Fade out the last 18 pixels of the only visible line.
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 10));
+    /// let text_box = TextBox::with_height_mode(
+    ///     "A long description that doesn't fit",
+    ///     bounding_box,
+    ///     character_style,
+    ///     HeightMode::TruncateWithFade(18),
+    /// );
+    /// ```
+    TruncateWithFade(u32),
+
+    /// Sets both the width and the height of the [`TextBox`] to exactly fit the text.
+    ///
+    /// The text is wrapped using the original width of the [`TextBox`], then the width is set to
+    /// the width of the widest line. Lines are only broken at explicit line breaks (`\n`) if the
+    /// text is narrower than the original width, or if wrapping is disabled using
+    /// [`WrapMode::None`].
+    ///
+    /// Note: in this mode, vertical alignment is meaningless. Make sure to use [`Top`] alignment
+    /// for efficiency.
+    ///
+    /// # Example: `FitToWidth` shrinks the [`TextBox`] to the size of a label.
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// # };
+    /// # let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+    /// #
+    /// use embedded_graphics::primitives::Rectangle;
+    /// use embedded_text::{TextBox, style::HeightMode};
+    ///
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(120, 60));
+    /// let text_box = TextBox::with_height_mode(
+    ///     "Two lines\nof text",
+    ///     bounding_box,
+    ///     character_style,
+    ///     HeightMode::FitToWidth,
+    /// );
+    ///
+    /// // The widest line is "Two lines"
+    /// assert_eq!(text_box.bounding_box().size, Size::new(9 * 6, 18));
+    /// ```
+    ///
+    /// [`Top`]: crate::alignment::VerticalAlignment::Top
+    /// [`WrapMode::None`]: crate::style::WrapMode::None
+    FitToWidth,
 }
 
 impl HeightMode {
@@ -186,12 +296,18 @@ impl HeightMode {
         M: Plugin<'a, F::Color>,
     {
         match self {
-            HeightMode::Exact(_) => {}
+            HeightMode::Exact(_)
+            | HeightMode::TruncateWithEllipsis
+            | HeightMode::TruncateWithFade(_) => {}
             HeightMode::FitToText => {
                 text_box.fit_height();
             }
+            HeightMode::FitToWidth => {
+                text_box.fit_size();
+            }
             HeightMode::ShrinkToText(_) => {
-                text_box.fit_height_limited(text_box.bounding_box().size.height);
+                let rotation = text_box.style.rotation;
+                text_box.fit_height_limited(rotation.layout_size(text_box.bounds.size).height);
             }
         }
     }
@@ -204,9 +320,366 @@ impl HeightMode {
     pub(crate) fn calculate_displayed_row_range(self, cursor: &Cursor) -> Range<u32> {
         let overdraw = match self {
             HeightMode::Exact(overdraw) | HeightMode::ShrinkToText(overdraw) => overdraw,
-            HeightMode::FitToText => VerticalOverdraw::Visible,
+            HeightMode::FitToText | HeightMode::FitToWidth => VerticalOverdraw::Visible,
+            HeightMode::TruncateWithEllipsis | HeightMode::TruncateWithFade(_) => {
+                VerticalOverdraw::FullRowsOnly
+            }
         };
 
         overdraw.calculate_displayed_row_range(cursor)
     }
+
+    /// Returns whether text that doesn't fit is truncated.
+    pub(crate) const fn truncates(self) -> bool {
+        matches!(
+            self,
+            HeightMode::TruncateWithEllipsis | HeightMode::TruncateWithFade(_)
+        )
+    }
+
+    /// Returns whether the line at the cursor's position should end with an ellipsis or fade out
+    /// if the text doesn't fit.
+    ///
+    /// Only the last completely visible line is truncated. `last_line` is true if no more lines
+    /// are allowed to be rendered after the current one.
+    pub(crate) fn should_truncate(self, cursor: &Cursor, last_line: bool) -> bool {
+        match self {
+            HeightMode::TruncateWithEllipsis | HeightMode::TruncateWithFade(_) => {
+                let mut next_line = *cursor;
+                next_line.new_line();
+
+                cursor.in_display_area() && (last_line || !next_line.in_display_area())
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        style::{HeightMode, Padding, TextBoxStyleBuilder, WrapMode},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_truncated(text: &str, size: Size, pattern: &[&str]) {
+        assert_truncated_with(text, size, "\u{2026}", pattern);
+    }
+
+    #[track_caller]
+    fn assert_truncated_with(text: &str, size: Size, ellipsis: &'static str, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .height_mode(HeightMode::TruncateWithEllipsis)
+            .ellipsis(ellipsis)
+            .build();
+
+        TextBox::with_textbox_style(
+            text,
+            Rectangle::new(Point::zero(), size),
+            character_style,
+            style,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn fit_to_width() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (text; wrap mode; original width in characters; expected size in characters)
+            ("Two lines\nof text", WrapMode::Word, 20, (9, 2)),
+            ("word word word", WrapMode::Word, 10, (9, 2)),
+            ("word word word", WrapMode::None, 5, (14, 1)),
+            ("", WrapMode::Word, 5, (0, 1)),
+        ];
+
+        for (text, wrap_mode, width, (columns, rows)) in data {
+            let text_box = TextBox::with_textbox_style(
+                text,
+                Rectangle::new(Point::zero(), size_for(&FONT_6X9, width, 3)),
+                character_style,
+                TextBoxStyleBuilder::new()
+                    .height_mode(HeightMode::FitToWidth)
+                    .wrap_mode(wrap_mode)
+                    .build(),
+            );
+
+            assert_eq!(
+                text_box.bounding_box().size,
+                size_for(&FONT_6X9, columns, rows),
+                "Size of {:?} is incorrect",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn content_size() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (text; max width in characters; expected size in characters)
+            ("", 5, (0, 1)),
+            ("word", 5, (4, 1)),
+            ("word word", 5, (4, 2)),
+            ("word\n", 5, (4, 2)),
+        ];
+
+        for (text, width, (columns, rows)) in data {
+            let text_box = TextBox::new(text, Rectangle::zero(), character_style);
+
+            assert_eq!(
+                text_box.content_size(width * 6),
+                size_for(&FONT_6X9, columns, rows),
+                "Size of {:?} is incorrect",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn fit_to_text_includes_padding() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "word word",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 1) + Size::new(4, 0)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .padding(Padding::new(1, 2, 3, 2))
+                .build(),
+        );
+
+        assert_eq!(
+            text_box.bounding_box().size,
+            size_for(&FONT_6X9, 6, 2) + Size::new(4, 4)
+        );
+    }
+
+    #[test]
+    fn fit_to_text_includes_border() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "word word",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 1) + Size::new(6, 0)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .border_width(2)
+                .padding(Padding::all(1))
+                .build(),
+        );
+
+        assert_eq!(
+            text_box.bounding_box().size,
+            size_for(&FONT_6X9, 6, 2) + Size::new(6, 6)
+        );
+    }
+
+    #[test]
+    fn truncate_last_line() {
+        assert_truncated(
+            "word and other words",
+            size_for(&FONT_6X9, 9, 1),
+            &[
+                "..................................................##..",
+                "......................#.......................#..#..#.",
+                "......................#.......................#.....#.",
+                "#...#...##...#.#....###.........###..###....###...##..",
+                "#.#.#..#..#..##.#..#..#........#..#..#..#..#..#...#...",
+                "#.#.#..#..#..#.....#..#........#..#..#..#..#..#.......",
+                ".#.#....##...#......###.........###..#..#...###...#...",
+                "......................................................",
+                "......................................................",
+            ],
+        );
+    }
+
+    #[test]
+    fn truncate_exact_fit_has_no_ellipsis() {
+        assert_truncated(
+            "word\nand",
+            size_for(&FONT_6X9, 9, 2),
+            &[
+                "........................",
+                "......................#.",
+                "......................#.",
+                "#...#...##...#.#....###.",
+                "#.#.#..#..#..##.#..#..#.",
+                "#.#.#..#..#..#.....#..#.",
+                ".#.#....##...#......###.",
+                "........................",
+                "........................",
+                "..................      ",
+                "................#.      ",
+                "................#.      ",
+                "..###..###....###.      ",
+                ".#..#..#..#..#..#.      ",
+                ".#..#..#..#..#..#.      ",
+                "..###..#..#...###.      ",
+                "..................      ",
+                "..................      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn truncate_with_custom_ellipsis() {
+        assert_truncated_with(
+            "abcdefg",
+            size_for(&FONT_6X9, 5, 1),
+            "\u{2026}",
+            &[
+                "..........................##..",
+                ".......#..............#..#..#.",
+                ".......#..............#.....#.",
+                "..###..###....###...###...##..",
+                ".#..#..#..#..#.....#..#...#...",
+                ".#..#..#..#..#.....#..#.......",
+                "..###..###....###...###...#...",
+                "..............................",
+                "..............................",
+            ],
+        );
+        assert_truncated_with(
+            "abcdefg",
+            size_for(&FONT_6X9, 5, 1),
+            "...",
+            &[
+                "..............................",
+                ".......#......................",
+                ".......#......................",
+                "..###..###....................",
+                ".#..#..#..#...................",
+                ".#..#..#..#...##....##....##..",
+                "..###..###....##....##....##..",
+                "..............................",
+                "..............................",
+            ],
+        );
+    }
+
+    #[test]
+    fn truncate_long_word() {
+        assert_truncated(
+            "verylongword",
+            size_for(&FONT_6X9, 5, 1),
+            &[
+                "..........................##..",
+                ".........................#..#.",
+                "............................#.",
+                ".#..#...##...#.#...#..#...##..",
+                ".#..#..#.##..##.#..#..#...#...",
+                "..##...##....#.....#..#.......",
+                "..##....###..#......###...#...",
+                "...................#..#.......",
+                "....................##........",
+            ],
+        );
+    }
+
+    #[test]
+    fn truncate_empty_text() {
+        assert_truncated("", size_for(&FONT_6X9, 5, 1), &[]);
+    }
+
+    #[track_caller]
+    fn assert_faded(text: &str, size: Size, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .height_mode(HeightMode::TruncateWithFade(12))
+            .build();
+
+        TextBox::with_textbox_style(
+            text,
+            Rectangle::new(Point::zero(), size),
+            character_style,
+            style,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn fade_last_line() {
+        assert_faded(
+            "##### #",
+            size_for(&FONT_6X9, 5, 1),
+            &[
+                "..............................",
+                ".#.#...#.#...#.#...#.#........",
+                ".#.#...#.#...#.#...#.#...#....",
+                "#####.#####.#####.#####.#.#.#.",
+                ".#.#...#.#...#.#...#.#........",
+                "#####.#####.#####.#####.#.#...",
+                ".#.#...#.#...#.#...#.#...#....",
+                ".#.#...#.#...#.#...#.#........",
+                "..............................",
+            ],
+        );
+    }
+
+    #[test]
+    fn fade_only_if_text_is_truncated() {
+        assert_faded(
+            "#####",
+            size_for(&FONT_6X9, 5, 1),
+            &[
+                "..............................",
+                ".#.#...#.#...#.#...#.#...#.#..",
+                ".#.#...#.#...#.#...#.#...#.#..",
+                "#####.#####.#####.#####.#####.",
+                ".#.#...#.#...#.#...#.#...#.#..",
+                "#####.#####.#####.#####.#####.",
+                ".#.#...#.#...#.#...#.#...#.#..",
+                ".#.#...#.#...#.#...#.#...#.#..",
+                "..............................",
+            ],
+        );
+    }
 }
@@ -0,0 +1,164 @@
+//! Iterator over the wrapped lines of a text.
+
+use embedded_graphics::text::renderer::TextRenderer;
+
+use crate::{
+    parser::Parser,
+    plugin::{NoPlugin, PluginWrapper},
+    rendering::fonts::{Fonts, StyleState},
+    style::{LineEndType, LineMeasurement, TextBoxStyle},
+};
+
+/// A single wrapped line of text.
+///
+/// See [`TextBoxStyle::lines`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct WrappedLine<'a> {
+    /// The part of the text that makes up the line.
+    ///
+    /// The slice includes the whitespace that is consumed by wrapping the line and the line break
+    /// character that ends the line, if any.
+    pub text: &'a str,
+
+    /// The width of the line, in pixels, excluding the indentation of the line.
+    pub width: u32,
+
+    /// How the line ends.
+    pub line_end_type: LineEndType,
+}
+
+/// Iterator over the wrapped lines of a text.
+///
+/// Created by [`TextBoxStyle::lines`].
+#[must_use]
+pub struct Lines<'a, S>
+where
+    S: TextRenderer,
+{
+    style: TextBoxStyle,
+    fonts: Fonts<'a, S>,
+    plugin: PluginWrapper<'a, NoPlugin<S::Color>, S::Color>,
+    parser: Parser<'a, S::Color>,
+    style_state: StyleState,
+    max_width: u32,
+    paragraph_start: bool,
+    line_count: usize,
+    finished: bool,
+}
+
+impl<S> Clone for Lines<'_, S>
+where
+    S: TextRenderer,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            style: self.style,
+            fonts: self.fonts,
+            plugin: self.plugin.clone(),
+            parser: self.parser.clone(),
+            style_state: self.style_state,
+            max_width: self.max_width,
+            paragraph_start: self.paragraph_start,
+            line_count: self.line_count,
+            finished: self.finished,
+        }
+    }
+}
+
+impl<'a, S> Lines<'a, S>
+where
+    S: TextRenderer,
+{
+    pub(crate) fn new(
+        style: TextBoxStyle,
+        character_style: &'a S,
+        text: &'a str,
+        max_width: u32,
+    ) -> Self {
+        Self {
+            style,
+            fonts: Fonts::single(character_style),
+            plugin: PluginWrapper::new(NoPlugin::new()),
+            parser: Parser::parse(text),
+            style_state: StyleState::new(),
+            max_width,
+            paragraph_start: true,
+            line_count: 0,
+            finished: style.max_lines == Some(0),
+        }
+    }
+
+    fn remaining_text(&self) -> &'a str {
+        self.plugin.remaining_text(&self.parser)
+    }
+
+    fn measure_line(&mut self) -> LineMeasurement {
+        self.plugin.new_line();
+        let indent = self
+            .style
+            .line_indent(self.paragraph_start)
+            .min(self.max_width);
+        self.style.measure_line(
+            &self.plugin,
+            self.fonts,
+            &mut self.style_state,
+            &mut self.parser,
+            self.max_width - indent,
+        )
+    }
+}
+
+impl<'a, S> Iterator for Lines<'a, S>
+where
+    S: TextRenderer,
+{
+    type Item = WrappedLine<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let start = self.remaining_text();
+        let measurement = self.measure_line();
+        let mut line_end_type = measurement.line_end_type;
+
+        // Wrapped lines without visible text are not displayed, they are part of this line.
+        while line_end_type == LineEndType::LineBreak {
+            let mut lookahead = self.clone();
+            lookahead.paragraph_start = false;
+            let next = lookahead.measure_line();
+            if !next.is_empty() {
+                break;
+            }
+
+            *self = lookahead;
+            line_end_type = next.line_end_type;
+        }
+
+        let end = self.remaining_text();
+
+        match line_end_type {
+            LineEndType::CarriageReturn => {}
+            LineEndType::LineBreak => self.paragraph_start = false,
+            LineEndType::NewLine => self.paragraph_start = true,
+            LineEndType::EndOfText => self.finished = true,
+        }
+
+        if line_end_type != LineEndType::CarriageReturn {
+            self.line_count += 1;
+            if Some(self.line_count) == self.style.max_lines {
+                self.finished = true;
+            }
+        }
+
+        Some(WrappedLine {
+            text: &start[..start.len() - end.len()],
+            width: measurement.width,
+            line_end_type,
+        })
+    }
+}
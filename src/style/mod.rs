@@ -23,7 +23,11 @@
 //! bounding box. Similarly `Right` aligned text will line up the ends of the lines with the right
 //! side of the bounding box. `Center`ed text will be positioned at equal distance from the left and
 //! right sides. `Justified` text will distribute the text in such a way that both the start and end
-//! of a line will align with the respective sides of the bounding box.
+//! of a line will align with the respective sides of the bounding box. The last line of each
+//! paragraph is left aligned, unless [`justify_last_line`] is set. Lines that would need spaces
+//! wider than [`justify_max_space_width`] are also left aligned. Lines without spaces, e.g. in
+//! Chinese or Japanese text, are stretched between their characters if [`justify_characters`] is
+//! set.
 //!
 //! The [`vertical_alignment`] setting sets the vertical alignment of the text.
 //! With the default value `Top` the top of the text is lined up with the top of the bounding box.
@@ -38,7 +42,40 @@
 //! the line spacing.
 //!
 //! The [`tab_size`] setting sets the maximum width of a tab character. It can be specified in
-//! either pixels of number of space characters.
+//! either pixels of number of space characters. Alternatively, a list of tab stop positions can be
+//! given. The [`tab_alignment`] setting sets whether the text after a tab starts, ends, is centered
+//! or has its decimal point at the tab stop.
+//!
+//! The [`letter_spacing`] setting adds extra space between the characters of words, in pixels.
+//! Negative values tighten the text.
+//!
+//! The [`word_spacing`] setting changes the width of the spaces between words, in pixels.
+//! Justified text is stretched starting from the adjusted space width.
+//!
+//! The [`text_direction`] setting sets whether lines are laid out from left to right or from right
+//! to left. The horizontal alignment follows the text direction, so right-to-left text with the
+//! default `Left` alignment is lined up with the right side of the bounding box.
+//!
+//! The [`baseline`] setting sets which baseline of the characters is lined up within a line.
+//!
+//! The [`first_line_indent`] setting sets the indentation of the first line of each paragraph.
+//! The [`subsequent_line_indent`] setting sets the indentation of the rest of the lines of a
+//! paragraph, which can be used to create hanging indents.
+//!
+//! The [`wrap_mode`] setting sets whether lines that don't fit the width of the bounding box are
+//! wrapped. With [`WrapMode::None`] lines are only broken by line break characters and the
+//! overflowing part of the lines is clipped. The [`word_separators`] setting lists characters,
+//! e.g. `/` in paths, after which words can be wrapped. The [`break_urls`] setting wraps URLs at
+//! their separators.
+//!
+//! The [`padding`] setting sets the space between the edges of the bounding box and the text. A
+//! border, [`border_width`] pixels wide, can be drawn between the edges and the padding.
+//!
+//! The [`horizontal_rules`] setting draws lines that only contain `---` as horizontal rules
+//! across the text box, e.g. to separate sections of help text.
+//!
+//! The [`max_lines`] setting limits the number of rendered lines, regardless of the height of the
+//! bounding box. The limit is also applied when measuring the height of the text.
 //!
 //! Advanced settings
 //! -----------------
@@ -57,6 +94,12 @@
 //! the text clipped. [`FullRowsOnly`] only renders lines that are completely inside the bounding
 //! box.
 //!
+//! [`TruncateWithEllipsis`] keeps the height of the bounding box and only renders full rows. If the
+//! text does not fit, the end of the last visible line is replaced by an ellipsis.
+//! [`TruncateWithFade`] fades out the end of the last visible line instead.
+//!
+//! [`FitToWidth`] adjusts both the width and the height of the text box to the size of the text.
+//!
 //! For examples on how to use height mode settings, see the documentation of [`HeightMode`].
 //!
 //! The [`leading_spaces`] and [`trailing_spaces`] settings set whether the spaces at the beginning
@@ -163,41 +206,91 @@
 //! [`TextBox`]: crate::TextBox
 //! [`alignment`]: TextBoxStyle::alignment
 //! [`vertical_alignment`]: TextBoxStyle::vertical_alignment
+//! [`justify_last_line`]: TextBoxStyle::justify_last_line
+//! [`justify_max_space_width`]: TextBoxStyle::justify_max_space_width
+//! [`justify_characters`]: TextBoxStyle::justify_characters
 //! [`line_height`]: TextBoxStyle::line_height
 //! [`paragraph_spacing`]: TextBoxStyle::paragraph_spacing
 //! [`tab_size`]: TextBoxStyle::tab_size
+//! [`tab_alignment`]: TextBoxStyle::tab_alignment
+//! [`letter_spacing`]: TextBoxStyle::letter_spacing
+//! [`word_spacing`]: TextBoxStyle::word_spacing
+//! [`text_direction`]: TextBoxStyle::text_direction
+//! [`baseline`]: TextBoxStyle::baseline
+//! [`first_line_indent`]: TextBoxStyle::first_line_indent
+//! [`subsequent_line_indent`]: TextBoxStyle::subsequent_line_indent
+//! [`max_lines`]: TextBoxStyle::max_lines
+//! [`padding`]: TextBoxStyle::padding
+//! [`border_width`]: TextBoxStyle::border_width
+//! [`wrap_mode`]: TextBoxStyle::wrap_mode
+//! [`word_separators`]: TextBoxStyle::word_separators
+//! [`break_urls`]: TextBoxStyle::break_urls
+//! [`horizontal_rules`]: TextBoxStyle::horizontal_rules
 //! [`height_mode`]: TextBoxStyle::height_mode
 //! [`leading_spaces`]: TextBoxStyle::leading_spaces
 //! [`trailing_spaces`]: TextBoxStyle::trailing_spaces
 //! [`Exact`]: HeightMode::Exact
 //! [`FitToText`]: HeightMode::FitToText
 //! [`ShrinkToText`]: HeightMode::ShrinkToText
+//! [`TruncateWithEllipsis`]: HeightMode::TruncateWithEllipsis
+//! [`TruncateWithFade`]: HeightMode::TruncateWithFade
+//! [`FitToWidth`]: HeightMode::FitToWidth
 //! [`Visible`]: VerticalOverdraw::Visible
 //! [`Hidden`]: VerticalOverdraw::Hidden
 //! [`FullRowsOnly`]: VerticalOverdraw::FullRowsOnly
 //! [`embedded-graphics` documentation]: https://docs.rs/embedded-graphics/0.7.1/embedded_graphics/text/index.html
 
 mod builder;
+mod font_variants;
 mod height_mode;
+mod lines;
+mod padding;
+#[cfg(feature = "serde")]
+mod remote;
+mod rotation;
+mod strikethrough;
+mod tab_alignment;
+mod text_direction;
+mod underline;
 mod vertical_overdraw;
+mod wrap_mode;
 
 use core::convert::Infallible;
 
 use crate::{
     alignment::{HorizontalAlignment, VerticalAlignment},
-    parser::Parser,
+    parser::{ChangeTextStyle, Parser},
     plugin::{NoPlugin, PluginMarker as Plugin, PluginWrapper, ProcessingState},
     rendering::{
-        cursor::LineCursor,
-        line_iter::{ElementHandler, LineElementParser, LineEndType},
+        cursor::{LineCursor, TabStops},
+        drop_cap::DropCap,
+        fonts::{Fonts, StyleState},
+        line_iter::{ElementHandler, LineElementParser},
         space_config::SpaceConfig,
     },
-    utils::str_width,
+    utils::{baseline_offset, clusters, str_width, str_width_with_spacing},
+};
+use az::SaturatingAs;
+use embedded_graphics::{
+    geometry::Size,
+    text::{renderer::TextRenderer, Baseline, LineHeight},
 };
-use embedded_graphics::text::{renderer::TextRenderer, LineHeight};
+
+pub use crate::rendering::line_iter::LineEndType;
 
 pub use self::{
-    builder::TextBoxStyleBuilder, height_mode::HeightMode, vertical_overdraw::VerticalOverdraw,
+    builder::TextBoxStyleBuilder,
+    font_variants::FontVariants,
+    height_mode::HeightMode,
+    lines::{Lines, WrappedLine},
+    padding::Padding,
+    rotation::Rotation,
+    strikethrough::Strikethrough,
+    tab_alignment::TabAlignment,
+    text_direction::TextDirection,
+    underline::Underline,
+    vertical_overdraw::VerticalOverdraw,
+    wrap_mode::WrapMode,
 };
 
 /// Tab size helper
@@ -205,12 +298,20 @@ pub use self::{
 /// This type makes it more obvious what unit is used to define the width of tabs.
 /// The default tab size is 4 spaces.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TabSize {
     /// Tab width as a number of pixels.
     Pixels(u16),
 
     /// Tab width as a number of space characters.
     Spaces(u16),
+
+    /// Tab stops at the given positions, in pixels from the start of the line.
+    ///
+    /// The positions must be in ascending order. After the last stop, tab stops are repeated
+    /// using the distance between the last two stops.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Stops(&'static [u32]),
 }
 
 impl TabSize {
@@ -220,12 +321,13 @@ impl TabSize {
         Self::Spaces(4)
     }
 
-    /// Calculate the rendered with of the next tab
+    /// Calculate the positions of the tab stops.
     #[inline]
-    pub(crate) fn into_pixels(self, renderer: &impl TextRenderer) -> u32 {
+    pub(crate) fn into_tab_stops(self, renderer: &impl TextRenderer) -> TabStops {
         match self {
-            TabSize::Pixels(px) => px as u32,
-            TabSize::Spaces(n) => n as u32 * str_width(renderer, " "),
+            TabSize::Pixels(px) => TabStops::uniform(px as u32),
+            TabSize::Spaces(n) => TabStops::uniform(n as u32 * str_width(renderer, " ")),
+            TabSize::Stops(stops) => TabStops::at(stops),
         }
     }
 }
@@ -239,8 +341,31 @@ impl TabSize {
 /// To construct a new `TextBoxStyle` object, use the [`TextBoxStyle::default`] method or
 /// the [`TextBoxStyleBuilder`] object.
 ///
+/// Both can be used in constant expressions, so styles can be stored in `static` tables:
+///
+/// ```rust
+/// use embedded_text::{
+///     alignment::HorizontalAlignment,
+///     style::{TextBoxStyle, TextBoxStyleBuilder},
+/// };
+///
+/// static STYLES: [TextBoxStyle; 2] = [
+///     TextBoxStyle::DEFAULT,
+///     TextBoxStyleBuilder::new()
+///         .alignment(HorizontalAlignment::Center)
+///         .build(),
+/// ];
+/// ```
+///
+/// With the `serde` feature, `TextBoxStyle` can be serialized and deserialized. Missing fields are
+/// deserialized with their default values. The [`ellipsis`](Self::ellipsis),
+/// [`word_separators`](Self::word_separators) and [`TabSize::Stops`] can't be borrowed from the
+/// deserialized data, so they are serialized, but not deserialized.
+///
 /// [`TextBox`]: crate::TextBox
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default = "TextBoxStyle::default"))]
 #[non_exhaustive]
 #[must_use]
 pub struct TextBoxStyle {
@@ -254,8 +379,12 @@ pub struct TextBoxStyle {
     pub height_mode: HeightMode,
 
     /// Line height.
+    #[cfg_attr(feature = "serde", serde(with = "remote::LineHeightDef"))]
     pub line_height: LineHeight,
 
+    /// The minimum distance between the baselines of two adjacent lines, in pixels.
+    pub min_line_height: u32,
+
     /// Paragraph spacing.
     pub paragraph_spacing: u32,
 
@@ -267,13 +396,111 @@ pub struct TextBoxStyle {
 
     /// True to render trailing spaces
     pub trailing_spaces: bool,
+
+    /// The maximum number of lines to render. `None` means no limit.
+    pub max_lines: Option<usize>,
+
+    /// Extra space between characters, in pixels. Negative values tighten the text.
+    pub letter_spacing: i32,
+
+    /// Extra width of the spaces between words, in pixels. Negative values make spaces narrower.
+    pub word_spacing: i32,
+
+    /// Text direction.
+    pub text_direction: TextDirection,
+
+    /// The rotation of the text box.
+    pub rotation: Rotation,
+
+    /// The number of display pixels every pixel of the text is drawn as, horizontally and
+    /// vertically.
+    pub scale: u32,
+
+    /// The baseline that is used to align the text within a line.
+    #[cfg_attr(feature = "serde", serde(with = "remote::BaselineDef"))]
+    pub baseline: Baseline,
+
+    /// Indentation of the first line of each paragraph, in pixels.
+    pub first_line_indent: u32,
+
+    /// Indentation of the wrapped lines of each paragraph, in pixels.
+    pub subsequent_line_indent: u32,
+
+    /// Line wrapping mode.
+    pub wrap_mode: WrapMode,
+
+    /// True to also stretch the last line of each paragraph when the text is justified.
+    pub justify_last_line: bool,
+
+    /// The maximum width of a space in justified lines, in pixels. `None` means no limit.
+    pub justify_max_space_width: Option<u32>,
+
+    /// True to justify lines without usable spaces by adding space between their characters.
+    pub justify_characters: bool,
+
+    /// True to remove every space at which a line is wrapped, instead of only one of them.
+    ///
+    /// Has no effect on preformatted text.
+    pub trim_wrapped_spaces: bool,
+
+    /// The space between the edges of the text box and the text.
+    pub padding: Padding,
+
+    /// The width of the border drawn around the text box, in pixels.
+    ///
+    /// The border is drawn inside the bounding box, between its edges and the padding.
+    pub border_width: u32,
+
+    /// The string that replaces the end of the last line when the text is truncated.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    pub ellipsis: &'static str,
+
+    /// True to wrap lines earlier to avoid leaving the last word of a paragraph alone in a line.
+    pub avoid_widows: bool,
+
+    /// The character that fills the space before tab stops. `None` leaves the space blank.
+    pub tab_leader: Option<char>,
+
+    /// True to render the whitespace of the text as it is.
+    ///
+    /// Runs of spaces and tabs are rendered at the start and at the end of lines, regardless of
+    /// the [`leading_spaces`](Self::leading_spaces) and
+    /// [`trailing_spaces`](Self::trailing_spaces) settings. Lines are only broken by line break
+    /// characters (`\n`), unless [`wrap_mode`](Self::wrap_mode) is [`WrapMode::BreakAll`], which
+    /// also breaks lines that are too long. Spaces at the wrapping points are kept and continue on
+    /// the next line.
+    pub preformatted: bool,
+
+    /// The characters after which words can be wrapped, in addition to whitespace.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    pub word_separators: &'static str,
+
+    /// True to wrap URLs at their separators, e.g. before `/` or `?`.
+    pub break_urls: bool,
+
+    /// True to draw lines that only contain three or more `-` characters as horizontal rules.
+    pub horizontal_rules: bool,
+
+    /// The alignment of the text that follows a tab, relative to the tab stop.
+    pub tab_alignment: TabAlignment,
+
+    /// The strikethrough line drawn instead of the one of the character style. `None` uses the
+    /// strikethrough of the character style.
+    pub strikethrough: Option<Strikethrough>,
+
+    /// The position of the underlines drawn instead of the ones of the character style. `None`
+    /// uses the underline of the character style.
+    pub underline: Option<Underline>,
 }
 
 impl TextBoxStyle {
+    /// The text box style with default settings.
+    pub const DEFAULT: Self = TextBoxStyleBuilder::new().build();
+
     /// Creates a new text box style object with default settings.
     #[inline]
     pub const fn default() -> Self {
-        TextBoxStyleBuilder::new().build()
+        Self::DEFAULT
     }
 
     /// Creates a new text box style with the given alignment.
@@ -319,24 +546,156 @@ impl TextBoxStyle {
     }
 }
 
+/// The result of measuring a piece of text.
+///
+/// See [`TextBoxStyle::measure_text`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct TextMeasurement {
+    /// The size of the text.
+    ///
+    /// The width is the width of the widest line, the height is the height of every line,
+    /// including line and paragraph spacing.
+    pub size: Size,
+
+    /// The number of lines.
+    pub line_count: usize,
+}
+
+/// The vertical metrics of the font of a text box.
+///
+/// See [`TextBox::font_metrics`].
+///
+/// [`TextBox::font_metrics`]: crate::TextBox::font_metrics()
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct FontMetrics {
+    /// The distance from the top of a line to the alphabetic baseline, in pixels.
+    pub ascent: u32,
+
+    /// The distance from the alphabetic baseline to the bottom of a line, in pixels.
+    pub descent: u32,
+
+    /// The distance between the tops of two adjacent lines, in pixels.
+    pub line_spacing: u32,
+}
+
+/// The scroll position of a text box, e.g. to draw a scrollbar.
+///
+/// The values are in the same units as the [`vertical_offset`] of the text box, so the
+/// [`scale`](TextBoxStyle::scale) of the text box is not applied to them.
+///
+/// See [`TextBox::scroll_metrics`].
+///
+/// [`vertical_offset`]: crate::TextBox::vertical_offset
+/// [`TextBox::scroll_metrics`]: crate::TextBox::scroll_metrics()
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ScrollMetrics {
+    /// The height of the text, in pixels.
+    pub content_height: u32,
+
+    /// The height of the text area, the part of the text that is visible at once, in pixels.
+    pub viewport_height: u32,
+
+    /// The distance the text is scrolled up by, in pixels.
+    ///
+    /// This is the negated vertical offset of the text box.
+    pub offset: i32,
+}
+
+impl ScrollMetrics {
+    /// Returns the offset at which the end of the text is at the bottom of the text area.
+    ///
+    /// Returns 0 if the text fits into the text area.
+    #[inline]
+    #[must_use]
+    pub const fn max_offset(&self) -> u32 {
+        self.content_height.saturating_sub(self.viewport_height)
+    }
+}
+
 /// Information about a line.
-#[derive(Debug, Copy, Clone)]
+///
+/// The measurement is made before the line is rendered, using the default space width returned
+/// by the text renderer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[must_use]
-pub(crate) struct LineMeasurement {
+pub struct LineMeasurement {
     /// Maximum line width in pixels.
-    pub max_line_width: u32,
+    pub(crate) max_line_width: u32,
 
     /// Width in pixels, using the default space width returned by the text renderer.
-    pub width: u32,
+    pub(crate) width: u32,
 
     /// What kind of line ending was encountered.
-    pub line_end_type: LineEndType,
+    pub(crate) line_end_type: LineEndType,
 
     /// Number of spaces in the current line.
-    pub space_count: u32,
+    pub(crate) space_count: u32,
+
+    /// Number of spaces at the end of the line that are included in `space_count`.
+    pub(crate) trailing_space_count: u32,
+
+    /// Number of printed characters in the line.
+    pub(crate) character_count: u32,
+
+    /// Height of the line, in pixels.
+    pub(crate) line_height: u32,
+
+    /// Distance of the baseline from the top of the line, in pixels.
+    pub(crate) baseline_offset: i32,
 }
 
 impl LineMeasurement {
+    /// Returns the maximum width of the line, in pixels.
+    #[inline]
+    pub fn max_line_width(&self) -> u32 {
+        self.max_line_width
+    }
+
+    /// Returns the width of the line, in pixels.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns how the line ends.
+    #[inline]
+    pub fn line_end_type(&self) -> LineEndType {
+        self.line_end_type
+    }
+
+    /// Returns the number of spaces in the line.
+    #[inline]
+    pub fn space_count(&self) -> u32 {
+        self.space_count
+    }
+
+    /// Returns the number of spaces at the end of the line, which are included in
+    /// [`space_count`](Self::space_count).
+    #[inline]
+    pub fn trailing_space_count(&self) -> u32 {
+        self.trailing_space_count
+    }
+
+    /// Returns the number of printed characters in the line, not including whitespace.
+    #[inline]
+    pub fn character_count(&self) -> u32 {
+        self.character_count
+    }
+
+    /// Returns the height of the line, in pixels.
+    ///
+    /// If the line contains text in multiple fonts, this is the height needed to fit every font
+    /// on a common baseline.
+    #[inline]
+    pub fn line_height(&self) -> u32 {
+        self.line_height
+    }
+
+    /// Returns whether the line is the last line of a paragraph.
+    #[inline]
     pub fn last_line(&self) -> bool {
         matches!(
             self.line_end_type,
@@ -344,6 +703,8 @@ impl LineMeasurement {
         )
     }
 
+    /// Returns whether the line contains no visible text.
+    #[inline]
     pub fn is_empty(&self) -> bool {
         self.width == 0
     }
@@ -351,15 +712,35 @@ impl LineMeasurement {
 
 struct MeasureLineElementHandler<'a, S> {
     style: &'a S,
+    fonts: Fonts<'a, S>,
+    style_state: StyleState,
+    baseline: Baseline,
+    letter_spacing: i32,
     trailing_spaces: bool,
     cursor: u32,
     pos: u32,
     right: u32,
     partial_space_count: u32,
     space_count: u32,
+    character_count: u32,
+
+    /// The extents of the used fonts above and below the baseline.
+    ascent: i32,
+    descent: i32,
 }
 
-impl<'a, S> MeasureLineElementHandler<'a, S> {
+impl<'a, S: TextRenderer> MeasureLineElementHandler<'a, S> {
+    /// Includes the current font in the height of the line.
+    fn use_font(&mut self) {
+        let line_height = self.style.line_height();
+        let offset = baseline_offset(self.style, self.baseline)
+            - self.style_state.script.offset(line_height);
+        self.ascent = self.ascent.max(offset);
+        self.descent = self
+            .descent
+            .max(line_height.saturating_as::<i32>() - offset);
+    }
+
     fn space_count(&self) -> u32 {
         if self.trailing_spaces {
             self.partial_space_count
@@ -368,6 +749,10 @@ impl<'a, S> MeasureLineElementHandler<'a, S> {
         }
     }
 
+    fn trailing_space_count(&self) -> u32 {
+        self.space_count() - self.space_count
+    }
+
     fn right(&self) -> u32 {
         if self.trailing_spaces {
             self.pos
@@ -382,10 +767,11 @@ impl<'a, S: TextRenderer> ElementHandler for MeasureLineElementHandler<'a, S> {
     type Color = S::Color;
 
     fn measure(&self, st: &str) -> u32 {
-        str_width(self.style, st)
+        str_width_with_spacing(self.style, st, self.letter_spacing)
     }
 
     fn whitespace(&mut self, _st: &str, count: u32, width: u32) -> Result<(), Self::Error> {
+        self.use_font();
         self.cursor += width;
         self.pos = self.pos.max(self.cursor);
         self.partial_space_count += count;
@@ -394,10 +780,12 @@ impl<'a, S: TextRenderer> ElementHandler for MeasureLineElementHandler<'a, S> {
     }
 
     fn printed_characters(&mut self, str: &str, width: Option<u32>) -> Result<(), Self::Error> {
+        self.use_font();
         self.cursor += width.unwrap_or_else(|| self.measure(str));
         self.pos = self.pos.max(self.cursor);
         self.right = self.pos;
         self.space_count = self.partial_space_count;
+        self.character_count += clusters(str).count() as u32;
 
         Ok(())
     }
@@ -407,9 +795,120 @@ impl<'a, S: TextRenderer> ElementHandler for MeasureLineElementHandler<'a, S> {
 
         Ok(())
     }
+
+    fn inline_object(&mut self, _id: u32, size: Size) -> Result<(), Self::Error> {
+        // Objects stand on the alphabetic baseline of the current font, and include the baseline
+        // row like the glyphs do.
+        self.use_font();
+        let bottom = baseline_offset(self.style, Baseline::Alphabetic)
+            - baseline_offset(self.style, self.baseline)
+            + 1;
+        self.ascent = self.ascent.max(size.height.saturating_as::<i32>() - bottom);
+
+        self.cursor += size.width;
+        self.pos = self.pos.max(self.cursor);
+        self.right = self.pos;
+        self.space_count = self.partial_space_count;
+
+        Ok(())
+    }
+
+    fn change_text_style(
+        &mut self,
+        change: ChangeTextStyle<Self::Color>,
+    ) -> Result<(), Self::Error> {
+        if let Some(style) = self.style_state.update(&self.fonts, change) {
+            self.style = style;
+        }
+
+        Ok(())
+    }
 }
 
 impl TextBoxStyle {
+    /// Returns the distance between the baseline of a line and the next one.
+    ///
+    /// `line_height` is the height of the fonts used in the line.
+    pub(crate) fn line_spacing(&self, line_height: u32) -> u32 {
+        self.line_height
+            .to_absolute(line_height)
+            .max(self.min_line_height)
+    }
+
+    /// Returns the wrap mode used to lay out the text.
+    ///
+    /// Preformatted text is only wrapped in `BreakAll` mode.
+    pub(crate) fn effective_wrap_mode(&self) -> WrapMode {
+        match self.wrap_mode {
+            WrapMode::Word if self.preformatted => WrapMode::None,
+            wrap_mode => wrap_mode,
+        }
+    }
+
+    /// Returns whether the spaces at the start of lines are rendered.
+    pub(crate) const fn keeps_leading_spaces(&self) -> bool {
+        self.leading_spaces || self.preformatted
+    }
+
+    /// Returns whether the spaces at the end of lines are rendered.
+    pub(crate) const fn keeps_trailing_spaces(&self) -> bool {
+        self.trailing_spaces || self.preformatted
+    }
+
+    /// Returns the width of a space character, including the word spacing.
+    pub(crate) fn space_width(&self, renderer: &impl TextRenderer) -> u32 {
+        self.add_word_spacing(str_width(renderer, " "))
+    }
+
+    /// Adds the word spacing to the measured width of a space character.
+    pub(crate) fn add_word_spacing(&self, width: u32) -> u32 {
+        width
+            .saturating_as::<i32>()
+            .saturating_add(self.word_spacing)
+            .max(0) as u32
+    }
+
+    /// Returns the scale of the text box. A scale of 0 is treated as 1.
+    pub(crate) const fn scale_factor(&self) -> u32 {
+        if self.scale == 0 {
+            1
+        } else {
+            self.scale
+        }
+    }
+
+    /// Returns the size of the box the text is laid out in, for a text box of the given size.
+    pub(crate) fn layout_size(&self, size: Size) -> Size {
+        self.rotation.layout_size(size) / self.scale_factor()
+    }
+
+    /// Returns the indentation of a line, in pixels.
+    pub(crate) const fn line_indent(&self, paragraph_start: bool) -> u32 {
+        if paragraph_start {
+            self.first_line_indent
+        } else {
+            self.subsequent_line_indent
+        }
+    }
+
+    /// Returns the space between the edges of the text box and the text, including the border.
+    pub(crate) const fn insets(&self) -> Padding {
+        let Padding {
+            top,
+            right,
+            bottom,
+            left,
+        } = self.padding;
+        let border = self.border_width;
+
+        Padding::new(
+            top.saturating_add(border),
+            right.saturating_add(border),
+            bottom.saturating_add(border),
+            left.saturating_add(border),
+        )
+    }
+
     /// Measure the width and count spaces in a single line of text.
     ///
     /// Returns (width, rendered space count, carried token)
@@ -417,11 +916,15 @@ impl TextBoxStyle {
     /// Instead of peeking ahead when processing tokens, this function advances the parser before
     /// processing a token. If a token opens a new line, it will be returned as the carried token.
     /// If the carried token is `None`, the parser has finished processing the text.
+    ///
+    /// `style_state` is the text style state at the start of the line, and is updated to the state at the end
+    /// of the line.
     #[inline]
     pub(crate) fn measure_line<'a, S, M>(
         &self,
         plugin: &PluginWrapper<'a, M, S::Color>,
-        character_style: &S,
+        fonts: Fonts<'_, S>,
+        style_state: &mut StyleState,
         parser: &mut Parser<'a, S::Color>,
         max_line_width: u32,
     ) -> LineMeasurement
@@ -429,33 +932,55 @@ impl TextBoxStyle {
         S: TextRenderer,
         M: Plugin<'a, S::Color>,
     {
-        let cursor = LineCursor::new(max_line_width, self.tab_size.into_pixels(character_style));
+        let character_style = fonts.select(style_state);
+        let cursor = LineCursor::new(
+            max_line_width,
+            self.tab_size.into_tab_stops(character_style),
+        );
 
         let mut iter = LineElementParser::new(
             parser,
             plugin,
             cursor,
-            SpaceConfig::new(str_width(character_style, " "), None),
+            SpaceConfig::new(self.space_width(character_style), None),
             self,
         );
 
         let mut handler = MeasureLineElementHandler {
             style: character_style,
-            trailing_spaces: self.trailing_spaces,
+            fonts,
+            style_state: *style_state,
+            baseline: self.baseline,
+            letter_spacing: self.letter_spacing,
+            trailing_spaces: self.keeps_trailing_spaces(),
 
             cursor: 0,
             pos: 0,
             right: 0,
             partial_space_count: 0,
             space_count: 0,
+            character_count: 0,
+            ascent: 0,
+            descent: 0,
         };
         let last_token = iter.process(&mut handler).unwrap();
 
+        if handler.ascent == 0 && handler.descent == 0 {
+            // Nothing was printed, the line has the height of the current font.
+            handler.use_font();
+        }
+
+        *style_state = handler.style_state;
+
         LineMeasurement {
             max_line_width,
             width: handler.right(),
             space_count: handler.space_count(),
+            trailing_space_count: handler.trailing_space_count(),
+            character_count: handler.character_count,
             line_end_type: last_token,
+            line_height: (handler.ascent + handler.descent).saturating_as(),
+            baseline_offset: handler.ascent,
         }
     }
 
@@ -501,13 +1026,13 @@ impl TextBoxStyle {
         S: TextRenderer,
     {
         let plugin = PluginWrapper::new(NoPlugin::new());
-        self.measure_text_height_impl(plugin, character_style, text, max_width)
+        self.measure_text_height_impl(plugin, Fonts::single(character_style), text, max_width)
     }
 
     pub(crate) fn measure_text_height_impl<'a, S, M>(
         &self,
         plugin: PluginWrapper<'a, M, S::Color>,
-        character_style: &S,
+        fonts: Fonts<'_, S>,
         text: &'a str,
         max_width: u32,
     ) -> u32
@@ -515,30 +1040,192 @@ impl TextBoxStyle {
         S: TextRenderer,
         M: Plugin<'a, S::Color>,
     {
-        let mut parser = Parser::parse(text);
-        let base_line_height = character_style.line_height();
-        let line_height = self.line_height.to_absolute(base_line_height);
-        let mut height = base_line_height;
+        self.measure_text_impl(plugin, fonts, text, max_width)
+            .size
+            .height
+    }
+
+    /// Measures the size and the number of lines of text when rendered using a given width.
+    ///
+    /// The returned width is the width of the widest line.
+    ///
+    /// # Example: measure text when rendered using a 6x9 MonoFont and 72px width.
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// # use embedded_graphics::{
+    /// #     geometry::Size,
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+    /// #     pixelcolor::BinaryColor,
+    /// # };
+    /// #
+    /// let character_style = MonoTextStyleBuilder::new()
+    ///     .font(&FONT_6X9)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    /// let style = TextBoxStyleBuilder::new().build();
+    ///
+    /// let measurement = style.measure_text(
+    ///     &character_style,
+    ///     "Lorem Ipsum is simply dummy text of the printing and typesetting industry.",
+    ///     72,
+    /// );
+    ///
+    /// // The widest line is "printing and".
+    /// assert_eq!(7, measurement.line_count);
+    /// assert_eq!(Size::new(12 * 6, 7 * 9), measurement.size);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn measure_text<S>(
+        &self,
+        character_style: &S,
+        text: &str,
+        max_width: u32,
+    ) -> TextMeasurement
+    where
+        S: TextRenderer,
+    {
+        let plugin = PluginWrapper::new(NoPlugin::new());
+        self.measure_text_impl(plugin, Fonts::single(character_style), text, max_width)
+    }
+
+    /// Returns an iterator over the lines of text when rendered using a given width.
+    ///
+    /// The lines are wrapped the same way as they are when the text is rendered. Each line is
+    /// returned as a slice of `text`, along with its width.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// # use embedded_graphics::{
+    /// #     mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+    /// #     pixelcolor::BinaryColor,
+    /// # };
+    /// #
+    /// let character_style = MonoTextStyleBuilder::new()
+    ///     .font(&FONT_6X9)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    /// let style = TextBoxStyleBuilder::new().build();
+    ///
+    /// let mut lines = style.lines(&character_style, "Lorem Ipsum is simply dummy text", 72);
+    ///
+    /// assert_eq!(lines.next().unwrap().text, "Lorem Ipsum ");
+    /// assert_eq!(lines.next().unwrap().text, "is simply ");
+    /// assert_eq!(lines.next().unwrap().text, "dummy text");
+    /// assert_eq!(lines.next(), None);
+    /// ```
+    #[inline]
+    pub fn lines<'a, S>(
+        &self,
+        character_style: &'a S,
+        text: &'a str,
+        max_width: u32,
+    ) -> Lines<'a, S>
+    where
+        S: TextRenderer,
+    {
+        Lines::new(*self, character_style, text, max_width)
+    }
+
+    pub(crate) fn measure_text_impl<'a, S, M>(
+        &self,
+        plugin: PluginWrapper<'a, M, S::Color>,
+        fonts: Fonts<'_, S>,
+        text: &'a str,
+        max_width: u32,
+    ) -> TextMeasurement
+    where
+        S: TextRenderer,
+        M: Plugin<'a, S::Color>,
+    {
+        let max_lines = self.max_lines.unwrap_or(usize::MAX);
+        if max_lines == 0 {
+            return TextMeasurement {
+                size: Size::zero(),
+                line_count: 0,
+            };
+        }
+
+        let mut parser = Parser::parse(text).with_horizontal_rules(self.horizontal_rules);
+        let mut style_state = StyleState::new();
+
+        // The total height of the finished lines, and the height of the current line.
+        let mut height = 0_u32;
+        let mut line_height = 0;
+        let mut width = 0;
+        let mut line_count = 1;
 
         plugin.set_state(ProcessingState::Measure);
 
+        let drop_cap = DropCap::take(self, fonts, &plugin, &mut parser);
+
         let mut prev_end = LineEndType::EndOfText;
+        let mut paragraph_start = true;
+        let mut measured_lines = 0;
 
         loop {
             plugin.new_line();
-            let lm = self.measure_line(&plugin, character_style, &mut parser, max_width);
+            let indent = self
+                .line_indent(paragraph_start)
+                .saturating_add(plugin.line_indent(fonts.primary()))
+                .saturating_add(drop_cap.map_or(0, |d| d.line_indent(measured_lines)))
+                .min(max_width);
+            measured_lines += 1;
+            let lm = self.measure_line(
+                &plugin,
+                fonts,
+                &mut style_state,
+                &mut parser,
+                max_width - indent,
+            );
 
             if prev_end == LineEndType::LineBreak && !lm.is_empty() {
-                height += line_height;
+                if line_count == max_lines {
+                    break;
+                }
+                line_count += 1;
+                height = height.saturating_add(self.line_spacing(line_height));
+                line_height = 0;
+            }
+
+            // Empty wrapped lines are not displayed, so they don't affect the line height.
+            if prev_end != LineEndType::LineBreak || !lm.is_empty() {
+                line_height = line_height.max(lm.line_height);
             }
+            width = width.max(indent.saturating_add(lm.width));
 
             match lm.line_end_type {
-                LineEndType::CarriageReturn | LineEndType::LineBreak => {}
-                LineEndType::NewLine => height += line_height + self.paragraph_spacing,
-                LineEndType::EndOfText => return height,
+                LineEndType::CarriageReturn => {}
+                LineEndType::LineBreak => paragraph_start = false,
+                LineEndType::NewLine => {
+                    paragraph_start = true;
+                    if line_count == max_lines {
+                        break;
+                    }
+                    line_count += 1;
+                    height = height
+                        .saturating_add(self.line_spacing(line_height))
+                        .saturating_add(self.paragraph_spacing);
+                    line_height = 0;
+                }
+                LineEndType::EndOfText => break,
             }
             prev_end = lm.line_end_type;
         }
+
+        // The drop cap may be taller than the lines next to it.
+        let drop_cap_height = drop_cap.map_or(0, |d| d.style.line_height());
+
+        TextMeasurement {
+            size: Size::new(
+                width,
+                height.saturating_add(line_height).max(drop_cap_height),
+            ),
+            line_count,
+        }
     }
 }
 
@@ -548,7 +1235,9 @@ mod test {
         alignment::*,
         parser::Parser,
         plugin::{NoPlugin, PluginWrapper},
-        style::{builder::TextBoxStyleBuilder, TextBoxStyle},
+        rendering::fonts::{Fonts, StyleState},
+        style::{builder::TextBoxStyleBuilder, TabAlignment, TabSize, TextBoxStyle, WrapMode},
+        utils::test::size_for,
     };
     use embedded_graphics::{
         mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
@@ -667,7 +1356,8 @@ mod test {
         let mut plugin = PluginWrapper::new(NoPlugin::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            Fonts::single(&character_style),
+            &mut StyleState::new(),
             &mut text,
             6 * FONT_6X9.character_size.width,
         );
@@ -690,7 +1380,8 @@ mod test {
         let mut plugin = PluginWrapper::new(NoPlugin::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            Fonts::single(&character_style),
+            &mut StyleState::new(),
             &mut text,
             5 * FONT_6X9.character_size.width,
         );
@@ -744,6 +1435,373 @@ mod test {
         assert_eq!(height, 6 * 11 + 9);
     }
 
+    #[test]
+    fn height_with_min_line_height() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .line_height(LineHeight::Pixels(7))
+            .min_line_height(11)
+            .paragraph_spacing(1)
+            .build();
+
+        let height = style.measure_text_height(&character_style, "a\nb\nc", 72);
+
+        assert_eq!(height, 2 * (11 + 1) + 9);
+    }
+
+    #[test]
+    fn height_with_huge_min_line_height() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .min_line_height(u32::MAX)
+            .paragraph_spacing(1)
+            .build();
+
+        let height = style.measure_text_height(&character_style, "a\nb c\nd", 6);
+
+        assert_eq!(height, u32::MAX);
+    }
+
+    #[test]
+    fn height_with_overlapping_lines() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .line_height(LineHeight::Pixels(7))
+            .build();
+
+        let height = style.measure_text_height(&character_style, "a\nb\nc", 72);
+
+        assert_eq!(height, 2 * 7 + 9);
+    }
+
+    #[test]
+    fn measure_text_counts_lines() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .tab_size(TabSize::Spaces(4))
+            .build();
+
+        let data = [
+            // (text; max width; expected size in characters; expected lines)
+            ("", 36, (0, 1), 1),
+            ("word", 36, (4, 1), 1),
+            ("word\n", 36, (4, 2), 2),
+            ("word\n\n", 36, (4, 3), 3),
+            ("a\tb", 36, (5, 1), 1),
+            ("word word", 36, (4, 2), 2),
+            ("Longer\rnowrap", 36, (6, 1), 1),
+        ];
+
+        for (text, width, (columns, rows), expected_lines) in data {
+            let measurement = style.measure_text(&character_style, text, width);
+
+            assert_eq!(
+                measurement.size,
+                size_for(&FONT_6X9, columns, rows),
+                "Size of {:?} is incorrect",
+                text
+            );
+            assert_eq!(
+                measurement.line_count, expected_lines,
+                "Line count of {:?} is incorrect",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn measure_text_without_wrapping() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new().wrap_mode(WrapMode::None).build();
+
+        let measurement = style.measure_text(&character_style, "word word\nwordword", 36);
+
+        assert_eq!(measurement.size, size_for(&FONT_6X9, 9, 2));
+        assert_eq!(measurement.line_count, 2);
+    }
+
+    #[test]
+    fn measure_text_break_all() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .wrap_mode(WrapMode::BreakAll)
+            .build();
+
+        let data = [
+            // (text; expected size in characters; expected lines)
+            ("abcdefghijklmnopqrst", (5, 4), 4),
+            ("ab cdefgh", (5, 2), 2),
+            ("ab\ncdefgh", (5, 3), 3),
+        ];
+
+        for (text, (columns, rows), expected_lines) in data {
+            let measurement = style.measure_text(&character_style, text, 5 * 6);
+
+            assert_eq!(
+                measurement.size,
+                size_for(&FONT_6X9, columns, rows),
+                "Size of {:?} is incorrect",
+                text
+            );
+            assert_eq!(
+                measurement.line_count, expected_lines,
+                "Line count of {:?} is incorrect",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn lines() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyle::default();
+
+        let data: [(&str, &[(&str, u32)]); 7] = [
+            // (text; expected lines and their width in characters)
+            ("", &[("", 0)]),
+            ("word", &[("word", 4)]),
+            ("word\n", &[("word\n", 4), ("", 0)]),
+            ("word word", &[("word ", 4), ("word", 4)]),
+            ("word      \nword", &[("word      \n", 4), ("word", 4)]),
+            ("word\r\nword", &[("word\r\n", 4), ("word", 4)]),
+            ("wordword", &[("word", 4), ("word", 4)]),
+        ];
+
+        for (text, expected) in data {
+            let mut lines = style.lines(&character_style, text, 24);
+
+            for (line_text, width) in expected {
+                let line = lines.next().unwrap();
+                assert_eq!(line.text, *line_text, "Line of {:?} is incorrect", text);
+                assert_eq!(line.width, width * 6, "Width of {:?} is incorrect", text);
+            }
+            assert_eq!(lines.next(), None, "Too many lines in {:?}", text);
+        }
+    }
+
+    #[test]
+    fn lines_avoid_widows() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new().avoid_widows(true).build();
+
+        let data: [(&str, &[&str]); 5] = [
+            // (text; expected lines)
+            ("aa bb cc dd\nee", &["aa bb ", "cc dd\n", "ee"]),
+            ("aa bb cc dd  ", &["aa bb ", "cc dd  "]),
+            ("aa bb cc dd ee", &["aa bb cc ", "dd ee"]),
+            ("aa bbbb cccc", &["aa bbbb ", "cccc"]),
+            ("aa bb\u{AD}cc dd", &["aa bb\u{AD}", "cc dd"]),
+        ];
+
+        for (text, expected) in data {
+            let lines = style
+                .lines(&character_style, text, 8 * 6)
+                .map(|line| line.text)
+                .collect::<Vec<_>>();
+
+            assert_eq!(lines, expected, "Lines of {:?} are incorrect", text);
+        }
+    }
+
+    #[test]
+    fn lines_break_words_with_multibyte_characters() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .wrap_mode(WrapMode::BreakAll)
+            .build();
+
+        let lines = style
+            .lines(&character_style, "a\u{e9}bc", 2 * 6)
+            .map(|line| line.text)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["a\u{e9}", "bc"]);
+    }
+
+    #[test]
+    #[cfg(feature = "grapheme")]
+    fn lines_break_between_grapheme_clusters() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .wrap_mode(WrapMode::BreakAll)
+            .build();
+
+        let lines = style
+            .lines(&character_style, "ae\u{301}e\u{301}b", 2 * 6)
+            .map(|line| (line.text, line.width))
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, [("ae\u{301}", 12), ("e\u{301}b", 12)]);
+    }
+
+    #[test]
+    fn lines_match_measured_line_count() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new().max_lines(3).build();
+        let text = "Lorem Ipsum is simply dummy text of the printing and typesetting industry.";
+
+        let measurement = style.measure_text(&character_style, text, 72);
+
+        assert_eq!(
+            style.lines(&character_style, text, 72).count(),
+            measurement.line_count
+        );
+    }
+
+    #[test]
+    fn height_with_max_lines() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new().max_lines(3).build();
+
+        let height = style.measure_text_height(
+            &character_style,
+            "Lorem Ipsum is simply dummy text of the printing and typesetting industry.",
+            72,
+        );
+
+        assert_eq!(height, 3 * 9);
+
+        let style = TextBoxStyleBuilder::new().max_lines(3).build();
+
+        let height = style.measure_text_height(&character_style, "Lorem\nIpsum", 72);
+
+        assert_eq!(height, 2 * 9);
+    }
+
+    #[test]
+    fn measure_with_letter_spacing() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [(0, 18), (2, 22), (-1, 16), (-10, 6)];
+
+        for (letter_spacing, width) in data {
+            let style = TextBoxStyleBuilder::new()
+                .letter_spacing(letter_spacing)
+                .build();
+
+            let measurement = style.measure_text(&character_style, "abc", 72);
+
+            assert_eq!(measurement.size.width, width, "{}", letter_spacing);
+        }
+
+        // Wrapping takes the extra space into account.
+        let style = TextBoxStyleBuilder::new().letter_spacing(2).build();
+        let measurement = style.measure_text(&character_style, "abc abc", 42);
+
+        assert_eq!(measurement.line_count, 2);
+    }
+
+    #[test]
+    fn measure_with_word_spacing() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [(0, 30), (2, 34), (-4, 22), (-10, 18)];
+
+        for (word_spacing, width) in data {
+            let style = TextBoxStyleBuilder::new()
+                .word_spacing(word_spacing)
+                .build();
+
+            let measurement = style.measure_text(&character_style, "a b c", 72);
+
+            assert_eq!(measurement.size.width, width, "{}", word_spacing);
+        }
+
+        // Wrapping takes the extra space into account.
+        let style = TextBoxStyleBuilder::new().word_spacing(6).build();
+        let measurement = style.measure_text(&character_style, "a b c", 36);
+
+        assert_eq!(measurement.line_count, 2);
+    }
+
+    #[test]
+    fn measure_with_huge_word_spacing() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .word_spacing(i32::MAX)
+            .avoid_widows(true)
+            .tab_alignment(TabAlignment::Right)
+            .build();
+
+        let measurement = style.measure_text(&character_style, "ab cd ef\tgh ij", 36);
+
+        assert_eq!(measurement.line_count, 4);
+    }
+
+    #[test]
+    fn measure_with_first_line_indent() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let style = TextBoxStyleBuilder::new().first_line_indent(12).build();
+
+        let measurement = style.measure_text(&character_style, "ab cd ef\ngh ij", 36);
+
+        assert_eq!(measurement.line_count, 4);
+        assert_eq!(measurement.size.width, 30);
+        assert_eq!(measurement.size.height, 4 * 9);
+    }
+
     #[test]
     fn soft_hyphenated_line_width_includes_hyphen_width() {
         let character_style = MonoTextStyleBuilder::new()
@@ -758,11 +1816,43 @@ mod test {
         let mut plugin = PluginWrapper::new(NoPlugin::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            Fonts::single(&character_style),
+            &mut StyleState::new(),
             &mut Parser::parse("soft\u{AD}hyphen"),
             50,
         );
 
         assert_eq!(lm.width, 30);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn style_is_serialized() {
+        let style = TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .line_height(LineHeight::Percent(150))
+            .tab_size(TabSize::Pixels(10))
+            .build();
+
+        let json = serde_json::to_string(&style).unwrap();
+        let deserialized: TextBoxStyle = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized, style);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn missing_fields_are_deserialized_as_default() {
+        let style: TextBoxStyle =
+            serde_json::from_str(r#"{"vertical_alignment":"Middle","line_height":{"Pixels":12}}"#)
+                .unwrap();
+
+        assert_eq!(
+            style,
+            TextBoxStyleBuilder::new()
+                .vertical_alignment(VerticalAlignment::Middle)
+                .line_height(LineHeight::Pixels(12))
+                .build()
+        );
+    }
 }
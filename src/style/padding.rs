@@ -0,0 +1,94 @@
+//! Text box padding.
+
+use az::SaturatingAs;
+use embedded_graphics::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+/// The space between the edges of a [`TextBox`] and the text, in pixels.
+///
+/// [`TextBox`]: crate::TextBox
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Padding {
+    /// Space above the text.
+    pub top: u32,
+
+    /// Space on the right side of the text.
+    pub right: u32,
+
+    /// Space below the text.
+    pub bottom: u32,
+
+    /// Space on the left side of the text.
+    pub left: u32,
+}
+
+impl Padding {
+    /// Creates a new padding object.
+    #[inline]
+    pub const fn new(top: u32, right: u32, bottom: u32, left: u32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Creates a padding object that has the same padding on every side.
+    #[inline]
+    pub const fn all(padding: u32) -> Self {
+        Self::new(padding, padding, padding, padding)
+    }
+
+    /// Creates a padding object without padding.
+    #[inline]
+    pub const fn zero() -> Self {
+        Self::all(0)
+    }
+
+    /// Returns the sum of the left and right padding.
+    pub(crate) const fn horizontal(&self) -> u32 {
+        self.left.saturating_add(self.right)
+    }
+
+    /// Returns the sum of the top and bottom padding.
+    pub(crate) const fn vertical(&self) -> u32 {
+        self.top.saturating_add(self.bottom)
+    }
+
+    /// Returns the area inside the padding.
+    ///
+    /// If the padding is larger than the bounds, it is clamped to the size of the bounds.
+    pub(crate) fn shrink(&self, bounds: Rectangle) -> Rectangle {
+        let Size { width, height } = bounds.size;
+
+        let left = self.left.min(width);
+        let right = self.right.min(width - left);
+        let top = self.top.min(height);
+        let bottom = self.bottom.min(height - top);
+
+        Rectangle::new(
+            bounds.top_left + Point::new(left.saturating_as(), top.saturating_as()),
+            Size::new(width - left - right, height - top - bottom),
+        )
+    }
+
+    /// Returns the size that contains the given size and the padding.
+    pub(crate) fn expand(&self, size: Size) -> Size {
+        Size::new(
+            size.width.saturating_add(self.horizontal()),
+            size.height.saturating_add(self.vertical()),
+        )
+    }
+}
+
+impl From<(u32, u32, u32, u32)> for Padding {
+    /// Creates a padding object from a `(top, right, bottom, left)` tuple.
+    #[inline]
+    fn from((top, right, bottom, left): (u32, u32, u32, u32)) -> Self {
+        Self::new(top, right, bottom, left)
+    }
+}
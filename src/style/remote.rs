@@ -0,0 +1,22 @@
+//! Serialization of the `embedded-graphics` types used in [`TextBoxStyle`].
+//!
+//! [`TextBoxStyle`]: super::TextBoxStyle
+
+use embedded_graphics::text::{Baseline, LineHeight};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LineHeight")]
+pub(crate) enum LineHeightDef {
+    Pixels(u32),
+    Percent(u32),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Baseline")]
+pub(crate) enum BaselineDef {
+    Top,
+    Bottom,
+    Middle,
+    Alphabetic,
+}
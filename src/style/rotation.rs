@@ -0,0 +1,94 @@
+//! Text box rotation options.
+
+use embedded_graphics::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+/// The rotation of a text box, clockwise.
+///
+/// The text is laid out in a box that is rotated back to the upright position, then every pixel is
+/// rotated into the bounding box of the text box. With `Rotate90` and `Rotate270`, the lines of
+/// text run along the height of the bounding box, so the width of the bounding box limits the
+/// number of lines.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    /// The text is not rotated.
+    Rotate0,
+
+    /// The text is rotated by 90 degrees, the lines run from top to bottom.
+    Rotate90,
+
+    /// The text is rotated by 180 degrees, it is upside down.
+    Rotate180,
+
+    /// The text is rotated by 270 degrees, the lines run from bottom to top.
+    Rotate270,
+}
+
+impl Rotation {
+    /// Returns the size of the box the text is laid out in, for a text box of the given size.
+    ///
+    /// This function is its own inverse.
+    pub(crate) const fn layout_size(self, size: Size) -> Size {
+        match self {
+            Rotation::Rotate0 | Rotation::Rotate180 => size,
+            Rotation::Rotate90 | Rotation::Rotate270 => Size::new(size.height, size.width),
+        }
+    }
+
+    /// Maps a point of the layout box to the bounding box of the text box.
+    ///
+    /// The layout box has the same top left corner as the bounding box.
+    pub(crate) fn transform(self, bounds: &Rectangle, point: Point) -> Point {
+        let Point { x, y } = point - bounds.top_left;
+        let Size { width, height } = bounds.size;
+        let (right, bottom) = (width as i32 - 1, height as i32 - 1);
+
+        let rotated = match self {
+            Rotation::Rotate0 => Point::new(x, y),
+            Rotation::Rotate90 => Point::new(right - y, x),
+            Rotation::Rotate180 => Point::new(right - x, bottom - y),
+            Rotation::Rotate270 => Point::new(y, bottom - x),
+        };
+
+        bounds.top_left + rotated
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        primitives::Rectangle,
+    };
+
+    use crate::style::Rotation;
+
+    #[test]
+    fn corners_are_rotated() {
+        let bounds = Rectangle::new(Point::new(10, 20), Size::new(3, 5));
+
+        // The layout box is 5 pixels wide and 3 pixels tall.
+        let top_left = Point::new(10, 20);
+        let top_right = Point::new(14, 20);
+
+        assert_eq!(
+            Rotation::Rotate90.transform(&bounds, top_left),
+            Point::new(12, 20)
+        );
+        assert_eq!(
+            Rotation::Rotate90.transform(&bounds, top_right),
+            Point::new(12, 24)
+        );
+        assert_eq!(
+            Rotation::Rotate270.transform(&bounds, top_left),
+            Point::new(10, 24)
+        );
+        assert_eq!(
+            Rotation::Rotate270.transform(&bounds, top_right),
+            Point::new(10, 20)
+        );
+    }
+}
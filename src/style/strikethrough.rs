@@ -0,0 +1,42 @@
+//! Strikethrough options.
+
+/// A strikethrough line drawn by `embedded-text`, instead of by the character style.
+///
+/// The line is drawn over the text that has a strikethrough color, in that color. Its position is
+/// relative to the top of the character style, so it follows superscript and subscript text.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Strikethrough {
+    /// The distance of the top row of the line from the top of the character style, in pixels.
+    pub offset: u32,
+
+    /// The height of the line, in pixels.
+    pub thickness: u32,
+
+    /// True to draw a second line below the first one, separated by a gap as tall as a line.
+    pub double: bool,
+}
+
+impl Strikethrough {
+    /// Creates a single strikethrough line.
+    #[inline]
+    pub const fn new(offset: u32, thickness: u32) -> Self {
+        Self {
+            offset,
+            thickness,
+            double: false,
+        }
+    }
+
+    /// Creates a double strikethrough line.
+    ///
+    /// `offset` is the position of the upper line.
+    #[inline]
+    pub const fn double(offset: u32, thickness: u32) -> Self {
+        Self {
+            offset,
+            thickness,
+            double: true,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//! Tab stop alignment options.
+
+/// Sets how the text that follows a tab is aligned to the tab stop.
+///
+/// The aligned text ends at the next tab, or at the end of the line. If the text doesn't fit
+/// before the tab stop, it starts at the position of the tab.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TabAlignment {
+    /// The text starts at the tab stop. This is the default.
+    Left,
+
+    /// The text is centered on the tab stop.
+    Center,
+
+    /// The text ends at the tab stop.
+    Right,
+
+    /// The first `.` of the text is placed at the tab stop. Text without a `.` ends at the tab
+    /// stop, like [`Right`](Self::Right) aligned text.
+    Decimal,
+}
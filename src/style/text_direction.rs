@@ -0,0 +1,22 @@
+//! Text direction options.
+
+/// The direction in which the characters of a line are laid out.
+///
+/// `RightToLeft` is meant for text written in a single right-to-left script, without shaping.
+/// Mixing left-to-right and right-to-left text in a single line (bidirectional text) is not
+/// supported.
+///
+/// The horizontal alignment follows the text direction: in right-to-left mode
+/// [`HorizontalAlignment::Left`] aligns the start of the lines with the right side of the bounding
+/// box.
+///
+/// [`HorizontalAlignment::Left`]: crate::alignment::HorizontalAlignment::Left
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextDirection {
+    /// Lines start on the left side.
+    LeftToRight,
+
+    /// Lines start on the right side.
+    RightToLeft,
+}
@@ -0,0 +1,38 @@
+//! Underline options.
+
+/// The position of underlines drawn by `embedded-text`, instead of by the character style.
+///
+/// Underlines of every [`UnderlineStyle`] are drawn at this position. Its position is relative to
+/// the top of the character style, so it follows superscript and subscript text.
+///
+/// [`UnderlineStyle`]: crate::UnderlineStyle
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Underline {
+    /// The distance of the row of the underline from the top of the character style, in pixels.
+    ///
+    /// The second line of a double underline is drawn two rows above this one.
+    pub offset: u32,
+
+    /// True to leave gaps in the underline around the parts of the glyphs that cross it, e.g. the
+    /// descenders of `g`, `p` and `y`.
+    pub skip_descenders: bool,
+}
+
+impl Underline {
+    /// Creates a continuous underline at the given offset.
+    #[inline]
+    pub const fn new(offset: u32) -> Self {
+        Self {
+            offset,
+            skip_descenders: false,
+        }
+    }
+
+    /// Sets whether the underline leaves gaps around the glyphs that cross it.
+    #[inline]
+    pub const fn with_skip_descenders(mut self, skip: bool) -> Self {
+        self.skip_descenders = skip;
+        self
+    }
+}
@@ -6,7 +6,15 @@ use crate::rendering::cursor::Cursor;
 use core::ops::Range;
 
 /// Vertical overdraw options used by height modes that don't conform exactly to the text size.
+///
+/// Every line is drawn through a draw target that is clipped to the width of the text area, so
+/// words that don't fit in a line never extend past the sides of the text box. The overdraw option
+/// sets which rows of a line are drawn: with [`Hidden`](Self::Hidden) the text is clipped to the
+/// bounding box on every edge, and with [`FullRowsOnly`](Self::FullRowsOnly) lines that would be
+/// clipped are not drawn at all. Only [`Visible`](Self::Visible) draws text outside the bounding
+/// box.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerticalOverdraw {
     /// Only render full rows of text.
     FullRowsOnly,
@@ -55,7 +63,7 @@ mod test {
 
     use crate::{
         alignment::*,
-        style::{HeightMode, TextBoxStyleBuilder, VerticalOverdraw},
+        style::{HeightMode, TextBoxStyleBuilder, VerticalOverdraw, WrapMode},
         TextBox,
     };
 
@@ -171,4 +179,28 @@ mod test {
             "#.#.#..#..#..#.....#..#.",
         ]);
     }
+
+    #[test]
+    fn hidden_clips_to_every_edge() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let style = TextBoxStyleBuilder::new()
+            .height_mode(HeightMode::Exact(VerticalOverdraw::Hidden))
+            .wrap_mode(WrapMode::None)
+            .build();
+
+        // The first line is too wide and the second line is only partially visible.
+        let bounds = Rectangle::new(Point::new(3, 2), Size::new(20, 13));
+        TextBox::with_textbox_style("words\nwords", bounds, character_style, style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(display.affected_area(), bounds);
+    }
 }
@@ -0,0 +1,22 @@
+//! Line wrapping options.
+
+/// Sets how lines are broken when the text doesn't fit the width of the bounding box.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WrapMode {
+    /// Lines are wrapped at word boundaries. Words that are longer than a line are broken up.
+    Word,
+
+    /// Lines are broken at any character, words are not moved to the next line as a whole.
+    ///
+    /// Lines are filled with as many characters as they fit and the rest of the word continues on
+    /// the next line.
+    BreakAll,
+
+    /// Lines are only broken by line break characters (`\n`).
+    ///
+    /// Lines that are wider than the bounding box overflow it and the overflowing part is
+    /// clipped. Overflowing lines start at the left side of the bounding box, regardless of the
+    /// horizontal alignment.
+    None,
+}
@@ -1,16 +1,304 @@
 //! Misc utilities
 
+use core::{convert::Infallible, marker::PhantomData, ops::Range};
+
+use az::SaturatingAs;
+
 use embedded_graphics::{
-    prelude::Point,
-    text::{renderer::TextRenderer, Baseline},
+    draw_target::DrawTarget,
+    prelude::{Dimensions, PixelColor, Point, Size},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Baseline, DecorationColor,
+    },
+    Pixel,
 };
 
 /// Measure the width of a piece of string.
+///
+/// With the `grapheme` feature, grapheme clusters are measured as wide as their first character.
 pub fn str_width(renderer: &impl TextRenderer, s: &str) -> u32 {
-    renderer
-        .measure_string(s, Point::zero(), Baseline::Top)
-        .next_position
-        .x as u32
+    visible_runs(s)
+        .map(|run| {
+            renderer
+                .measure_string(run, Point::zero(), Baseline::Top)
+                .next_position
+                .x as u32
+        })
+        .sum()
+}
+
+/// Returns the characters of a string with their byte offsets.
+///
+/// With the `grapheme` feature, grapheme clusters are returned instead of characters.
+#[cfg(not(feature = "grapheme"))]
+pub fn clusters(s: &str) -> impl Iterator<Item = (usize, &str)> + '_ {
+    s.char_indices()
+        .map(move |(idx, c)| (idx, &s[idx..idx + c.len_utf8()]))
+}
+
+/// Returns the characters of a string with their byte offsets.
+///
+/// With the `grapheme` feature, grapheme clusters are returned instead of characters.
+#[cfg(feature = "grapheme")]
+pub fn clusters(s: &str) -> impl Iterator<Item = (usize, &str)> + '_ {
+    unicode_segmentation::UnicodeSegmentation::grapheme_indices(s, true)
+}
+
+/// Returns the part of a grapheme cluster that is drawn, its first character.
+pub fn cluster_base(cluster: &str) -> &str {
+    let len = cluster.chars().next().map_or(0, char::len_utf8);
+    &cluster[..len]
+}
+
+/// Returns the pieces of a string that are drawn.
+///
+/// Without the `grapheme` feature, this is the whole string.
+#[cfg(not(feature = "grapheme"))]
+pub fn visible_runs(s: &str) -> impl Iterator<Item = &str> {
+    core::iter::once(s)
+}
+
+/// Returns the pieces of a string that are drawn.
+///
+/// Runs of single character grapheme clusters are returned as they are, but only the first
+/// character of longer clusters is drawn.
+#[cfg(feature = "grapheme")]
+pub fn visible_runs(s: &str) -> impl Iterator<Item = &str> {
+    VisibleRuns { rest: s }
+}
+
+#[cfg(feature = "grapheme")]
+struct VisibleRuns<'a> {
+    rest: &'a str,
+}
+
+#[cfg(feature = "grapheme")]
+impl<'a> Iterator for VisibleRuns<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest;
+        if rest.is_empty() {
+            return None;
+        }
+
+        for (idx, cluster) in clusters(rest) {
+            let base = cluster_base(cluster);
+            if base.len() < cluster.len() {
+                // Return the run of single characters before the cluster first.
+                return Some(if idx > 0 {
+                    self.rest = &rest[idx..];
+                    &rest[..idx]
+                } else {
+                    self.rest = &rest[cluster.len()..];
+                    base
+                });
+            }
+        }
+
+        self.rest = "";
+        Some(rest)
+    }
+}
+
+/// Returns the distance of a baseline from the top of a line.
+pub fn baseline_offset(renderer: &impl TextRenderer, baseline: Baseline) -> i32 {
+    -renderer
+        .measure_string(" ", Point::zero(), baseline)
+        .bounding_box
+        .top_left
+        .y
+}
+
+/// Draw target that records the color of the first drawn pixel.
+struct ColorProbe<C> {
+    color: Option<C>,
+}
+
+impl<C> Dimensions for ColorProbe<C> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(u32::MAX, u32::MAX))
+    }
+}
+
+impl<C: PixelColor> DrawTarget for ColorProbe<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        if self.color.is_none() {
+            self.color = pixels.into_iter().next().map(|Pixel(_, color)| color);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the background color of a text renderer, or `None` if the background is transparent.
+pub fn background_color<T: TextRenderer>(renderer: &T) -> Option<T::Color> {
+    let mut probe = ColorProbe { color: None };
+    let _ = renderer.draw_whitespace(1, Point::zero(), Baseline::Top, &mut probe);
+
+    probe.color
+}
+
+/// Returns the text color of a text renderer, or `None` if the text is transparent.
+///
+/// The color is found by drawing an underline in the text color.
+pub fn text_color<T>(renderer: &T) -> Option<<T as TextRenderer>::Color>
+where
+    T: TextRenderer + CharacterStyle<Color = <T as TextRenderer>::Color>,
+{
+    let mut renderer = renderer.clone();
+    renderer.set_background_color(None);
+    renderer.set_strikethrough_color(DecorationColor::None);
+    renderer.set_underline_color(DecorationColor::TextColor);
+
+    let mut probe = ColorProbe { color: None };
+    let _ = renderer.draw_whitespace(1, Point::zero(), Baseline::Top, &mut probe);
+
+    probe.color
+}
+
+/// Returns the underline color of a text renderer, or `None` if the text is not underlined.
+pub fn underline_color<T>(renderer: &T) -> Option<<T as TextRenderer>::Color>
+where
+    T: TextRenderer + CharacterStyle<Color = <T as TextRenderer>::Color>,
+{
+    let mut renderer = renderer.clone();
+    renderer.set_background_color(None);
+    renderer.set_strikethrough_color(DecorationColor::None);
+
+    let mut probe = ColorProbe { color: None };
+    let _ = renderer.draw_whitespace(1, Point::zero(), Baseline::Top, &mut probe);
+
+    probe.color
+}
+
+/// Returns the strikethrough color of a text renderer, or `None` if the text is not struck through.
+pub fn strikethrough_color<T>(renderer: &T) -> Option<<T as TextRenderer>::Color>
+where
+    T: TextRenderer + CharacterStyle<Color = <T as TextRenderer>::Color>,
+{
+    let mut renderer = renderer.clone();
+    renderer.set_background_color(None);
+    renderer.set_underline_color(DecorationColor::None);
+
+    let mut probe = ColorProbe { color: None };
+    let _ = renderer.draw_whitespace(1, Point::zero(), Baseline::Top, &mut probe);
+
+    probe.color
+}
+
+/// Draw target that records the horizontal extent of the pixels drawn to a range of rows.
+struct InkProbe<C> {
+    rows: Range<i32>,
+    extent: Option<(i32, i32)>,
+    color: PhantomData<C>,
+}
+
+impl<C> Dimensions for InkProbe<C> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(u32::MAX, u32::MAX))
+    }
+}
+
+impl<C: PixelColor> DrawTarget for InkProbe<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, _) in pixels {
+            if self.rows.contains(&p.y) {
+                self.extent = Some(match self.extent {
+                    Some((left, right)) => (left.min(p.x), right.max(p.x)),
+                    None => (p.x, p.x),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the leftmost and rightmost columns of the glyph pixels of `st` that are in the given
+/// rows, or `None` if there are none.
+///
+/// The text is drawn at the origin, using [`Baseline::Top`], without background and decorations.
+pub fn ink_extent<T>(renderer: &T, st: &str, rows: Range<i32>) -> Option<(i32, i32)>
+where
+    T: TextRenderer + CharacterStyle<Color = <T as TextRenderer>::Color>,
+{
+    let mut renderer = renderer.clone();
+    renderer.set_background_color(None);
+    renderer.set_underline_color(DecorationColor::None);
+    renderer.set_strikethrough_color(DecorationColor::None);
+
+    let mut probe = InkProbe {
+        rows,
+        extent: None,
+        color: PhantomData,
+    };
+    let mut pos = Point::zero();
+    for run in visible_runs(st) {
+        pos = renderer
+            .draw_string(run, pos, Baseline::Top, &mut probe)
+            .unwrap_or(pos);
+    }
+
+    probe.extent
+}
+
+/// Returns the byte offset of `st` if it is a slice of `text`.
+pub fn offset_in(text: &str, st: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let ptr = st.as_ptr() as usize;
+
+    if !st.is_empty() && start <= ptr && ptr + st.len() <= start + text.len() {
+        Some(ptr - start)
+    } else {
+        None
+    }
+}
+
+/// Measure the width of a piece of string, with extra space between the characters.
+///
+/// The space between two characters is never negative.
+pub fn str_width_with_spacing(renderer: &impl TextRenderer, s: &str, letter_spacing: i32) -> u32 {
+    if letter_spacing == 0 {
+        return str_width(renderer, s);
+    }
+
+    if letter_spacing > 0 {
+        // Positive spacing doesn't depend on the width of the characters, so the string can be
+        // measured at once.
+        let gaps = clusters(s).count().saturating_sub(1) as u32;
+        return str_width(renderer, s).saturating_add(gaps.saturating_mul(letter_spacing as u32));
+    }
+
+    let mut width = 0;
+    let mut chars = clusters(s).peekable();
+    while let Some((_, c)) = chars.next() {
+        let char_width = str_width(renderer, c);
+        width += if chars.peek().is_some() {
+            char_width
+                .saturating_as::<i32>()
+                .saturating_add(letter_spacing)
+                .max(0) as u32
+        } else {
+            char_width
+        };
+    }
+
+    width
 }
 
 #[cfg(test)]
@@ -21,15 +309,47 @@ pub mod test {
         prelude::Size,
     };
 
-    use super::str_width;
+    use embedded_graphics::text::Baseline;
+
+    use super::{baseline_offset, str_width, str_width_with_spacing};
 
     pub fn size_for(font: &MonoFont, chars: u32, lines: u32) -> Size {
         font.character_size.x_axis() * chars + font.character_size.y_axis() * lines
     }
 
+    #[test]
+    fn baseline_offsets() {
+        let renderer = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        assert_eq!(baseline_offset(&renderer, Baseline::Top), 0);
+        assert_eq!(baseline_offset(&renderer, Baseline::Middle), 4);
+        assert_eq!(baseline_offset(&renderer, Baseline::Alphabetic), 6);
+        assert_eq!(baseline_offset(&renderer, Baseline::Bottom), 8);
+    }
+
     #[test]
     fn width_of_nbsp_is_single_space() {
         let renderer = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
         assert_eq!(str_width(&renderer, " "), str_width(&renderer, "\u{a0}"));
     }
+
+    #[test]
+    fn letter_spacing_is_added_between_characters() {
+        let renderer = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        assert_eq!(str_width_with_spacing(&renderer, "abc", 2), 22);
+        assert_eq!(str_width_with_spacing(&renderer, "abc", -2), 14);
+        assert_eq!(str_width_with_spacing(&renderer, "abc", -7), 6);
+        assert_eq!(str_width_with_spacing(&renderer, "", 2), 0);
+        assert_eq!(str_width_with_spacing(&renderer, "abc", i32::MAX), u32::MAX);
+        assert_eq!(str_width_with_spacing(&renderer, "abc", i32::MIN), 6);
+    }
+
+    #[test]
+    #[cfg(feature = "grapheme")]
+    fn grapheme_clusters_are_as_wide_as_their_base() {
+        let renderer = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        assert_eq!(str_width(&renderer, "e\u{301}"), 6);
+        assert_eq!(str_width(&renderer, "ae\u{301}b"), 18);
+        assert_eq!(str_width_with_spacing(&renderer, "ae\u{301}b", 1), 20);
+    }
 }